@@ -0,0 +1,4 @@
+-- Optional JSON Schema (draft 2020-12) that responses must conform to.
+-- A scenario's schema takes precedence over its API's.
+ALTER TABLE apis      ADD COLUMN response_schema JSONB;
+ALTER TABLE scenarios ADD COLUMN response_schema JSONB;
@@ -0,0 +1,2 @@
+-- Response headers captured for each test execution: { name: [values] }
+ALTER TABLE responses ADD COLUMN response_headers JSONB;
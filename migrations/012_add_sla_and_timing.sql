@@ -0,0 +1,5 @@
+-- Default response time limit for every scenario of an API
+ALTER TABLE apis ADD COLUMN sla_ms INTEGER CHECK (sla_ms > 0);
+
+-- Per-phase request timing: { dns_ms, connect_ms, ttfb_ms, download_ms, total_ms }
+ALTER TABLE responses ADD COLUMN timing JSONB;
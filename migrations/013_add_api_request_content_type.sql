@@ -0,0 +1,2 @@
+-- Default request body encoding for an API (NULL = application/json)
+ALTER TABLE apis ADD COLUMN request_content_type VARCHAR(100);
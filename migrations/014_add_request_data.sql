@@ -0,0 +1,2 @@
+-- Request sent for each test execution, secrets redacted: { method, url, headers, body }
+ALTER TABLE responses ADD COLUMN request_data JSONB;
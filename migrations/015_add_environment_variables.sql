@@ -0,0 +1,2 @@
+-- Per-environment variables: { name: { value, secret } }, secret values encrypted
+ALTER TABLE environments ADD COLUMN variables JSONB NOT NULL DEFAULT '{}';
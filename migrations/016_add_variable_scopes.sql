@@ -0,0 +1,5 @@
+-- Variables shared by everything below a project, collection or scenario:
+-- { name: value }, overridden by narrower scopes
+ALTER TABLE projects ADD COLUMN variables JSONB NOT NULL DEFAULT '{}';
+ALTER TABLE collections ADD COLUMN variables JSONB NOT NULL DEFAULT '{}';
+ALTER TABLE scenarios ADD COLUMN variables JSONB NOT NULL DEFAULT '{}';
@@ -0,0 +1,3 @@
+-- Seed of the generated placeholder values of the run that produced a report,
+-- so the run can be reproduced. NULL for reports not created by a run.
+ALTER TABLE reports ADD COLUMN seed BIGINT;
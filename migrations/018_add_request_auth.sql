@@ -0,0 +1,5 @@
+-- Authentication strategy applied to test requests, as { "type": ..., ... }.
+-- Secrets are sealed like secret environment variables. NULL on an API
+-- inherits the environment's strategy.
+ALTER TABLE environments ADD COLUMN auth JSONB;
+ALTER TABLE apis ADD COLUMN auth JSONB;
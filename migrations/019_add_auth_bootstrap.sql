@@ -0,0 +1,4 @@
+-- Scenario run at the start of every run against the environment, whose
+-- response provides the token for later requests, as
+-- { "scenario_id": ..., "token_path": ..., "header": ..., "prefix": ... }
+ALTER TABLE environments ADD COLUMN auth_bootstrap JSONB;
@@ -0,0 +1,3 @@
+-- TLS, proxy, DNS override and HTTP version settings for test requests.
+-- The client key and proxy password are sealed like secret variables.
+ALTER TABLE environments ADD COLUMN transport JSONB;
@@ -0,0 +1,5 @@
+-- Whether redirects are followed for an API's requests: { "follow": ..., "max_hops": ... }
+ALTER TABLE apis ADD COLUMN redirect_policy JSONB;
+
+-- Redirects followed before the final response: [{ status, url, location }]
+ALTER TABLE responses ADD COLUMN redirects JSONB;
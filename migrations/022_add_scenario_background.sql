@@ -0,0 +1,2 @@
+-- Steps of the feature's Background, run before the scenario's own steps
+ALTER TABLE scenarios ADD COLUMN background_steps JSONB NOT NULL DEFAULT '[]';
@@ -0,0 +1,6 @@
+-- 'single' scenarios call their own API; 'workflow' scenarios call several
+-- APIs of the project in turn, each from a `call "<API>"` step
+ALTER TABLE scenarios ADD COLUMN kind VARCHAR(20) NOT NULL DEFAULT 'single';
+
+-- Result of each call of a workflow scenario: [{ api_id, pass, ... }]
+ALTER TABLE responses ADD COLUMN workflow_steps JSONB;
@@ -0,0 +1,2 @@
+-- How expected bodies are compared for a scenario's examples: { "mode": ..., "ignore_paths": [...] }
+ALTER TABLE scenarios ADD COLUMN body_match JSONB;
@@ -13,6 +13,9 @@ pub struct Config {
     pub jwt_expiration_hours: i64,
     pub refresh_token_expiration_days: i64,
 
+    // Encryption key for environment secrets
+    pub secrets_key: String,
+
     // Server
     pub host: String,
     pub port: u16,
@@ -44,6 +47,11 @@ impl Config {
                 .parse()
                 .map_err(|_| ConfigError::Invalid("REFRESH_TOKEN_EXPIRATION_DAYS"))?,
 
+            // Secrets: kept apart from the JWT secret so that one can rotate
+            // without making stored secrets unreadable
+            secrets_key: env::var("SECRETS_KEY")
+                .map_err(|_| ConfigError::Missing("SECRETS_KEY"))?,
+
             // Server
             host: env::var("HOST").unwrap_or_else(|_| "0.0.0.0".to_string()),
             port: env::var("PORT")
@@ -12,6 +12,14 @@ pub struct Model {
     pub endpoint: String,
     pub severity: i16,
     pub description: Option<String>,
+    #[sea_orm(column_type = "Json", nullable)]
+    pub response_schema: Option<Json>,
+    pub sla_ms: Option<i32>,
+    pub request_content_type: Option<String>,
+    #[sea_orm(column_type = "Json", nullable)]
+    pub auth: Option<Json>,
+    #[sea_orm(column_type = "Json", nullable)]
+    pub redirect_policy: Option<Json>,
     pub created_at: TimeDateTimeWithTimeZone,
     pub updated_at: TimeDateTimeWithTimeZone,
 }
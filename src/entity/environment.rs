@@ -9,6 +9,14 @@ pub struct Model {
     pub project_id: Uuid,
     pub title: String,
     pub domain_name: String,
+    #[sea_orm(column_type = "Json")]
+    pub variables: Json,
+    #[sea_orm(column_type = "Json", nullable)]
+    pub auth: Option<Json>,
+    #[sea_orm(column_type = "Json", nullable)]
+    pub auth_bootstrap: Option<Json>,
+    #[sea_orm(column_type = "Json", nullable)]
+    pub transport: Option<Json>,
     pub created_at: TimeDateTimeWithTimeZone,
     pub updated_at: TimeDateTimeWithTimeZone,
 }
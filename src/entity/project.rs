@@ -9,6 +9,8 @@ pub struct Model {
     pub user_id: Uuid,
     pub name: String,
     pub description: Option<String>,
+    #[sea_orm(column_type = "Json")]
+    pub variables: Json,
     pub created_at: TimeDateTimeWithTimeZone,
     pub updated_at: TimeDateTimeWithTimeZone,
 }
@@ -17,6 +17,7 @@ pub struct Model {
     #[sea_orm(column_type = "Decimal(Some((5, 2)))")]
     pub pass_rate: Option<Decimal>,
     pub response_count: i32,
+    pub seed: Option<i64>,
     pub created_at: TimeDateTimeWithTimeZone,
     pub finished_at: Option<TimeDateTimeWithTimeZone>,
 }
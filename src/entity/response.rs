@@ -11,12 +11,22 @@ pub struct Model {
     pub scenario_id: Uuid,
     pub example_index: i32,
     #[sea_orm(column_type = "Json", nullable)]
+    pub request_data: Option<Json>,
+    #[sea_orm(column_type = "Json", nullable)]
     pub response_data: Option<Json>,
+    #[sea_orm(column_type = "Json", nullable)]
+    pub response_headers: Option<Json>,
     pub response_status: i16,
     pub pass: bool,
     pub error_message: Option<String>,
     pub request_time: TimeDateTimeWithTimeZone,
     pub request_duration_ms: Option<i32>,
+    #[sea_orm(column_type = "Json", nullable)]
+    pub timing: Option<Json>,
+    #[sea_orm(column_type = "Json", nullable)]
+    pub redirects: Option<Json>,
+    #[sea_orm(column_type = "Json", nullable)]
+    pub workflow_steps: Option<Json>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -10,10 +10,19 @@ pub struct Model {
     pub title: String,
     pub description: Option<String>,
     pub tags: Vec<String>,
+    pub kind: String,
+    #[sea_orm(column_type = "Json")]
+    pub background_steps: Json,
     #[sea_orm(column_type = "Json")]
     pub steps: Json,
     #[sea_orm(column_type = "Json")]
     pub examples: Json,
+    #[sea_orm(column_type = "Json", nullable)]
+    pub response_schema: Option<Json>,
+    #[sea_orm(column_type = "Json", nullable)]
+    pub body_match: Option<Json>,
+    #[sea_orm(column_type = "Json")]
+    pub variables: Json,
     pub created_at: TimeDateTimeWithTimeZone,
     pub updated_at: TimeDateTimeWithTimeZone,
 }
@@ -7,10 +7,16 @@ use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::error::{AppError, AppResult};
-use crate::handlers::{validate_optional, validate_required, ApiListResponse, PaginationParams};
+use crate::handlers::{
+    double_option, validate_auth, validate_optional, validate_required, validate_response_schema,
+    ApiListResponse, PaginationParams,
+};
 use crate::middlewares::AuthUser;
-use crate::models::{Api, CreateApi, UpdateApi};
+use crate::models::{
+    Api, BodyEncoding, CreateApi, RedirectPolicy, RequestAuth, UpdateApi, MAX_REDIRECT_HOPS,
+};
 use crate::repositories::ApiRepository;
+use crate::services::secrets::MASKED;
 use crate::state::AppState;
 
 // ============ Request/Response DTOs ============
@@ -22,6 +28,18 @@ pub struct CreateApiRequest {
     pub endpoint: String,
     pub severity: Option<i16>,
     pub description: Option<String>,
+    /// JSON Schema (draft 2020-12) every response must conform to
+    pub response_schema: Option<serde_json::Value>,
+    /// Default response time limit in milliseconds
+    pub sla_ms: Option<i32>,
+    /// Request body encoding: `application/json` (default),
+    /// `application/x-www-form-urlencoded` or `multipart/form-data`
+    pub request_content_type: Option<String>,
+    /// Authentication overriding the environment's; `{"type": "none"}` sends
+    /// no credentials
+    pub auth: Option<RequestAuth>,
+    /// Whether redirects are followed (default: followed, up to 10 hops)
+    pub redirect_policy: Option<RedirectPolicy>,
 }
 
 #[derive(Debug, Deserialize, ToSchema)]
@@ -31,6 +49,23 @@ pub struct UpdateApiRequest {
     pub endpoint: Option<String>,
     pub severity: Option<i16>,
     pub description: Option<String>,
+    /// JSON Schema (draft 2020-12) every response must conform to
+    pub response_schema: Option<serde_json::Value>,
+    /// Default response time limit in milliseconds
+    pub sla_ms: Option<i32>,
+    /// Request body encoding: `application/json` (default),
+    /// `application/x-www-form-urlencoded` or `multipart/form-data`
+    pub request_content_type: Option<String>,
+    /// Replaces the auth; `null` inherits the environment's again. A secret
+    /// sent back as `********` keeps its value while the other auth fields are
+    /// unchanged.
+    #[serde(default, deserialize_with = "double_option")]
+    #[schema(value_type = Option<RequestAuth>)]
+    pub auth: Option<Option<RequestAuth>>,
+    /// Replaces the redirect policy; `null` restores the default
+    #[serde(default, deserialize_with = "double_option")]
+    #[schema(value_type = Option<RedirectPolicy>)]
+    pub redirect_policy: Option<Option<RedirectPolicy>>,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -42,6 +77,12 @@ pub struct ApiResponse {
     pub endpoint: String,
     pub severity: i16,
     pub description: Option<String>,
+    pub response_schema: Option<serde_json::Value>,
+    pub sla_ms: Option<i32>,
+    pub request_content_type: Option<String>,
+    /// Auth overriding the environment's, with its secret shown as `********`
+    pub auth: Option<RequestAuth>,
+    pub redirect_policy: Option<RedirectPolicy>,
     #[schema(value_type = String)]
     pub created_at: time::OffsetDateTime,
     #[schema(value_type = String)]
@@ -58,6 +99,11 @@ impl From<Api> for ApiResponse {
             endpoint: a.endpoint,
             severity: a.severity,
             description: a.description,
+            response_schema: a.response_schema,
+            sla_ms: a.sla_ms,
+            request_content_type: a.request_content_type,
+            auth: a.auth.map(|auth| auth.masked(MASKED)),
+            redirect_policy: a.redirect_policy,
             created_at: a.created_at,
             updated_at: a.updated_at,
         }
@@ -95,6 +141,10 @@ pub async fn create_api(
     validate_required(&payload.endpoint, "Endpoint", 2048)?;
     validate_optional(&payload.description, "Description", 1000)?;
     validate_http_method(&payload.http_method)?;
+    validate_response_schema(payload.response_schema.as_ref())?;
+    validate_sla(payload.sla_ms)?;
+    validate_auth(payload.auth.as_ref())?;
+    validate_redirect_policy(payload.redirect_policy.as_ref())?;
 
     let create_api = CreateApi {
         name: payload.name,
@@ -102,9 +152,21 @@ pub async fn create_api(
         endpoint: payload.endpoint,
         severity: payload.severity,
         description: payload.description,
+        response_schema: payload.response_schema,
+        sla_ms: payload.sla_ms,
+        request_content_type: normalize_content_type(payload.request_content_type)?,
+        auth: payload.auth,
+        redirect_policy: payload.redirect_policy,
     };
 
-    let api = ApiRepository::create(&state.db, collection_id, user.id, &create_api).await?;
+    let api = ApiRepository::create(
+        &state.db,
+        &state.secret_cipher(),
+        collection_id,
+        user.id,
+        &create_api,
+    )
+    .await?;
     Ok(Json(api.into()))
 }
 
@@ -157,7 +219,8 @@ pub async fn list_apis(
     responses(
         (status = 200, description = "API details", body = ApiResponse),
         (status = 401, description = "Unauthorized"),
-        (status = 404, description = "API not found")
+        (status = 404, description = "API not found"),
+        (status = 409, description = "A scenario of the API is an environment's auth bootstrap")
     ),
     security(
         ("bearer_auth" = [])
@@ -203,6 +266,10 @@ pub async fn update_api(
     if let Some(ref method) = payload.http_method {
         validate_http_method(method)?;
     }
+    validate_response_schema(payload.response_schema.as_ref())?;
+    validate_sla(payload.sla_ms)?;
+    validate_auth(payload.auth.as_ref().and_then(Option::as_ref))?;
+    validate_redirect_policy(payload.redirect_policy.as_ref().and_then(Option::as_ref))?;
 
     let update_api = UpdateApi {
         name: payload.name,
@@ -210,9 +277,15 @@ pub async fn update_api(
         endpoint: payload.endpoint,
         severity: payload.severity,
         description: payload.description,
+        response_schema: payload.response_schema,
+        sla_ms: payload.sla_ms,
+        request_content_type: normalize_content_type(payload.request_content_type)?,
+        auth: payload.auth,
+        redirect_policy: payload.redirect_policy,
     };
 
-    let api = ApiRepository::update(&state.db, id, user.id, &update_api).await?;
+    let api =
+        ApiRepository::update(&state.db, &state.secret_cipher(), id, user.id, &update_api).await?;
     Ok(Json(api.into()))
 }
 
@@ -254,3 +327,38 @@ fn validate_http_method(method: &str) -> AppResult<()> {
     }
     Ok(())
 }
+
+fn validate_sla(sla_ms: Option<i32>) -> AppResult<()> {
+    if sla_ms.is_some_and(|ms| ms <= 0) {
+        return Err(AppError::Validation(
+            "SLA must be a positive number of milliseconds".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn validate_redirect_policy(policy: Option<&RedirectPolicy>) -> AppResult<()> {
+    if policy.is_some_and(|p| p.max_hops > MAX_REDIRECT_HOPS) {
+        return Err(AppError::Validation(format!(
+            "Redirect policy allows at most {} hops",
+            MAX_REDIRECT_HOPS
+        )));
+    }
+    Ok(())
+}
+
+/// Validate a request content type and store it as its canonical MIME type
+fn normalize_content_type(content_type: Option<String>) -> AppResult<Option<String>> {
+    content_type
+        .map(|ct| {
+            BodyEncoding::parse(&ct)
+                .map(|encoding| encoding.content_type().to_string())
+                .ok_or_else(|| {
+                    AppError::Validation(format!(
+                        "Invalid request content type '{}'. Must be one of: application/json, application/x-www-form-urlencoded, multipart/form-data",
+                        ct
+                    ))
+                })
+        })
+        .transpose()
+}
@@ -3,12 +3,14 @@ use axum::{
     Json,
 };
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::error::AppResult;
 use crate::handlers::{
-    validate_optional, validate_required, CollectionListResponse, PaginationParams,
+    validate_optional, validate_required, validate_variables, CollectionListResponse,
+    PaginationParams,
 };
 use crate::middlewares::AuthUser;
 use crate::models::{Collection, CreateCollection, UpdateCollection};
@@ -21,12 +23,17 @@ use crate::state::AppState;
 pub struct CreateCollectionRequest {
     pub name: String,
     pub description: Option<String>,
+    /// Variables overriding project variables for this collection's scenarios
+    #[serde(default)]
+    pub variables: BTreeMap<String, String>,
 }
 
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct UpdateCollectionRequest {
     pub name: Option<String>,
     pub description: Option<String>,
+    /// Replaces all variables
+    pub variables: Option<BTreeMap<String, String>>,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -35,6 +42,7 @@ pub struct CollectionResponse {
     pub project_id: Uuid,
     pub name: String,
     pub description: Option<String>,
+    pub variables: BTreeMap<String, String>,
     #[schema(value_type = String)]
     pub created_at: time::OffsetDateTime,
     #[schema(value_type = String)]
@@ -48,6 +56,7 @@ impl From<Collection> for CollectionResponse {
             project_id: c.project_id,
             name: c.name,
             description: c.description,
+            variables: c.variables,
             created_at: c.created_at,
             updated_at: c.updated_at,
         }
@@ -83,10 +92,12 @@ pub async fn create_collection(
 ) -> AppResult<Json<CollectionResponse>> {
     validate_required(&payload.name, "Name", 100)?;
     validate_optional(&payload.description, "Description", 1000)?;
+    validate_variables(Some(&payload.variables))?;
 
     let create_collection = CreateCollection {
         name: payload.name,
         description: payload.description,
+        variables: payload.variables,
     };
 
     let collection =
@@ -186,10 +197,12 @@ pub async fn update_collection(
 ) -> AppResult<Json<CollectionResponse>> {
     validate_optional(&payload.name, "Name", 100)?;
     validate_optional(&payload.description, "Description", 1000)?;
+    validate_variables(payload.variables.as_ref())?;
 
     let update_collection = UpdateCollection {
         name: payload.name,
         description: payload.description,
+        variables: payload.variables,
     };
 
     let collection =
@@ -207,7 +220,8 @@ pub async fn update_collection(
     responses(
         (status = 204, description = "Collection deleted successfully"),
         (status = 401, description = "Unauthorized"),
-        (status = 404, description = "Collection not found")
+        (status = 404, description = "Collection not found"),
+        (status = 409, description = "A scenario of the collection is an environment's auth bootstrap")
     ),
     security(
         ("bearer_auth" = [])
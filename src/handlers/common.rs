@@ -1,7 +1,11 @@
-use serde::{Deserialize, Serialize};
+use reqwest::header::HeaderName;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
 use utoipa::{IntoParams, ToSchema};
 
 use crate::error::{AppError, AppResult};
+use crate::models::{ApiKeyLocation, RequestAuth};
+use crate::services::{random_seed, ResponseSchema, MAX_SEED};
 
 /// Generic paginated list response used by all list endpoints
 #[derive(Debug, Serialize, ToSchema)]
@@ -18,6 +22,7 @@ pub type EnvironmentListResponse = ListResponse<super::EnvironmentResponse>;
 pub type ApiListResponse = ListResponse<super::ApiResponse>;
 pub type ScenarioListResponse = ListResponse<super::ScenarioResponse>;
 pub type ReportListResponse = ListResponse<super::ReportResponse>;
+pub type FixtureListResponse = ListResponse<super::FixtureResponse>;
 pub type JobListResponse = ListResponse<super::JobStatusResponse>;
 
 #[derive(Debug, Deserialize, IntoParams)]
@@ -52,3 +57,131 @@ pub fn validate_optional(value: &Option<String>, field: &str, max_len: usize) ->
     }
     Ok(())
 }
+
+/// Validate that an optional response schema is a valid JSON Schema document.
+pub fn validate_response_schema(schema: Option<&serde_json::Value>) -> AppResult<()> {
+    if let Some(schema) = schema {
+        ResponseSchema::compile(schema).map_err(AppError::Validation)?;
+    }
+    Ok(())
+}
+
+/// Maximum number of variables in one scope
+pub const MAX_VARIABLES: usize = 100;
+
+/// Maximum length of a variable value
+const MAX_VARIABLE_VALUE_LEN: usize = 4096;
+
+/// Validate a variable name and value. Names are used as `<name>` and `{name}`.
+pub fn validate_variable(name: &str, value: &str) -> AppResult<()> {
+    validate_required(name, "Variable name", 100)?;
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+    {
+        return Err(AppError::Validation(format!(
+            "Variable name {name} may only contain letters, digits, '_', '-' and '.'"
+        )));
+    }
+    if value.len() > MAX_VARIABLE_VALUE_LEN {
+        return Err(AppError::Validation(format!(
+            "Variable {name} must be at most {MAX_VARIABLE_VALUE_LEN} characters"
+        )));
+    }
+    Ok(())
+}
+
+/// Validate a project, collection or scenario variable map (if present).
+pub fn validate_variables(variables: Option<&BTreeMap<String, String>>) -> AppResult<()> {
+    let Some(variables) = variables else {
+        return Ok(());
+    };
+    if variables.len() > MAX_VARIABLES {
+        return Err(AppError::Validation(format!(
+            "At most {MAX_VARIABLES} variables are allowed"
+        )));
+    }
+    variables
+        .iter()
+        .try_for_each(|(name, value)| validate_variable(name, value))
+}
+
+/// Deserialize a field that may be absent (`None`), `null` (`Some(None)`) or
+/// set (`Some(Some(value))`). Use with `#[serde(default)]`.
+pub fn double_option<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+/// Validate an auth strategy's required fields, header names and token URL.
+pub fn validate_auth(auth: Option<&RequestAuth>) -> AppResult<()> {
+    let header = |name: &str, field: &str| {
+        validate_required(name, field, 100)?;
+        HeaderName::from_bytes(name.as_bytes())
+            .map(|_| ())
+            .map_err(|_| AppError::Validation(format!("{field} is not a valid header name")))
+    };
+
+    match auth {
+        None | Some(RequestAuth::None) => Ok(()),
+        Some(RequestAuth::Basic { username, password }) => {
+            validate_required(username, "Username", 256)?;
+            validate_required(password, "Password", 4096)
+        }
+        Some(RequestAuth::ApiKey {
+            name,
+            value,
+            location,
+        }) => {
+            match location {
+                ApiKeyLocation::Header => header(name, "API key name")?,
+                ApiKeyLocation::Query => validate_required(name, "API key name", 100)?,
+            }
+            validate_required(value, "API key", 4096)
+        }
+        Some(RequestAuth::OAuth2ClientCredentials {
+            token_url,
+            client_id,
+            client_secret,
+            scope,
+            ..
+        }) => {
+            validate_required(token_url, "Token URL", 2048)?;
+            let url = reqwest::Url::parse(token_url)
+                .map_err(|_| AppError::Validation("Token URL is not a valid URL".to_string()))?;
+            if !matches!(url.scheme(), "http" | "https") {
+                return Err(AppError::Validation(
+                    "Token URL must use http or https".to_string(),
+                ));
+            }
+            validate_required(client_id, "Client ID", 256)?;
+            validate_required(client_secret, "Client secret", 4096)?;
+            validate_optional(scope, "Scope", 1000)
+        }
+        Some(RequestAuth::Hmac {
+            secret,
+            key_id,
+            signature_header,
+            timestamp_header,
+        }) => {
+            validate_required(secret, "HMAC secret", 4096)?;
+            validate_optional(key_id, "Key ID", 256)?;
+            header(signature_header, "Signature header")?;
+            header(timestamp_header, "Timestamp header")
+        }
+    }
+}
+
+/// Seed for a test run: the requested one, else a random one.
+pub fn run_seed(seed: Option<u64>) -> AppResult<u64> {
+    match seed {
+        Some(seed) if seed > MAX_SEED => Err(AppError::Validation(format!(
+            "Seed must be at most {MAX_SEED}"
+        ))),
+        Some(seed) => Ok(seed),
+        None => Ok(random_seed()),
+    }
+}
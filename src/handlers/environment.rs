@@ -2,31 +2,89 @@ use axum::{
     extract::{Path, Query, State},
     Json,
 };
+use jsonpath_rust::JsonPath;
+use reqwest::header::{HeaderName, HeaderValue};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use utoipa::ToSchema;
 use uuid::Uuid;
 
-use crate::error::AppResult;
+use crate::error::{AppError, AppResult};
+use crate::handlers::common::MAX_VARIABLES;
 use crate::handlers::{
-    validate_optional, validate_required, EnvironmentListResponse, PaginationParams,
+    double_option, validate_auth, validate_optional, validate_required, validate_variable,
+    EnvironmentListResponse, PaginationParams,
 };
 use crate::middlewares::AuthUser;
-use crate::models::{CreateEnvironment, Environment, UpdateEnvironment};
-use crate::repositories::EnvironmentRepository;
+use crate::models::{
+    AuthBootstrap, CreateEnvironment, Environment, EnvironmentVariable, RequestAuth, ScenarioKind,
+    TransportSettings, UpdateEnvironment,
+};
+use crate::repositories::{
+    ApiRepository, CollectionRepository, EnvironmentRepository, ScenarioRepository,
+};
+use crate::services::secrets::MASKED;
+use crate::services::timing::TimingRecorder;
+use crate::services::transport;
+use crate::services::Hook;
 use crate::state::AppState;
 
+/// Most host-to-IP overrides an environment can have
+const MAX_DNS_OVERRIDES: usize = 100;
+
 // ============ Request/Response DTOs ============
 
+/// An environment variable, referenced from steps and endpoints as `<name>`
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct EnvironmentVariableRequest {
+    pub value: String,
+    /// Encrypt the value at rest and mask it in responses and reports
+    #[serde(default)]
+    pub secret: bool,
+}
+
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct CreateEnvironmentRequest {
     pub title: String,
     pub domain_name: String,
+    #[serde(default)]
+    pub variables: BTreeMap<String, EnvironmentVariableRequest>,
+    /// Authentication applied to test requests, unless an API overrides it
+    pub auth: Option<RequestAuth>,
+    /// Scenario run at the start of every run, whose token replaces `auth`
+    pub auth_bootstrap: Option<AuthBootstrap>,
+    /// TLS, proxy, DNS override and HTTP version settings for test requests
+    pub transport: Option<TransportSettings>,
 }
 
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct UpdateEnvironmentRequest {
     pub title: Option<String>,
     pub domain_name: Option<String>,
+    /// Replaces all variables. A secret sent back as `********` keeps its value.
+    pub variables: Option<BTreeMap<String, EnvironmentVariableRequest>>,
+    /// Replaces the auth; `null` removes it. A secret sent back as `********`
+    /// keeps its value while the other auth fields are unchanged.
+    #[serde(default, deserialize_with = "double_option")]
+    #[schema(value_type = Option<RequestAuth>)]
+    pub auth: Option<Option<RequestAuth>>,
+    /// Replaces the auth bootstrap; `null` removes it
+    #[serde(default, deserialize_with = "double_option")]
+    #[schema(value_type = Option<AuthBootstrap>)]
+    pub auth_bootstrap: Option<Option<AuthBootstrap>>,
+    /// Replaces the transport settings; `null` removes them. A client key or
+    /// proxy password sent back as `********` keeps its value while the client
+    /// certificate or proxy URL is unchanged.
+    #[serde(default, deserialize_with = "double_option")]
+    #[schema(value_type = Option<TransportSettings>)]
+    pub transport: Option<Option<TransportSettings>>,
+}
+
+/// An environment variable; secret values are shown as `********`
+#[derive(Debug, Serialize, ToSchema)]
+pub struct EnvironmentVariableResponse {
+    pub value: String,
+    pub secret: bool,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -35,6 +93,12 @@ pub struct EnvironmentResponse {
     pub project_id: Uuid,
     pub title: String,
     pub domain_name: String,
+    pub variables: BTreeMap<String, EnvironmentVariableResponse>,
+    /// Auth with its secret shown as `********`
+    pub auth: Option<RequestAuth>,
+    pub auth_bootstrap: Option<AuthBootstrap>,
+    /// Transport settings with the client key and proxy password shown as `********`
+    pub transport: Option<TransportSettings>,
     #[schema(value_type = String)]
     pub created_at: time::OffsetDateTime,
     #[schema(value_type = String)]
@@ -48,12 +112,156 @@ impl From<Environment> for EnvironmentResponse {
             project_id: e.project_id,
             title: e.title,
             domain_name: e.domain_name,
+            variables: e
+                .variables
+                .into_iter()
+                .map(|(name, variable)| {
+                    let value = if variable.secret {
+                        MASKED.to_string()
+                    } else {
+                        variable.value
+                    };
+                    let response = EnvironmentVariableResponse {
+                        value,
+                        secret: variable.secret,
+                    };
+                    (name, response)
+                })
+                .collect(),
+            auth: e.auth.map(|auth| auth.masked(MASKED)),
+            auth_bootstrap: e.auth_bootstrap,
+            transport: e.transport.map(|transport| transport.masked(MASKED)),
             created_at: e.created_at,
             updated_at: e.updated_at,
         }
     }
 }
 
+/// Validate variable names and values and convert them to the model
+fn validate_variables(
+    variables: BTreeMap<String, EnvironmentVariableRequest>,
+) -> AppResult<BTreeMap<String, EnvironmentVariable>> {
+    if variables.len() > MAX_VARIABLES {
+        return Err(AppError::Validation(format!(
+            "An environment can have at most {MAX_VARIABLES} variables"
+        )));
+    }
+
+    variables
+        .into_iter()
+        .map(|(name, variable)| {
+            validate_variable(&name, &variable.value)?;
+
+            let variable = EnvironmentVariable {
+                value: variable.value,
+                secret: variable.secret,
+            };
+            Ok((name, variable))
+        })
+        .collect()
+}
+
+/// Validate transport settings by building a client from them. A masked
+/// client key is not checked again.
+fn validate_transport(settings: &TransportSettings) -> AppResult<()> {
+    if settings.dns_overrides.len() > MAX_DNS_OVERRIDES {
+        return Err(AppError::Validation(format!(
+            "An environment can have at most {MAX_DNS_OVERRIDES} DNS overrides"
+        )));
+    }
+    for host in settings.dns_overrides.keys() {
+        validate_required(host, "DNS override host", 253)?;
+        if host.contains([':', '/']) {
+            return Err(AppError::Validation(format!(
+                "DNS override host {} must be a host name without scheme or port",
+                host
+            )));
+        }
+    }
+
+    if let Some(proxy_url) = &settings.proxy_url {
+        validate_required(proxy_url, "Proxy URL", 2048)?;
+        let url = reqwest::Url::parse(proxy_url)
+            .map_err(|_| AppError::Validation("Proxy URL is not a valid URL".to_string()))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(AppError::Validation(
+                "Proxy URL must use http or https".to_string(),
+            ));
+        }
+        if !url.username().is_empty() || url.password().is_some() {
+            return Err(AppError::Validation(
+                "Set proxy credentials in proxy_username and proxy_password".to_string(),
+            ));
+        }
+    }
+    if settings.proxy_password.is_some() && settings.proxy_username.is_none() {
+        return Err(AppError::Validation(
+            "Proxy password requires a proxy username".to_string(),
+        ));
+    }
+
+    let mut unmasked = settings.clone();
+    if unmasked.client_key.as_deref() == Some(MASKED) {
+        unmasked.client_cert = None;
+        unmasked.client_key = None;
+    }
+    transport::configure(
+        reqwest::Client::builder(),
+        &unmasked,
+        &TimingRecorder::default(),
+    )?
+    .build()
+    .map(|_| ())
+    .map_err(|e| AppError::Validation(format!("Invalid transport settings: {}", e)))
+}
+
+/// Validate an auth bootstrap's token path and header, and check that its
+/// scenario belongs to the environment's project
+async fn validate_auth_bootstrap(
+    state: &AppState,
+    user_id: Uuid,
+    project_id: Uuid,
+    bootstrap: &AuthBootstrap,
+) -> AppResult<()> {
+    validate_required(&bootstrap.token_path, "Token path", 256)?;
+    serde_json::Value::Null
+        .query(&bootstrap.token_path)
+        .map_err(|e| AppError::Validation(format!("Token path is not valid JSONPath: {}", e)))?;
+    validate_required(&bootstrap.header, "Token header", 100)?;
+    HeaderName::from_bytes(bootstrap.header.as_bytes())
+        .map_err(|_| AppError::Validation("Token header is not a valid header name".to_string()))?;
+    if bootstrap.prefix.len() > 100 || HeaderValue::from_str(&bootstrap.prefix).is_err() {
+        return Err(AppError::Validation(
+            "Token prefix must be at most 100 visible characters".to_string(),
+        ));
+    }
+
+    // The bootstrap runs the scenario's first example as one request
+    let scenario =
+        ScenarioRepository::find_by_id_and_user(&state.db, bootstrap.scenario_id, user_id).await?;
+    if scenario.kind == ScenarioKind::Workflow || Hook::of(&scenario).is_some() {
+        return Err(AppError::Validation(
+            "Auth bootstrap scenario must be a single-request scenario, not a workflow or hook"
+                .to_string(),
+        ));
+    }
+    if scenario.examples.as_array().is_none_or(Vec::is_empty) {
+        return Err(AppError::Validation(
+            "Auth bootstrap scenario must have an example".to_string(),
+        ));
+    }
+    let api = ApiRepository::find_by_id_and_user(&state.db, scenario.api_id, user_id).await?;
+    let collection =
+        CollectionRepository::find_by_id_and_user(&state.db, api.collection_id, user_id).await?;
+    if collection.project_id != project_id {
+        return Err(AppError::Validation(
+            "Auth bootstrap scenario must belong to the environment's project".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
 // ============ Handlers ============
 
 /// Create a new environment in a project
@@ -83,14 +291,31 @@ pub async fn create_environment(
 ) -> AppResult<Json<EnvironmentResponse>> {
     validate_required(&payload.title, "Title", 100)?;
     validate_required(&payload.domain_name, "Domain name", 2048)?;
+    validate_auth(payload.auth.as_ref())?;
+    if let Some(bootstrap) = &payload.auth_bootstrap {
+        validate_auth_bootstrap(&state, user.id, project_id, bootstrap).await?;
+    }
+    if let Some(transport) = &payload.transport {
+        validate_transport(transport)?;
+    }
 
     let create_env = CreateEnvironment {
         title: payload.title,
         domain_name: payload.domain_name,
+        variables: validate_variables(payload.variables)?,
+        auth: payload.auth,
+        auth_bootstrap: payload.auth_bootstrap,
+        transport: payload.transport,
     };
 
-    let environment =
-        EnvironmentRepository::create(&state.db, project_id, user.id, &create_env).await?;
+    let environment = EnvironmentRepository::create(
+        &state.db,
+        &state.secret_cipher(),
+        project_id,
+        user.id,
+        &create_env,
+    )
+    .await?;
     Ok(Json(environment.into()))
 }
 
@@ -186,13 +411,28 @@ pub async fn update_environment(
 ) -> AppResult<Json<EnvironmentResponse>> {
     validate_optional(&payload.title, "Title", 100)?;
     validate_optional(&payload.domain_name, "Domain name", 2048)?;
+    validate_auth(payload.auth.as_ref().and_then(Option::as_ref))?;
+    if let Some(Some(bootstrap)) = &payload.auth_bootstrap {
+        let environment =
+            EnvironmentRepository::find_by_id_and_user(&state.db, id, user.id).await?;
+        validate_auth_bootstrap(&state, user.id, environment.project_id, bootstrap).await?;
+    }
+    if let Some(Some(transport)) = &payload.transport {
+        validate_transport(transport)?;
+    }
 
     let update_env = UpdateEnvironment {
         title: payload.title,
         domain_name: payload.domain_name,
+        variables: payload.variables.map(validate_variables).transpose()?,
+        auth: payload.auth,
+        auth_bootstrap: payload.auth_bootstrap,
+        transport: payload.transport,
     };
 
-    let environment = EnvironmentRepository::update(&state.db, id, user.id, &update_env).await?;
+    let environment =
+        EnvironmentRepository::update(&state.db, &state.secret_cipher(), id, user.id, &update_env)
+            .await?;
     Ok(Json(environment.into()))
 }
 
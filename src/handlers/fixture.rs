@@ -0,0 +1,186 @@
+use axum::{
+    body::Bytes,
+    extract::{Path, Query, State},
+    http::{header, HeaderMap},
+    Json,
+};
+use serde::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::{AppError, AppResult};
+use crate::handlers::{validate_required, FixtureListResponse, PaginationParams};
+use crate::middlewares::AuthUser;
+use crate::models::Fixture;
+use crate::repositories::{FixtureRepository, OwnershipVerifier};
+use crate::state::AppState;
+
+/// Largest fixture accepted by the upload endpoint (10 MiB)
+pub const MAX_FIXTURE_BYTES: usize = 10 * 1024 * 1024;
+
+// ============ Request/Response DTOs ============
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FixtureResponse {
+    pub name: String,
+    pub content_type: Option<String>,
+    pub size: i64,
+    pub sha256: String,
+    #[schema(value_type = String)]
+    pub uploaded_at: time::OffsetDateTime,
+}
+
+impl From<Fixture> for FixtureResponse {
+    fn from(f: Fixture) -> Self {
+        Self {
+            name: f.name,
+            content_type: f.content_type,
+            size: f.size,
+            sha256: f.sha256,
+            uploaded_at: f.uploaded_at,
+        }
+    }
+}
+
+// ============ Handlers ============
+
+/// Upload a fixture file, replacing any fixture with the same name.
+///
+/// The request body is stored as-is, with the request's `Content-Type`.
+#[utoipa::path(
+    put,
+    path = "/api/projects/{project_id}/fixtures/{name}",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        ("name" = String, Path, description = "Fixture name, referenced from steps")
+    ),
+    request_body(content = Vec<u8>, description = "Fixture file contents", content_type = "application/octet-stream"),
+    responses(
+        (status = 200, description = "Fixture stored", body = FixtureResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Project not found"),
+        (status = 400, description = "Validation error"),
+        (status = 413, description = "Fixture too large")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Fixtures"
+)]
+pub async fn upload_fixture(
+    user: AuthUser,
+    State(state): State<AppState>,
+    Path((project_id, name)): Path<(Uuid, String)>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> AppResult<Json<FixtureResponse>> {
+    validate_fixture_name(&name)?;
+    if body.is_empty() {
+        return Err(AppError::Validation(
+            "Fixture content cannot be empty".to_string(),
+        ));
+    }
+
+    OwnershipVerifier::verify_project(&state.db, project_id, user.id).await?;
+
+    let mongo = state.mongo_db();
+    let content_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+
+    let fixture = FixtureRepository::upload(&mongo, project_id, &name, content_type, &body).await?;
+    Ok(Json(fixture.into()))
+}
+
+/// List the fixtures of a project
+#[utoipa::path(
+    get,
+    path = "/api/projects/{project_id}/fixtures",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        PaginationParams
+    ),
+    responses(
+        (status = 200, description = "List of fixtures", body = FixtureListResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Project not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Fixtures"
+)]
+pub async fn list_fixtures(
+    user: AuthUser,
+    State(state): State<AppState>,
+    Path(project_id): Path<Uuid>,
+    Query(params): Query<PaginationParams>,
+) -> AppResult<Json<FixtureListResponse>> {
+    let limit = params.limit.unwrap_or(20).clamp(1, 100) as u64;
+    let offset = params.offset.unwrap_or(0).max(0) as u64;
+
+    OwnershipVerifier::verify_project(&state.db, project_id, user.id).await?;
+
+    let fixtures = FixtureRepository::list_by_project(&state.mongo_db(), project_id).await?;
+    let total = fixtures.len() as u64;
+
+    Ok(Json(FixtureListResponse {
+        data: fixtures
+            .into_iter()
+            .skip(offset as usize)
+            .take(limit as usize)
+            .map(|f| f.into())
+            .collect(),
+        total,
+        limit,
+        offset,
+    }))
+}
+
+/// Delete a fixture
+#[utoipa::path(
+    delete,
+    path = "/api/projects/{project_id}/fixtures/{name}",
+    params(
+        ("project_id" = Uuid, Path, description = "Project ID"),
+        ("name" = String, Path, description = "Fixture name")
+    ),
+    responses(
+        (status = 200, description = "Fixture deleted"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Project or fixture not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Fixtures"
+)]
+pub async fn delete_fixture(
+    user: AuthUser,
+    State(state): State<AppState>,
+    Path((project_id, name)): Path<(Uuid, String)>,
+) -> AppResult<()> {
+    OwnershipVerifier::verify_project(&state.db, project_id, user.id).await?;
+    FixtureRepository::delete(&state.mongo_db(), project_id, &name).await?;
+    Ok(())
+}
+
+/// Fixture names are referenced as `@name` and `fixture "name"` in steps, so
+/// they are limited to characters that cannot end either reference early
+fn validate_fixture_name(name: &str) -> AppResult<()> {
+    validate_required(name, "Fixture name", 255)?;
+
+    let valid = name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'))
+        && !name.starts_with('.');
+    if !valid {
+        return Err(AppError::Validation(
+            "Fixture name may only contain letters, digits, '.', '_' and '-', and cannot start with '.'"
+                .to_string(),
+        ));
+    }
+
+    Ok(())
+}
@@ -3,10 +3,12 @@ pub mod auth;
 pub mod collection;
 pub mod common;
 pub mod environment;
+pub mod fixture;
 pub mod job;
 pub mod project;
 pub mod report;
 pub mod scenario;
+pub mod step;
 pub mod test_run;
 
 pub use api::{
@@ -22,14 +24,17 @@ pub use collection::{
     CollectionResponse, CreateCollectionRequest, UpdateCollectionRequest,
 };
 pub use common::{
-    validate_optional, validate_required, ApiListResponse, CollectionListResponse,
-    EnvironmentListResponse, JobListResponse, ListResponse, PaginationParams, ProjectListResponse,
-    ReportListResponse, ScenarioListResponse,
+    double_option, run_seed, validate_auth, validate_optional, validate_required,
+    validate_response_schema, validate_variable, validate_variables, ApiListResponse,
+    CollectionListResponse, EnvironmentListResponse, FixtureListResponse, JobListResponse,
+    ListResponse, PaginationParams, ProjectListResponse, ReportListResponse, ScenarioListResponse,
 };
 pub use environment::{
     create_environment, delete_environment, get_environment, list_environments, update_environment,
-    CreateEnvironmentRequest, EnvironmentResponse, UpdateEnvironmentRequest,
+    CreateEnvironmentRequest, EnvironmentResponse, EnvironmentVariableRequest,
+    EnvironmentVariableResponse, UpdateEnvironmentRequest,
 };
+pub use fixture::{delete_fixture, list_fixtures, upload_fixture, FixtureResponse};
 pub use job::{
     cancel_job, get_job_status, get_queue_stats, list_jobs, requeue_job, JobStatusResponse,
     QueueStatsResponse,
@@ -39,13 +44,18 @@ pub use project::{
     CreateProjectRequest, ProjectResponse, UpdateProjectRequest,
 };
 pub use report::{
-    create_report, delete_report, get_report, get_report_detail, list_reports, CreateReportRequest,
-    ReportDetailResponse, ReportResponse, ResponseSummary,
+    create_report, delete_report, get_report, get_report_detail, list_reports, replay_response,
+    CreateReportRequest, ReplayComparison, ReplayRequest, ReplayResponse, ReportDetailResponse,
+    ReportResponse, ResponseSummary,
 };
 pub use scenario::{
-    create_from_gherkin, create_scenario, delete_scenario, get_scenario, list_scenarios,
-    parse_gherkin, update_scenario, BatchCreateResponse, CreateScenarioRequest,
-    ParseGherkinRequest, ParseGherkinResponse, ScenarioResponse, UpdateScenarioRequest,
+    create_from_gherkin, create_scenario, delete_scenario, get_effective_variables, get_scenario,
+    list_scenarios, parse_gherkin, update_scenario, BatchCreateResponse, CreateScenarioRequest,
+    EffectiveVariablesResponse, ParseGherkinRequest, ParseGherkinResponse, ScenarioResponse,
+    UpdateScenarioRequest,
+};
+pub use step::{
+    list_steps, ParameterTypeResponse, StepDefinitionResponse, StepListParams, StepListResponse,
 };
 pub use test_run::{
     run_api_tests, run_collection_tests, run_scenario_test, AsyncTestResponse, RunTestRequest,
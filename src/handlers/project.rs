@@ -3,12 +3,13 @@ use axum::{
     Json,
 };
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::error::AppResult;
 use crate::handlers::{
-    validate_optional, validate_required, PaginationParams, ProjectListResponse,
+    validate_optional, validate_required, validate_variables, PaginationParams, ProjectListResponse,
 };
 use crate::middlewares::AuthUser;
 use crate::models::{CreateProject, Project, UpdateProject};
@@ -21,12 +22,17 @@ use crate::state::AppState;
 pub struct CreateProjectRequest {
     pub name: String,
     pub description: Option<String>,
+    /// Variables available to every scenario in the project as `<name>`
+    #[serde(default)]
+    pub variables: BTreeMap<String, String>,
 }
 
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct UpdateProjectRequest {
     pub name: Option<String>,
     pub description: Option<String>,
+    /// Replaces all variables
+    pub variables: Option<BTreeMap<String, String>>,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -35,6 +41,7 @@ pub struct ProjectResponse {
     pub user_id: Uuid,
     pub name: String,
     pub description: Option<String>,
+    pub variables: BTreeMap<String, String>,
     #[schema(value_type = String)]
     pub created_at: time::OffsetDateTime,
     #[schema(value_type = String)]
@@ -48,6 +55,7 @@ impl From<Project> for ProjectResponse {
             user_id: p.user_id,
             name: p.name,
             description: p.description,
+            variables: p.variables,
             created_at: p.created_at,
             updated_at: p.updated_at,
         }
@@ -78,10 +86,12 @@ pub async fn create_project(
 ) -> AppResult<Json<ProjectResponse>> {
     validate_required(&payload.name, "Name", 100)?;
     validate_optional(&payload.description, "Description", 1000)?;
+    validate_variables(Some(&payload.variables))?;
 
     let create_project = CreateProject {
         name: payload.name,
         description: payload.description,
+        variables: payload.variables,
     };
 
     let project = ProjectRepository::create(&state.db, user.id, &create_project).await?;
@@ -173,10 +183,12 @@ pub async fn update_project(
 ) -> AppResult<Json<ProjectResponse>> {
     validate_optional(&payload.name, "Name", 100)?;
     validate_optional(&payload.description, "Description", 1000)?;
+    validate_variables(payload.variables.as_ref())?;
 
     let update_project = UpdateProject {
         name: payload.name,
         description: payload.description,
+        variables: payload.variables,
     };
 
     let project = ProjectRepository::update(&state.db, id, user.id, &update_project).await?;
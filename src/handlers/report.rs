@@ -7,11 +7,14 @@ use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 use uuid::Uuid;
 
-use crate::error::AppResult;
-use crate::handlers::{PaginationParams, ReportListResponse};
+use crate::error::{AppError, AppResult};
+use crate::handlers::{run_seed, PaginationParams, ReportListResponse, TestResultResponse};
 use crate::middlewares::AuthUser;
-use crate::models::{CreateReport, Report};
-use crate::repositories::{ReportRepository, ResponseRepository};
+use crate::models::{CreateReport, CreateResponse, Report, Response};
+use crate::repositories::{
+    ApiRepository, EnvironmentRepository, ReportRepository, ResponseRepository, ScenarioRepository,
+};
+use crate::services::{SentRequest, TestConfig, TestRunner};
 use crate::state::AppState;
 
 // ============ Request/Response DTOs ============
@@ -40,6 +43,8 @@ pub struct ReportResponse {
     #[schema(value_type = Option<f64>)]
     pub pass_rate: Option<Decimal>,
     pub response_count: i32,
+    /// Seed of the run's generated values; pass it as `seed` to reproduce them
+    pub seed: Option<i64>,
     #[schema(value_type = String)]
     pub created_at: time::OffsetDateTime,
     #[schema(value_type = Option<String>)]
@@ -59,6 +64,7 @@ impl From<Report> for ReportResponse {
             calculated: r.calculated,
             pass_rate: r.pass_rate,
             response_count: r.response_count,
+            seed: r.seed,
             created_at: r.created_at,
             finished_at: r.finished_at,
         }
@@ -83,7 +89,81 @@ pub struct ResponseSummary {
     pub response_status: i16,
     pub pass: bool,
     pub error_message: Option<String>,
+    /// Request sent, secrets redacted: `{ method, url, headers, body }`
+    pub request_data: Option<serde_json::Value>,
+    pub response_data: Option<serde_json::Value>,
+    pub response_headers: Option<serde_json::Value>,
     pub request_duration_ms: Option<i32>,
+    /// Per-phase request timing
+    pub timing: Option<serde_json::Value>,
+    /// Redirects followed before the final response: `[{ status, url, location }]`
+    pub redirects: Option<serde_json::Value>,
+    /// Result of each call of a workflow scenario; the fields above are those
+    /// of the last call
+    pub workflow_steps: Option<serde_json::Value>,
+}
+
+impl From<Response> for ResponseSummary {
+    fn from(r: Response) -> Self {
+        Self {
+            id: r.id,
+            api_id: r.api_id,
+            scenario_id: r.scenario_id,
+            example_index: r.example_index,
+            response_status: r.response_status,
+            pass: r.pass,
+            error_message: r.error_message,
+            request_data: r.request_data,
+            response_data: r.response_data,
+            response_headers: r.response_headers,
+            request_duration_ms: r.request_duration_ms,
+            timing: r.timing,
+            redirects: r.redirects,
+            workflow_steps: r.workflow_steps,
+        }
+    }
+}
+
+/// Request to replay a stored response
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ReplayRequest {
+    /// Environment to run against (default: the report's environment)
+    pub environment_id: Option<Uuid>,
+    /// Optional auth token for authenticated APIs
+    pub auth_token: Option<String>,
+    /// Optional custom headers (key-value pairs)
+    pub custom_headers: Option<std::collections::HashMap<String, String>>,
+    /// Timeout in seconds (default: 30)
+    pub timeout_seconds: Option<u64>,
+    /// Add the replayed result to the original report (default: false)
+    #[serde(default)]
+    pub append_to_report: bool,
+    /// Seed for generated values (default: the report's seed, so the
+    /// original values are reproduced)
+    pub seed: Option<u64>,
+}
+
+/// Differences between the original and the replayed result
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReplayComparison {
+    pub pass_changed: bool,
+    pub status_changed: bool,
+    /// Whether the stored response bodies differ
+    pub body_changed: bool,
+    /// Replay duration minus original duration, in milliseconds
+    pub duration_delta_ms: Option<i64>,
+}
+
+/// Original and replayed result side by side
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReplayResponse {
+    pub original: ResponseSummary,
+    pub replay: TestResultResponse,
+    pub comparison: ReplayComparison,
+    /// Seed of the replay's generated values
+    pub seed: u64,
+    /// ID of the response added to the report, when `append_to_report` is set
+    pub appended_response_id: Option<Uuid>,
 }
 
 // ============ Handlers ============
@@ -118,6 +198,7 @@ pub async fn create_report(
         collection_id: payload.collection_id,
         report_level: payload.report_level,
         report_type: payload.report_type,
+        seed: None,
     };
 
     let report = ReportRepository::create(&state.db, project_id, user.id, &create_report).await?;
@@ -218,19 +299,8 @@ pub async fn get_report_detail(
     let report = ReportRepository::find_by_id_and_user(&state.db, id, user.id).await?;
     let responses = ResponseRepository::list_by_report(&state.db, id, user.id, 1000, 0).await?;
 
-    let response_summaries: Vec<ResponseSummary> = responses
-        .into_iter()
-        .map(|r| ResponseSummary {
-            id: r.id,
-            api_id: r.api_id,
-            scenario_id: r.scenario_id,
-            example_index: r.example_index,
-            response_status: r.response_status,
-            pass: r.pass,
-            error_message: r.error_message,
-            request_duration_ms: r.request_duration_ms,
-        })
-        .collect();
+    let response_summaries: Vec<ResponseSummary> =
+        responses.into_iter().map(|r| r.into()).collect();
 
     Ok(Json(ReportDetailResponse {
         report: report.into(),
@@ -267,3 +337,138 @@ pub async fn delete_report(
 
     Ok(())
 }
+
+/// Replay a stored response.
+///
+/// Sends the stored request again, against the report's environment or
+/// another environment of the same project. The request is rebuilt from the
+/// scenario example to fill in the masked secrets, and the replay is refused
+/// when the rebuilt request differs from the stored one, e.g. because the
+/// scenario changed or used values captured by other scenarios of the run.
+#[utoipa::path(
+    post,
+    path = "/api/responses/{id}/replay",
+    params(
+        ("id" = Uuid, Path, description = "Response ID")
+    ),
+    request_body = ReplayRequest,
+    responses(
+        (status = 200, description = "Original and replayed result", body = ReplayResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Response, scenario or environment not found"),
+        (status = 400, description = "Validation error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Reports"
+)]
+pub async fn replay_response(
+    user: AuthUser,
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<ReplayRequest>,
+) -> AppResult<Json<ReplayResponse>> {
+    let original = ResponseRepository::find_by_id_and_user(&state.db, id, user.id).await?;
+    let report =
+        ReportRepository::find_by_id_and_user(&state.db, original.report_id, user.id).await?;
+
+    let environment_id = payload.environment_id.unwrap_or(report.environment_id);
+    let environment =
+        EnvironmentRepository::find_by_id_and_user(&state.db, environment_id, user.id).await?;
+    if environment.project_id != report.project_id {
+        return Err(AppError::Validation(
+            "Environment must belong to the report's project".to_string(),
+        ));
+    }
+
+    let stored: SentRequest = original
+        .request_data
+        .clone()
+        .and_then(|request| serde_json::from_value(request).ok())
+        .ok_or_else(|| {
+            AppError::Validation("The response has no stored request to replay".to_string())
+        })?;
+    let scenario =
+        ScenarioRepository::find_by_id_and_user(&state.db, original.scenario_id, user.id).await?;
+    let api = ApiRepository::find_by_id_and_user(&state.db, original.api_id, user.id).await?;
+
+    let seed = run_seed(payload.seed.or(report.seed.map(|seed| seed as u64)))?;
+    let config = TestConfig {
+        timeout: std::time::Duration::from_secs(payload.timeout_seconds.unwrap_or(30)),
+        auth_token: payload.auth_token,
+        custom_headers: payload.custom_headers.unwrap_or_default(),
+        seed,
+        // A replay sends the one request again, without hooks around it
+        ..TestConfig::load(&state, &environment, None, [&scenario]).await?
+    };
+
+    let mut runner = TestRunner::with_config(config)?;
+
+    runner.bootstrap(&environment).await?;
+    let result = runner
+        .replay_example(
+            &scenario,
+            &api,
+            &environment,
+            original.example_index,
+            &stored,
+        )
+        .await?;
+
+    let comparison = ReplayComparison {
+        pass_changed: result.pass != original.pass,
+        status_changed: result.response_status != original.response_status,
+        body_changed: result.response_data != original.response_data,
+        duration_delta_ms: original
+            .request_duration_ms
+            .map(|ms| result.request_duration_ms - i64::from(ms)),
+    };
+
+    let appended_response_id = if payload.append_to_report {
+        let input = CreateResponse {
+            api_id: result.api_id,
+            scenario_id: result.scenario_id,
+            example_index: result.example_index,
+            request_data: result
+                .request
+                .as_ref()
+                .and_then(|r| serde_json::to_value(r).ok()),
+            response_data: result.response_data.clone(),
+            response_headers: result.response_headers.clone(),
+            response_status: result.response_status,
+            pass: result.pass,
+            error_message: result.error_message.clone(),
+            request_duration_ms: Some(result.request_duration_ms as i32),
+            timing: result
+                .timing
+                .as_ref()
+                .and_then(|t| serde_json::to_value(t).ok()),
+            redirects: result.stored_redirects(),
+            workflow_steps: result.stored_workflow_steps(),
+        };
+        let appended = ResponseRepository::create(&state.db, report.id, &input).await?;
+
+        let (total, passed, _) =
+            ResponseRepository::get_report_stats(&state.db, report.id, user.id).await?;
+        let pass_rate = if total > 0 {
+            Decimal::from(passed) * Decimal::from(100) / Decimal::from(total)
+        } else {
+            Decimal::ZERO
+        };
+        ReportRepository::update_stats(&state.db, report.id, user.id, pass_rate, total as i32)
+            .await?;
+
+        Some(appended.id)
+    } else {
+        None
+    };
+
+    Ok(Json(ReplayResponse {
+        original: original.into(),
+        replay: result.into(),
+        comparison,
+        seed,
+        appended_response_id,
+    }))
+}
@@ -3,18 +3,27 @@ use axum::{
     Json,
 };
 use serde::{Deserialize, Serialize};
-use utoipa::ToSchema;
+use std::collections::BTreeMap;
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
 use crate::error::{AppError, AppResult};
 use crate::handlers::{
-    validate_optional, validate_required, PaginationParams, ScenarioListResponse,
+    double_option, validate_optional, validate_required, validate_response_schema,
+    validate_variables, PaginationParams, ScenarioListResponse,
 };
 use crate::middlewares::AuthUser;
-use crate::models::{CreateScenario, GherkinStep, Scenario, TestExample, UpdateScenario};
+use crate::models::{
+    BodyMatch, CreateScenario, GherkinStep, Scenario, ScenarioKind, TestExample, UpdateScenario,
+};
 use crate::repositories::mongo::MongoRepository;
-use crate::repositories::ScenarioRepository;
-use crate::services::GherkinService;
+use crate::repositories::{
+    ApiRepository, CollectionRepository, EnvironmentRepository, ScenarioRepository,
+    VariableRepository,
+};
+use crate::services::body_match::IgnorePath;
+use crate::services::secrets::MASKED;
+use crate::services::{workflow, GherkinService, ResolvedVariable};
 use crate::state::AppState;
 
 // ============ Request/Response DTOs ============
@@ -28,13 +37,33 @@ pub struct GherkinStepRequest {
     pub doc_string: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub data_table: Option<Vec<serde_json::Value>>,
+    /// Cells of the data table as written, header row first; needed by
+    /// `I send the following item:` steps
+    pub data_table_cells: Option<Vec<Vec<String>>>,
+}
+
+impl From<GherkinStepRequest> for GherkinStep {
+    fn from(s: GherkinStepRequest) -> Self {
+        Self {
+            keyword: s.keyword,
+            keyword_type: s.keyword_type,
+            text: s.text,
+            doc_string: s.doc_string,
+            data_table: s.data_table,
+            data_table_cells: s.data_table_cells,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct TestExampleRequest {
     pub example: serde_json::Value,
+    /// Expected body; strings such as `"<any-uuid>"` or `"<any-iso8601>"`
+    /// match any value of that kind
     pub expected_response_body: serde_json::Value,
     pub expected_status_code: i16,
+    /// Overrides the scenario's body matching for this example
+    pub body_match: Option<BodyMatch>,
 }
 
 #[derive(Debug, Deserialize, ToSchema)]
@@ -42,8 +71,21 @@ pub struct CreateScenarioRequest {
     pub title: String,
     pub description: Option<String>,
     pub tags: Option<Vec<String>>,
+    /// `workflow` scenarios call APIs of the project with `call "<API>"` steps
+    #[serde(default)]
+    pub kind: ScenarioKind,
+    /// Steps run before `steps`, as from a feature's Background
+    #[serde(default)]
+    pub background_steps: Vec<GherkinStepRequest>,
     pub steps: Vec<GherkinStepRequest>,
     pub examples: Vec<TestExampleRequest>,
+    /// JSON Schema (draft 2020-12) overriding the API's response schema
+    pub response_schema: Option<serde_json::Value>,
+    /// How expected bodies are compared; subset matching if unset
+    pub body_match: Option<BodyMatch>,
+    /// Variables overriding project, collection and environment variables
+    #[serde(default)]
+    pub variables: BTreeMap<String, String>,
 }
 
 #[derive(Debug, Deserialize, ToSchema)]
@@ -51,8 +93,21 @@ pub struct UpdateScenarioRequest {
     pub title: Option<String>,
     pub description: Option<String>,
     pub tags: Option<Vec<String>>,
+    pub kind: Option<ScenarioKind>,
+    pub background_steps: Option<Vec<GherkinStepRequest>>,
     pub steps: Option<Vec<GherkinStepRequest>>,
     pub examples: Option<Vec<TestExampleRequest>>,
+    /// JSON Schema (draft 2020-12) overriding the API's response schema;
+    /// `null` falls back to the API's schema again
+    #[serde(default, deserialize_with = "double_option")]
+    #[schema(value_type = Option<Object>)]
+    pub response_schema: Option<Option<serde_json::Value>>,
+    /// Replaces the body matching; `null` restores subset matching
+    #[serde(default, deserialize_with = "double_option")]
+    #[schema(value_type = Option<BodyMatch>)]
+    pub body_match: Option<Option<BodyMatch>>,
+    /// Replaces all variables
+    pub variables: Option<BTreeMap<String, String>>,
 }
 
 /// Request to parse Gherkin and create scenarios
@@ -70,6 +125,8 @@ pub struct GherkinStepResponse {
     pub doc_string: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub data_table: Option<Vec<serde_json::Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data_table_cells: Option<Vec<Vec<String>>>,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -77,6 +134,8 @@ pub struct TestExampleResponse {
     pub example: serde_json::Value,
     pub expected_response_body: serde_json::Value,
     pub expected_status_code: i16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body_match: Option<BodyMatch>,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -86,8 +145,14 @@ pub struct ScenarioResponse {
     pub title: String,
     pub description: Option<String>,
     pub tags: Vec<String>,
+    pub kind: ScenarioKind,
+    /// Steps run before `steps`, from the feature's Background
+    pub background_steps: serde_json::Value,
     pub steps: serde_json::Value,
     pub examples: serde_json::Value,
+    pub response_schema: Option<serde_json::Value>,
+    pub body_match: Option<BodyMatch>,
+    pub variables: BTreeMap<String, String>,
     #[schema(value_type = String)]
     pub created_at: time::OffsetDateTime,
     #[schema(value_type = String)]
@@ -102,8 +167,13 @@ impl From<Scenario> for ScenarioResponse {
             title: s.title,
             description: s.description,
             tags: s.tags,
+            kind: s.kind,
+            background_steps: s.background_steps,
             steps: s.steps,
             examples: s.examples,
+            response_schema: s.response_schema,
+            body_match: s.body_match,
+            variables: s.variables,
             created_at: s.created_at,
             updated_at: s.updated_at,
         }
@@ -136,6 +206,25 @@ pub struct ParseGherkinResponse {
     pub scenarios: Vec<ParsedScenarioResponse>,
 }
 
+/// Query for the effective variables of a scenario
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct EffectiveVariablesParams {
+    /// Environment the scenario would run against
+    pub environment_id: Uuid,
+    /// Also apply the columns of this example row
+    pub example_index: Option<usize>,
+}
+
+/// Variables a scenario resolves, with the scope each value comes from
+#[derive(Debug, Serialize, ToSchema)]
+pub struct EffectiveVariablesResponse {
+    pub scenario_id: Uuid,
+    pub environment_id: Uuid,
+    pub example_index: Option<usize>,
+    /// Variables by name; secret values are shown as `********`
+    pub variables: BTreeMap<String, ResolvedVariable>,
+}
+
 /// Response for batch create from Gherkin
 #[derive(Debug, Serialize, ToSchema)]
 pub struct BatchCreateResponse {
@@ -145,6 +234,43 @@ pub struct BatchCreateResponse {
 
 // ============ Handlers ============
 
+/// Check that a workflow's steps start with a call step and that each call
+/// names exactly one API of the project `api_id` belongs to
+async fn validate_workflow(
+    state: &AppState,
+    api_id: Uuid,
+    user_id: Uuid,
+    steps: &[GherkinStep],
+) -> AppResult<()> {
+    let calls = workflow::split(steps).map_err(AppError::Validation)?;
+
+    let api = ApiRepository::find_by_id_and_user(&state.db, api_id, user_id).await?;
+    let collection =
+        CollectionRepository::find_by_id_and_user(&state.db, api.collection_id, user_id).await?;
+    let apis = ApiRepository::list_by_project(&state.db, collection.project_id).await?;
+    for (index, call) in calls.iter().enumerate() {
+        workflow::resolve(&call.target, &apis)
+            .map_err(|e| AppError::Validation(format!("Call {}: {}", index + 1, e)))?;
+    }
+
+    Ok(())
+}
+
+/// Check that the ignore paths of a scenario's and its examples' body
+/// matching parse
+fn validate_body_match<'a>(
+    body_matches: impl IntoIterator<Item = Option<&'a BodyMatch>>,
+) -> AppResult<()> {
+    for path in body_matches
+        .into_iter()
+        .flatten()
+        .flat_map(|body_match| &body_match.ignore_paths)
+    {
+        IgnorePath::parse(path).map_err(AppError::Validation)?;
+    }
+    Ok(())
+}
+
 /// Create a new scenario for an API
 #[utoipa::path(
     post,
@@ -172,22 +298,29 @@ pub async fn create_scenario(
 ) -> AppResult<Json<ScenarioResponse>> {
     validate_required(&payload.title, "Title", 200)?;
     validate_optional(&payload.description, "Description", 1000)?;
+    validate_response_schema(payload.response_schema.as_ref())?;
+    validate_variables(Some(&payload.variables))?;
+    validate_body_match(
+        std::iter::once(payload.body_match.as_ref())
+            .chain(payload.examples.iter().map(|e| e.body_match.as_ref())),
+    )?;
+
+    let steps: Vec<GherkinStep> = payload.steps.into_iter().map(GherkinStep::from).collect();
+    if payload.kind == ScenarioKind::Workflow {
+        validate_workflow(&state, api_id, user.id, &steps).await?;
+    }
 
     let create_scenario = CreateScenario {
         title: payload.title,
         description: payload.description,
         tags: payload.tags,
-        steps: payload
-            .steps
+        kind: payload.kind,
+        background_steps: payload
+            .background_steps
             .into_iter()
-            .map(|s| GherkinStep {
-                keyword: s.keyword,
-                keyword_type: s.keyword_type,
-                text: s.text,
-                doc_string: s.doc_string,
-                data_table: s.data_table,
-            })
+            .map(GherkinStep::from)
             .collect(),
+        steps,
         examples: payload
             .examples
             .into_iter()
@@ -195,8 +328,12 @@ pub async fn create_scenario(
                 example: e.example,
                 expected_response_body: e.expected_response_body,
                 expected_status_code: e.expected_status_code,
+                body_match: e.body_match,
             })
             .collect(),
+        response_schema: payload.response_schema,
+        body_match: payload.body_match,
+        variables: payload.variables,
     };
 
     let scenario = ScenarioRepository::create(&state.db, api_id, user.id, &create_scenario).await?;
@@ -294,23 +431,42 @@ pub async fn update_scenario(
 ) -> AppResult<Json<ScenarioResponse>> {
     validate_optional(&payload.title, "Title", 200)?;
     validate_optional(&payload.description, "Description", 1000)?;
+    validate_response_schema(payload.response_schema.as_ref().and_then(Option::as_ref))?;
+    validate_variables(payload.variables.as_ref())?;
+    validate_body_match(
+        std::iter::once(payload.body_match.as_ref().and_then(Option::as_ref)).chain(
+            payload
+                .examples
+                .iter()
+                .flatten()
+                .map(|e| e.body_match.as_ref()),
+        ),
+    )?;
+
+    let steps: Option<Vec<GherkinStep>> = payload
+        .steps
+        .map(|steps| steps.into_iter().map(GherkinStep::from).collect());
+    if payload.kind.is_some() || steps.is_some() {
+        let scenario = ScenarioRepository::find_by_id_and_user(&state.db, id, user.id).await?;
+        if payload.kind.unwrap_or(scenario.kind) == ScenarioKind::Workflow {
+            let steps = match &steps {
+                Some(steps) => steps.clone(),
+                None => serde_json::from_value(scenario.steps)
+                    .map_err(|e| AppError::Validation(format!("Invalid steps format: {}", e)))?,
+            };
+            validate_workflow(&state, scenario.api_id, user.id, &steps).await?;
+        }
+    }
 
     let update_scenario = UpdateScenario {
         title: payload.title,
         description: payload.description,
         tags: payload.tags,
-        steps: payload.steps.map(|steps| {
-            steps
-                .into_iter()
-                .map(|s| GherkinStep {
-                    keyword: s.keyword,
-                    keyword_type: s.keyword_type,
-                    text: s.text,
-                    doc_string: s.doc_string,
-                    data_table: s.data_table,
-                })
-                .collect()
-        }),
+        kind: payload.kind,
+        background_steps: payload
+            .background_steps
+            .map(|steps| steps.into_iter().map(GherkinStep::from).collect()),
+        steps,
         examples: payload.examples.map(|examples| {
             examples
                 .into_iter()
@@ -318,9 +474,13 @@ pub async fn update_scenario(
                     example: e.example,
                     expected_response_body: e.expected_response_body,
                     expected_status_code: e.expected_status_code,
+                    body_match: e.body_match,
                 })
                 .collect()
         }),
+        response_schema: payload.response_schema,
+        body_match: payload.body_match,
+        variables: payload.variables,
     };
 
     let scenario = ScenarioRepository::update(&state.db, id, user.id, &update_scenario).await?;
@@ -337,7 +497,8 @@ pub async fn update_scenario(
     responses(
         (status = 204, description = "Scenario deleted successfully"),
         (status = 401, description = "Unauthorized"),
-        (status = 404, description = "Scenario not found")
+        (status = 404, description = "Scenario not found"),
+        (status = 409, description = "Scenario is an environment's auth bootstrap")
     ),
     security(
         ("bearer_auth" = [])
@@ -353,6 +514,79 @@ pub async fn delete_scenario(
     Ok(())
 }
 
+/// Show the variables a scenario resolves against an environment.
+///
+/// Scopes apply from broadest to narrowest: project, collection, environment,
+/// scenario, then the example row. Variables captured from responses during a
+/// run are not known ahead of it and are not included.
+#[utoipa::path(
+    get,
+    path = "/api/scenarios/{id}/variables",
+    params(
+        ("id" = Uuid, Path, description = "Scenario ID"),
+        EffectiveVariablesParams
+    ),
+    responses(
+        (status = 200, description = "Effective variables", body = EffectiveVariablesResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Scenario or environment not found"),
+        (status = 400, description = "Validation error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Scenarios"
+)]
+pub async fn get_effective_variables(
+    user: AuthUser,
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(params): Query<EffectiveVariablesParams>,
+) -> AppResult<Json<EffectiveVariablesResponse>> {
+    let scenario = ScenarioRepository::find_by_id_and_user(&state.db, id, user.id).await?;
+    let api = ApiRepository::find_by_id_and_user(&state.db, scenario.api_id, user.id).await?;
+    let collection =
+        CollectionRepository::find_by_id_and_user(&state.db, api.collection_id, user.id).await?;
+    let environment =
+        EnvironmentRepository::find_by_id_and_user(&state.db, params.environment_id, user.id)
+            .await?;
+    if environment.project_id != collection.project_id {
+        return Err(AppError::Validation(
+            "Environment must belong to the scenario's project".to_string(),
+        ));
+    }
+
+    let example = match params.example_index {
+        Some(index) => {
+            let examples: Vec<TestExample> = serde_json::from_value(scenario.examples.clone())
+                .map_err(|e| AppError::Validation(format!("Invalid examples format: {}", e)))?;
+            let example = examples.get(index).ok_or_else(|| {
+                AppError::Validation(format!(
+                    "Scenario has no example {} (it has {})",
+                    index,
+                    examples.len()
+                ))
+            })?;
+            Some(example.example.clone())
+        }
+        None => None,
+    };
+
+    let scopes =
+        VariableRepository::load_scopes(&state.db, &state.secret_cipher(), &environment).await?;
+    let mut variables = scopes.resolve(api.collection_id, &scenario.variables, example.as_ref());
+    for variable in variables.values_mut().filter(|v| v.secret) {
+        variable.value = MASKED.to_string();
+    }
+
+    Ok(Json(EffectiveVariablesResponse {
+        scenario_id: scenario.id,
+        environment_id: environment.id,
+        example_index: params.example_index,
+        variables,
+    }))
+}
+
 /// Parse Gherkin code and preview scenarios (without saving)
 #[utoipa::path(
     post,
@@ -400,6 +634,7 @@ pub async fn parse_gherkin(
                     text: step.text,
                     doc_string: step.doc_string,
                     data_table: step.data_table,
+                    data_table_cells: step.data_table_cells,
                 })
                 .collect(),
             examples: s
@@ -422,6 +657,7 @@ pub async fn parse_gherkin(
             text: step.text,
             doc_string: step.doc_string,
             data_table: step.data_table,
+            data_table_cells: step.data_table_cells,
         })
         .collect();
 
@@ -481,22 +717,52 @@ pub async fn create_from_gherkin(
 
     let mut created_scenarios = Vec::new();
 
-    for parsed_scenario in parsed.scenarios {
+    // Every scenario of the feature runs its Background first
+    let background_steps: Vec<GherkinStep> = parsed
+        .background_steps
+        .into_iter()
+        .map(GherkinStep::from)
+        .collect();
+
+    // Scenarios tagged `@workflow` call APIs of the project; they are all
+    // checked before any scenario is created
+    let mut scenarios = Vec::new();
+    for mut parsed_scenario in parsed.scenarios {
+        let kind = if parsed_scenario
+            .tags
+            .iter()
+            .any(|tag| tag.trim_start_matches('@').eq_ignore_ascii_case("workflow"))
+        {
+            ScenarioKind::Workflow
+        } else {
+            ScenarioKind::Single
+        };
+        let steps: Vec<GherkinStep> = std::mem::take(&mut parsed_scenario.steps)
+            .into_iter()
+            .map(GherkinStep::from)
+            .collect();
+        if kind == ScenarioKind::Workflow {
+            validate_workflow(&state, api_id, user.id, &steps)
+                .await
+                .map_err(|e| match e {
+                    AppError::Validation(message) => AppError::Validation(format!(
+                        "Scenario \"{}\": {}",
+                        parsed_scenario.title, message
+                    )),
+                    e => e,
+                })?;
+        }
+        scenarios.push((parsed_scenario, kind, steps));
+    }
+
+    for (parsed_scenario, kind, steps) in scenarios {
         let create_scenario = CreateScenario {
             title: parsed_scenario.title,
             description: parsed_scenario.description,
             tags: Some(parsed_scenario.tags),
-            steps: parsed_scenario
-                .steps
-                .into_iter()
-                .map(|s| GherkinStep {
-                    keyword: s.keyword,
-                    keyword_type: s.keyword_type,
-                    text: s.text,
-                    doc_string: s.doc_string,
-                    data_table: s.data_table,
-                })
-                .collect(),
+            kind,
+            background_steps: background_steps.clone(),
+            steps,
             examples: parsed_scenario
                 .examples
                 .into_iter()
@@ -504,8 +770,12 @@ pub async fn create_from_gherkin(
                     example: e.data,
                     expected_response_body: serde_json::Value::Null,
                     expected_status_code: e.expected_status_code.unwrap_or(200),
+                    body_match: None,
                 })
                 .collect(),
+            response_schema: None,
+            body_match: None,
+            variables: BTreeMap::new(),
         };
 
         let scenario =
@@ -0,0 +1,90 @@
+use axum::{extract::Query, Json};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::error::AppResult;
+use crate::middlewares::AuthUser;
+use crate::services::{PatternSyntax, StepKind, StepRegistry};
+
+// ============ Request/Response DTOs ============
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct StepListParams {
+    /// Only list definitions for request (Given/When) or outcome (Then) steps
+    pub kind: Option<StepKind>,
+}
+
+/// A step pattern scenario steps are matched against
+#[derive(Debug, Serialize, ToSchema)]
+pub struct StepDefinitionResponse {
+    pub kind: StepKind,
+    pub pattern: String,
+    pub syntax: PatternSyntax,
+    pub description: String,
+    /// Parameter type of each capture, in order; empty for regex groups
+    pub parameters: Vec<String>,
+}
+
+/// A `{name}` parameter type usable in step patterns
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ParameterTypeResponse {
+    /// Empty for the anonymous `{}` parameter
+    pub name: String,
+    pub regex: String,
+}
+
+/// Step definitions, in the order they are tried
+#[derive(Debug, Serialize, ToSchema)]
+pub struct StepListResponse {
+    pub data: Vec<StepDefinitionResponse>,
+    pub parameter_types: Vec<ParameterTypeResponse>,
+}
+
+// ============ Handlers ============
+
+/// List the step definitions scenario steps are matched against
+#[utoipa::path(
+    get,
+    path = "/api/steps",
+    params(StepListParams),
+    responses(
+        (status = 200, description = "Step definitions", body = StepListResponse),
+        (status = 401, description = "Unauthorized")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Steps"
+)]
+pub async fn list_steps(
+    _user: AuthUser,
+    Query(params): Query<StepListParams>,
+) -> AppResult<Json<StepListResponse>> {
+    let registry = StepRegistry::builtin();
+
+    let data = registry
+        .definitions()
+        .iter()
+        .filter(|definition| params.kind.is_none_or(|kind| definition.kind == kind))
+        .map(|definition| StepDefinitionResponse {
+            kind: definition.kind,
+            pattern: definition.pattern.clone(),
+            syntax: definition.syntax,
+            description: definition.description.clone(),
+            parameters: definition.parameters.clone(),
+        })
+        .collect();
+    let parameter_types = registry
+        .parameter_types()
+        .iter()
+        .map(|parameter| ParameterTypeResponse {
+            name: parameter.name.clone(),
+            regex: parameter.regex.clone(),
+        })
+        .collect();
+
+    Ok(Json(StepListResponse {
+        data,
+        parameter_types,
+    }))
+}
@@ -7,10 +7,14 @@ use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::error::{AppError, AppResult};
+use crate::handlers::run_seed;
 use crate::middlewares::AuthUser;
 use crate::queue::{TestJob, TestJobConfig, TestJobType};
 use crate::repositories::{ApiRepository, EnvironmentRepository, ScenarioRepository};
-use crate::services::{TestConfig, TestResult, TestRunner};
+use crate::services::{
+    CookieJarScope, Hook, RedirectHop, RequestTiming, SentRequest, TestConfig, TestResult,
+    TestRunner, WorkflowStepResult,
+};
 use crate::state::AppState;
 
 // ============ Request/Response DTOs ============
@@ -29,6 +33,13 @@ pub struct RunTestRequest {
     /// Run asynchronously via job queue (default: false for backward compatibility)
     #[serde(default)]
     pub async_execution: bool,
+    /// Seed for `{{generator}}` values; pass the seed of an earlier run to
+    /// reproduce its generated values (default: random)
+    pub seed: Option<u64>,
+    /// Keep cookies set by responses across each scenario's examples
+    /// (`scenario`) or the whole run (`run`). By default each example starts
+    /// without cookies, unless its scenario is tagged `@cookies`.
+    pub cookie_jar: Option<CookieJarScope>,
 }
 
 /// Single test result response
@@ -39,9 +50,17 @@ pub struct TestResultResponse {
     pub example_index: i32,
     pub pass: bool,
     pub error_message: Option<String>,
+    pub request: Option<SentRequest>,
     pub response_status: i16,
     pub response_data: Option<serde_json::Value>,
+    pub response_headers: Option<serde_json::Value>,
     pub request_duration_ms: i64,
+    pub timing: Option<RequestTiming>,
+    /// Redirects followed before the final response
+    pub redirects: Vec<RedirectHop>,
+    /// Result of each call of a workflow scenario, up to the first that
+    /// failed; the fields above are those of the last one
+    pub workflow_steps: Vec<WorkflowStepResult>,
     #[schema(value_type = String)]
     pub request_time: time::OffsetDateTime,
 }
@@ -54,9 +73,14 @@ impl From<TestResult> for TestResultResponse {
             example_index: r.example_index,
             pass: r.pass,
             error_message: r.error_message,
+            request: r.request,
             response_status: r.response_status,
             response_data: r.response_data,
+            response_headers: r.response_headers,
             request_duration_ms: r.request_duration_ms,
+            timing: r.timing,
+            redirects: r.redirects,
+            workflow_steps: r.workflow_steps,
             request_time: r.request_time,
         }
     }
@@ -70,6 +94,8 @@ pub struct TestRunResponse {
     pub failed: usize,
     pub pass_rate: f64,
     pub total_duration_ms: i64,
+    /// Seed of the generated values, to reproduce this run
+    pub seed: u64,
     pub results: Vec<TestResultResponse>,
 }
 
@@ -131,12 +157,16 @@ pub async fn run_scenario_test(
         EnvironmentRepository::find_by_id_and_user(&state.db, payload.environment_id, user.id)
             .await?;
 
+    let seed = run_seed(payload.seed)?;
+
     if payload.async_execution {
         // Async execution - enqueue job
         let config = TestJobConfig {
             timeout_seconds: payload.timeout_seconds.unwrap_or(30),
             auth_token: payload.auth_token,
             custom_headers: payload.custom_headers.unwrap_or_default(),
+            seed: Some(seed),
+            cookie_jar: payload.cookie_jar.unwrap_or_default(),
         };
 
         let job = TestJob::new(
@@ -164,12 +194,17 @@ pub async fn run_scenario_test(
             timeout: std::time::Duration::from_secs(payload.timeout_seconds.unwrap_or(30)),
             auth_token: payload.auth_token,
             custom_headers: payload.custom_headers.unwrap_or_default(),
+            seed,
+            cookie_jar: payload.cookie_jar.unwrap_or_default(),
+            ..TestConfig::load(&state, &environment, Some(api.collection_id), [&scenario]).await?
         };
 
-        let runner = TestRunner::with_config(config)?;
+        let mut runner = TestRunner::with_config(config)?;
+
+        runner.bootstrap(&environment).await?;
         let results = runner.run_scenario(&scenario, &api, &environment).await?;
 
-        let response = build_test_run_response(results);
+        let response = build_test_run_response(results, runner.seed());
         Ok(Json(
             serde_json::to_value(response).map_err(|e| AppError::Internal(e.to_string()))?,
         ))
@@ -210,8 +245,14 @@ pub async fn run_api_tests(
         EnvironmentRepository::find_by_id_and_user(&state.db, payload.environment_id, user.id)
             .await?;
 
-    // Get all scenarios for this API (for validation in sync mode)
-    let scenarios = ScenarioRepository::list_by_api(&state.db, api_id, user.id, 1000, 0).await?;
+    // Get all scenarios for this API (for validation in sync mode), in creation
+    // order so values captured by earlier scenarios reach later ones
+    // Hook scenarios only run around the others, and the auth bootstrap
+    // before them
+    let mut scenarios =
+        ScenarioRepository::list_by_api(&state.db, api_id, user.id, 1000, 0).await?;
+    scenarios.retain(|s| Hook::of(s).is_none() && !environment.is_bootstrap(s));
+    scenarios.sort_by_key(|s| s.created_at);
 
     if scenarios.is_empty() {
         return Err(AppError::Validation(
@@ -219,12 +260,16 @@ pub async fn run_api_tests(
         ));
     }
 
+    let seed = run_seed(payload.seed)?;
+
     if payload.async_execution {
         // Async execution - enqueue job
         let config = TestJobConfig {
             timeout_seconds: payload.timeout_seconds.unwrap_or(30),
             auth_token: payload.auth_token,
             custom_headers: payload.custom_headers.unwrap_or_default(),
+            seed: Some(seed),
+            cookie_jar: payload.cookie_jar.unwrap_or_default(),
         };
 
         let job = TestJob::new(
@@ -252,9 +297,14 @@ pub async fn run_api_tests(
             timeout: std::time::Duration::from_secs(payload.timeout_seconds.unwrap_or(30)),
             auth_token: payload.auth_token,
             custom_headers: payload.custom_headers.unwrap_or_default(),
+            seed,
+            cookie_jar: payload.cookie_jar.unwrap_or_default(),
+            ..TestConfig::load(&state, &environment, Some(api.collection_id), &scenarios).await?
         };
 
-        let runner = TestRunner::with_config(config)?;
+        let mut runner = TestRunner::with_config(config)?;
+
+        runner.bootstrap(&environment).await?;
         let mut all_results = Vec::new();
 
         for scenario in &scenarios {
@@ -262,7 +312,7 @@ pub async fn run_api_tests(
             all_results.extend(results);
         }
 
-        let response = build_test_run_response(all_results);
+        let response = build_test_run_response(all_results, runner.seed());
         Ok(Json(
             serde_json::to_value(response).map_err(|e| AppError::Internal(e.to_string()))?,
         ))
@@ -307,8 +357,9 @@ pub async fn run_collection_tests(
             .await?;
 
     // Get all APIs for this collection
-    let apis =
+    let mut apis =
         ApiRepository::list_by_collection(&state.db, collection_id, user.id, 1000, 0).await?;
+    apis.sort_by_key(|a| a.created_at);
 
     if apis.is_empty() {
         return Err(AppError::Validation(
@@ -316,12 +367,16 @@ pub async fn run_collection_tests(
         ));
     }
 
+    let seed = run_seed(payload.seed)?;
+
     if payload.async_execution {
         // Async execution - enqueue job
         let config = TestJobConfig {
             timeout_seconds: payload.timeout_seconds.unwrap_or(30),
             auth_token: payload.auth_token,
             custom_headers: payload.custom_headers.unwrap_or_default(),
+            seed: Some(seed),
+            cookie_jar: payload.cookie_jar.unwrap_or_default(),
         };
 
         let job = TestJob::new(
@@ -345,20 +400,37 @@ pub async fn run_collection_tests(
         ))
     } else {
         // Sync execution - run immediately
+        let mut runs = Vec::new();
+        for api in apis {
+            let mut scenarios =
+                ScenarioRepository::list_by_api(&state.db, api.id, user.id, 1000, 0).await?;
+            scenarios.retain(|s| Hook::of(s).is_none() && !environment.is_bootstrap(s));
+            scenarios.sort_by_key(|s| s.created_at);
+            runs.push((api, scenarios));
+        }
+
         let config = TestConfig {
             timeout: std::time::Duration::from_secs(payload.timeout_seconds.unwrap_or(30)),
             auth_token: payload.auth_token.clone(),
             custom_headers: payload.custom_headers.clone().unwrap_or_default(),
+            seed,
+            cookie_jar: payload.cookie_jar.unwrap_or_default(),
+            ..TestConfig::load(
+                &state,
+                &environment,
+                Some(collection_id),
+                runs.iter().flat_map(|(_, scenarios)| scenarios),
+            )
+            .await?
         };
 
-        let runner = TestRunner::with_config(config)?;
-        let mut all_results = Vec::new();
+        let mut runner = TestRunner::with_config(config)?;
 
-        for api in &apis {
-            let scenarios =
-                ScenarioRepository::list_by_api(&state.db, api.id, user.id, 1000, 0).await?;
+        runner.bootstrap(&environment).await?;
+        let mut all_results = Vec::new();
 
-            for scenario in &scenarios {
+        for (api, scenarios) in &runs {
+            for scenario in scenarios {
                 let results = runner.run_scenario(scenario, api, &environment).await?;
                 all_results.extend(results);
             }
@@ -370,7 +442,7 @@ pub async fn run_collection_tests(
             ));
         }
 
-        let response = build_test_run_response(all_results);
+        let response = build_test_run_response(all_results, runner.seed());
         Ok(Json(
             serde_json::to_value(response).map_err(|e| AppError::Internal(e.to_string()))?,
         ))
@@ -378,7 +450,7 @@ pub async fn run_collection_tests(
 }
 
 /// Helper to build test run response from results
-fn build_test_run_response(results: Vec<TestResult>) -> TestRunResponse {
+fn build_test_run_response(results: Vec<TestResult>, seed: u64) -> TestRunResponse {
     let total = results.len();
     let passed = results.iter().filter(|r| r.pass).count();
     let failed = total - passed;
@@ -395,6 +467,7 @@ fn build_test_run_response(results: Vec<TestResult>) -> TestRunResponse {
         failed,
         pass_rate,
         total_duration_ms,
+        seed,
         results: results.into_iter().map(|r| r.into()).collect(),
     }
 }
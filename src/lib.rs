@@ -13,7 +13,7 @@ pub mod services;
 pub mod state;
 
 use axum::{
-    extract::State,
+    extract::{DefaultBodyLimit, State},
     middleware,
     routing::{delete, get, post, put},
     Json, Router,
@@ -21,15 +21,17 @@ use axum::{
 use bson::doc;
 use tower_http::cors::CorsLayer;
 
+use crate::handlers::fixture::MAX_FIXTURE_BYTES;
 use crate::handlers::{
     cancel_job, create_api, create_collection, create_environment, create_from_gherkin,
     create_project, create_report, create_scenario, delete_api, delete_collection,
-    delete_environment, delete_project, delete_report, delete_scenario, get_api, get_collection,
-    get_environment, get_job_status, get_project, get_queue_stats, get_report, get_report_detail,
-    get_scenario, list_apis, list_collections, list_environments, list_jobs, list_projects,
-    list_reports, list_scenarios, login, logout, me, parse_gherkin, refresh, register, requeue_job,
+    delete_environment, delete_fixture, delete_project, delete_report, delete_scenario, get_api,
+    get_collection, get_effective_variables, get_environment, get_job_status, get_project,
+    get_queue_stats, get_report, get_report_detail, get_scenario, list_apis, list_collections,
+    list_environments, list_fixtures, list_jobs, list_projects, list_reports, list_scenarios,
+    list_steps, login, logout, me, parse_gherkin, refresh, register, replay_response, requeue_job,
     run_api_tests, run_collection_tests, run_scenario_test, update_api, update_collection,
-    update_environment, update_me, update_project, update_scenario,
+    update_environment, update_me, update_project, update_scenario, upload_fixture,
 };
 use crate::middlewares::auth_middleware;
 use crate::state::AppState;
@@ -73,6 +75,16 @@ pub fn build_router(state: AppState) -> Router {
         .route("/api/environments/{id}", get(get_environment))
         .route("/api/environments/{id}", put(update_environment))
         .route("/api/environments/{id}", delete(delete_environment))
+        // Fixture routes (nested under projects)
+        .route("/api/projects/{project_id}/fixtures", get(list_fixtures))
+        .route(
+            "/api/projects/{project_id}/fixtures/{name}",
+            put(upload_fixture).layer(DefaultBodyLimit::max(MAX_FIXTURE_BYTES)),
+        )
+        .route(
+            "/api/projects/{project_id}/fixtures/{name}",
+            delete(delete_fixture),
+        )
         // API routes (nested under collections)
         .route("/api/collections/{collection_id}/apis", get(list_apis))
         .route("/api/collections/{collection_id}/apis", post(create_api))
@@ -92,6 +104,10 @@ pub fn build_router(state: AppState) -> Router {
         .route("/api/scenarios/{id}", get(get_scenario))
         .route("/api/scenarios/{id}", put(update_scenario))
         .route("/api/scenarios/{id}", delete(delete_scenario))
+        .route(
+            "/api/scenarios/{id}/variables",
+            get(get_effective_variables),
+        )
         // Test execution routes
         .route("/api/scenarios/{scenario_id}/run", post(run_scenario_test))
         .route("/api/apis/{api_id}/run", post(run_api_tests))
@@ -99,6 +115,8 @@ pub fn build_router(state: AppState) -> Router {
             "/api/collections/{collection_id}/run",
             post(run_collection_tests),
         )
+        // Step definitions
+        .route("/api/steps", get(list_steps))
         // Job management routes
         .route("/api/jobs", get(list_jobs))
         .route("/api/jobs/stats", get(get_queue_stats))
@@ -112,6 +130,8 @@ pub fn build_router(state: AppState) -> Router {
         .route("/api/reports/{id}", get(get_report))
         .route("/api/reports/{id}/detail", get(get_report_detail))
         .route("/api/reports/{id}", delete(delete_report))
+        // Response replay
+        .route("/api/responses/{id}/replay", post(replay_response))
         // Auth routes (require authentication)
         .route("/api/auth/logout", post(logout))
         .route_layer(middleware::from_fn_with_state(
@@ -12,14 +12,24 @@ use serval_run::handlers::{
     ApiListResponse, ApiResponse, AsyncTestResponse, AuthResponse, BatchCreateResponse,
     CollectionListResponse, CollectionResponse, CreateApiRequest, CreateCollectionRequest,
     CreateEnvironmentRequest, CreateProjectRequest, CreateReportRequest, CreateScenarioRequest,
-    EnvironmentListResponse, EnvironmentResponse, JobListResponse, JobStatusResponse, LoginRequest,
-    ParseGherkinRequest, ParseGherkinResponse, ProjectListResponse, ProjectResponse,
-    QueueStatsResponse, RegisterRequest, ReportDetailResponse, ReportListResponse, ReportResponse,
-    ResponseSummary, RunTestRequest, ScenarioListResponse, ScenarioResponse, TestResultResponse,
+    EffectiveVariablesResponse, EnvironmentListResponse, EnvironmentResponse,
+    EnvironmentVariableRequest, EnvironmentVariableResponse, FixtureListResponse, FixtureResponse,
+    JobListResponse, JobStatusResponse, LoginRequest, ParameterTypeResponse, ParseGherkinRequest,
+    ParseGherkinResponse, ProjectListResponse, ProjectResponse, QueueStatsResponse,
+    RegisterRequest, ReplayComparison, ReplayRequest, ReplayResponse, ReportDetailResponse,
+    ReportListResponse, ReportResponse, ResponseSummary, RunTestRequest, ScenarioListResponse,
+    ScenarioResponse, StepDefinitionResponse, StepListResponse, TestResultResponse,
     TestRunResponse, UpdateApiRequest, UpdateCollectionRequest, UpdateEnvironmentRequest,
     UpdateProjectRequest, UpdateScenarioRequest, UpdateUserRequest,
 };
-use serval_run::models::UserResponse;
+use serval_run::models::{
+    ApiKeyLocation, AuthBootstrap, BodyMatch, ClientAuthMethod, HttpVersion, MatchMode,
+    RedirectPolicy, RequestAuth, ScenarioKind, TransportSettings, UserResponse,
+};
+use serval_run::services::{
+    CookieJarScope, PatternSyntax, RedirectHop, RequestTiming, ResolvedVariable, SentRequest,
+    StepKind, VariableScope, WorkflowStepResult,
+};
 use serval_run::state::AppState;
 use serval_run::{build_router, handlers};
 
@@ -64,6 +74,9 @@ impl Modify for SecurityAddon {
         handlers::environment::get_environment,
         handlers::environment::update_environment,
         handlers::environment::delete_environment,
+        handlers::fixture::upload_fixture,
+        handlers::fixture::list_fixtures,
+        handlers::fixture::delete_fixture,
         handlers::api::create_api,
         handlers::api::list_apis,
         handlers::api::get_api,
@@ -74,6 +87,7 @@ impl Modify for SecurityAddon {
         handlers::scenario::get_scenario,
         handlers::scenario::update_scenario,
         handlers::scenario::delete_scenario,
+        handlers::scenario::get_effective_variables,
         handlers::scenario::parse_gherkin,
         handlers::scenario::create_from_gherkin,
         handlers::test_run::run_scenario_test,
@@ -89,6 +103,8 @@ impl Modify for SecurityAddon {
         handlers::report::get_report,
         handlers::report::get_report_detail,
         handlers::report::delete_report,
+        handlers::report::replay_response,
+        handlers::step::list_steps,
     ),
     components(schemas(
         RegisterRequest,
@@ -108,19 +124,41 @@ impl Modify for SecurityAddon {
         EnvironmentListResponse,
         EnvironmentResponse,
         UpdateEnvironmentRequest,
+        EnvironmentVariableRequest,
+        EnvironmentVariableResponse,
+        RequestAuth,
+        ApiKeyLocation,
+        ClientAuthMethod,
+        AuthBootstrap,
+        TransportSettings,
+        HttpVersion,
+        FixtureResponse,
+        FixtureListResponse,
         CreateApiRequest,
         ApiListResponse,
         ApiResponse,
         UpdateApiRequest,
+        RedirectPolicy,
         CreateScenarioRequest,
         ScenarioListResponse,
         ScenarioResponse,
         UpdateScenarioRequest,
+        ScenarioKind,
+        BodyMatch,
+        MatchMode,
+        EffectiveVariablesResponse,
+        ResolvedVariable,
+        VariableScope,
         ParseGherkinRequest,
         ParseGherkinResponse,
         BatchCreateResponse,
         RunTestRequest,
+        CookieJarScope,
         TestResultResponse,
+        SentRequest,
+        RequestTiming,
+        RedirectHop,
+        WorkflowStepResult,
         TestRunResponse,
         AsyncTestResponse,
         JobStatusResponse,
@@ -131,6 +169,14 @@ impl Modify for SecurityAddon {
         ReportListResponse,
         ReportDetailResponse,
         ResponseSummary,
+        ReplayRequest,
+        ReplayComparison,
+        ReplayResponse,
+        StepListResponse,
+        StepDefinitionResponse,
+        ParameterTypeResponse,
+        StepKind,
+        PatternSyntax,
     )),
     modifiers(&SecurityAddon),
     tags(
@@ -140,11 +186,13 @@ impl Modify for SecurityAddon {
         (name = "Projects", description = "Project management endpoints"),
         (name = "Collections", description = "Collection management endpoints"),
         (name = "Environments", description = "Environment management endpoints"),
+        (name = "Fixtures", description = "Project fixture files referenced from steps"),
         (name = "APIs", description = "API management endpoints"),
         (name = "Scenarios", description = "Scenario management endpoints with Gherkin support"),
         (name = "Test Execution", description = "Run tests against APIs"),
         (name = "Jobs", description = "Job queue management endpoints"),
-        (name = "Reports", description = "Test report management endpoints")
+        (name = "Reports", description = "Test report management endpoints"),
+        (name = "Steps", description = "Step definitions scenario steps are matched against")
     )
 )]
 struct ApiDoc;
@@ -1,8 +1,14 @@
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use time::OffsetDateTime;
+use utoipa::ToSchema;
 use uuid::Uuid;
 
+use crate::models::RequestAuth;
+
+/// Most redirects a policy may allow
+pub const MAX_REDIRECT_HOPS: u32 = 20;
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Api {
     pub id: Uuid,
@@ -12,6 +18,15 @@ pub struct Api {
     pub endpoint: String,
     pub severity: i16,
     pub description: Option<String>,
+    pub response_schema: Option<serde_json::Value>, // JSONB: JSON Schema (draft 2020-12)
+    pub sla_ms: Option<i32>,                        // default response time limit
+    pub request_content_type: Option<String>,       // request body encoding, JSON if unset
+    /// Authentication overriding the environment's; the secret is stored sealed
+    #[sqlx(json(nullable))]
+    pub auth: Option<RequestAuth>,
+    /// Whether redirects are followed; followed up to 10 hops if unset
+    #[sqlx(json(nullable))]
+    pub redirect_policy: Option<RedirectPolicy>,
     pub created_at: OffsetDateTime,
     pub updated_at: OffsetDateTime,
 }
@@ -23,6 +38,12 @@ pub struct CreateApi {
     pub endpoint: String,
     pub severity: Option<i16>,
     pub description: Option<String>,
+    pub response_schema: Option<serde_json::Value>,
+    pub sla_ms: Option<i32>,
+    pub request_content_type: Option<String>,
+    /// Plaintext auth, sealed by the repository
+    pub auth: Option<RequestAuth>,
+    pub redirect_policy: Option<RedirectPolicy>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -32,4 +53,74 @@ pub struct UpdateApi {
     pub endpoint: Option<String>,
     pub severity: Option<i16>,
     pub description: Option<String>,
+    pub response_schema: Option<serde_json::Value>,
+    pub sla_ms: Option<i32>,
+    pub request_content_type: Option<String>,
+    /// Replaces the auth when present; `Some(None)` inherits the environment's
+    pub auth: Option<Option<RequestAuth>>,
+    /// Replaces the redirect policy when present; `Some(None)` restores the default
+    pub redirect_policy: Option<Option<RedirectPolicy>>,
+}
+
+/// Whether redirect responses are followed, and how many in a row
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct RedirectPolicy {
+    /// Follow redirects; when false the redirect response itself is checked
+    #[serde(default = "default_follow")]
+    pub follow: bool,
+    /// Redirects followed before the request fails
+    #[serde(default = "default_max_hops")]
+    pub max_hops: u32,
+}
+
+impl Default for RedirectPolicy {
+    fn default() -> Self {
+        Self {
+            follow: default_follow(),
+            max_hops: default_max_hops(),
+        }
+    }
+}
+
+fn default_follow() -> bool {
+    true
+}
+
+fn default_max_hops() -> u32 {
+    10
+}
+
+/// How a request body is encoded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BodyEncoding {
+    #[default]
+    Json,
+    /// `application/x-www-form-urlencoded`
+    Form,
+    /// `multipart/form-data`, with `@name` values sent as fixture file parts
+    Multipart,
+}
+
+impl BodyEncoding {
+    /// Parse a MIME type or a short name such as `form` or `multipart`
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "json" | "application/json" => Some(Self::Json),
+            "form"
+            | "urlencoded"
+            | "form-urlencoded"
+            | "x-www-form-urlencoded"
+            | "application/x-www-form-urlencoded" => Some(Self::Form),
+            "multipart" | "multipart form" | "multipart/form-data" => Some(Self::Multipart),
+            _ => None,
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Json => "application/json",
+            Self::Form => "application/x-www-form-urlencoded",
+            Self::Multipart => "multipart/form-data",
+        }
+    }
 }
@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use std::collections::BTreeMap;
 use time::OffsetDateTime;
 use uuid::Uuid;
 
@@ -9,6 +10,9 @@ pub struct Collection {
     pub project_id: Uuid,
     pub name: String,
     pub description: Option<String>,
+    /// Variables overriding project variables for the collection's scenarios
+    #[sqlx(json)]
+    pub variables: BTreeMap<String, String>,
     pub created_at: OffsetDateTime,
     pub updated_at: OffsetDateTime,
 }
@@ -17,10 +21,13 @@ pub struct Collection {
 pub struct CreateCollection {
     pub name: String,
     pub description: Option<String>,
+    pub variables: BTreeMap<String, String>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct UpdateCollection {
     pub name: Option<String>,
     pub description: Option<String>,
+    /// Replaces all variables when present
+    pub variables: Option<BTreeMap<String, String>>,
 }
@@ -1,26 +1,76 @@
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use std::collections::BTreeMap;
 use time::OffsetDateTime;
 use uuid::Uuid;
 
+use crate::models::{AuthBootstrap, RequestAuth, Scenario, TransportSettings};
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Environment {
     pub id: Uuid,
     pub project_id: Uuid,
     pub title: String,       // e.g., "dev", "staging", "production"
     pub domain_name: String, // base URL for this environment
+    /// Variables available to steps as `<name>`; secret values are stored sealed
+    #[sqlx(json)]
+    pub variables: BTreeMap<String, EnvironmentVariable>,
+    /// Authentication for test requests; the secret is stored sealed
+    #[sqlx(json(nullable))]
+    pub auth: Option<RequestAuth>,
+    /// Scenario providing a token for requests that use the environment's auth
+    #[sqlx(json(nullable))]
+    pub auth_bootstrap: Option<AuthBootstrap>,
+    /// TLS, proxy and DNS settings; the client key and proxy password are
+    /// stored sealed
+    #[sqlx(json(nullable))]
+    pub transport: Option<TransportSettings>,
     pub created_at: OffsetDateTime,
     pub updated_at: OffsetDateTime,
 }
 
+impl Environment {
+    /// Whether the scenario is the auth bootstrap, which runs once at the
+    /// start of a run rather than among the scenarios of an API or collection
+    pub fn is_bootstrap(&self, scenario: &Scenario) -> bool {
+        self.auth_bootstrap
+            .as_ref()
+            .is_some_and(|bootstrap| bootstrap.scenario_id == scenario.id)
+    }
+}
+
+/// A per-environment value such as a tenant ID or an API key
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EnvironmentVariable {
+    pub value: String,
+    /// Encrypted at rest and masked in responses and reports
+    #[serde(default)]
+    pub secret: bool,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct CreateEnvironment {
     pub title: String,
     pub domain_name: String,
+    /// Plaintext variables, sealed by the repository
+    pub variables: BTreeMap<String, EnvironmentVariable>,
+    /// Plaintext auth, sealed by the repository
+    pub auth: Option<RequestAuth>,
+    pub auth_bootstrap: Option<AuthBootstrap>,
+    /// Plaintext transport settings, sealed by the repository
+    pub transport: Option<TransportSettings>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct UpdateEnvironment {
     pub title: Option<String>,
     pub domain_name: Option<String>,
+    /// Replaces all variables when present
+    pub variables: Option<BTreeMap<String, EnvironmentVariable>>,
+    /// Replaces the auth when present; `Some(None)` removes it
+    pub auth: Option<Option<RequestAuth>>,
+    /// Replaces the auth bootstrap when present; `Some(None)` removes it
+    pub auth_bootstrap: Option<Option<AuthBootstrap>>,
+    /// Replaces the transport settings when present; `Some(None)` removes them
+    pub transport: Option<Option<TransportSettings>>,
 }
@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+/// File stored in a project's fixture store, referenced from steps by name
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Fixture {
+    pub name: String,
+    pub content_type: Option<String>,
+    pub size: i64,
+    pub sha256: String,
+    pub uploaded_at: OffsetDateTime,
+}
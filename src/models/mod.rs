@@ -1,19 +1,25 @@
 pub mod api;
 pub mod collection;
 pub mod environment;
+pub mod fixture;
 pub mod project;
 pub mod refresh_token;
 pub mod report;
+pub mod request_auth;
 pub mod response;
 pub mod scenario;
+pub mod transport;
 pub mod user;
 
 pub use api::*;
 pub use collection::*;
 pub use environment::*;
+pub use fixture::*;
 pub use project::*;
 pub use refresh_token::*;
 pub use report::*;
+pub use request_auth::*;
 pub use response::*;
 pub use scenario::*;
+pub use transport::*;
 pub use user::*;
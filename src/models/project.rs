@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use std::collections::BTreeMap;
 use time::OffsetDateTime;
 use uuid::Uuid;
 
@@ -9,6 +10,9 @@ pub struct Project {
     pub user_id: Uuid,
     pub name: String,
     pub description: Option<String>,
+    /// Variables available to every scenario in the project as `<name>`
+    #[sqlx(json)]
+    pub variables: BTreeMap<String, String>,
     pub created_at: OffsetDateTime,
     pub updated_at: OffsetDateTime,
 }
@@ -17,10 +21,13 @@ pub struct Project {
 pub struct CreateProject {
     pub name: String,
     pub description: Option<String>,
+    pub variables: BTreeMap<String, String>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct UpdateProject {
     pub name: Option<String>,
     pub description: Option<String>,
+    /// Replaces all variables when present
+    pub variables: Option<BTreeMap<String, String>>,
 }
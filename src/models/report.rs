@@ -22,6 +22,8 @@ pub struct Report {
     pub calculated: bool,
     pub pass_rate: Option<Decimal>, // percentage (0.00 - 100.00)
     pub response_count: i32,
+    /// Seed of the run's generated values; absent when not created by a run
+    pub seed: Option<i64>,
 
     pub created_at: OffsetDateTime,
     pub finished_at: Option<OffsetDateTime>,
@@ -33,6 +35,7 @@ pub struct CreateReport {
     pub collection_id: Option<Uuid>,
     pub report_level: i16,
     pub report_type: Option<String>,
+    pub seed: Option<i64>,
 }
 
 /// Report summary for list view
@@ -0,0 +1,136 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// How test requests authenticate against the target API. Set on an
+/// environment, and optionally overridden per API.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RequestAuth {
+    /// Send no credentials, e.g. for a public API in an authenticated environment
+    None,
+    /// `Authorization: Basic base64(username:password)`
+    Basic { username: String, password: String },
+    /// A static key in a header or query parameter
+    ApiKey {
+        name: String,
+        value: String,
+        #[serde(default)]
+        location: ApiKeyLocation,
+    },
+    /// Bearer token from an OAuth2 client credentials grant, cached for the
+    /// run and renewed when it expires
+    #[serde(rename = "oauth2_client_credentials")]
+    OAuth2ClientCredentials {
+        token_url: String,
+        client_id: String,
+        client_secret: String,
+        #[serde(default)]
+        scope: Option<String>,
+        /// How the client authenticates to the token endpoint
+        #[serde(default)]
+        client_auth: ClientAuthMethod,
+    },
+    /// HMAC-SHA256 signature of `METHOD\nPATH?QUERY\nTIMESTAMP\nSHA256(BODY)`,
+    /// sent hex-encoded, prefixed by `key_id:` when a key ID is set
+    Hmac {
+        secret: String,
+        #[serde(default)]
+        key_id: Option<String>,
+        #[serde(default = "default_signature_header")]
+        signature_header: String,
+        /// Header carrying the Unix timestamp that was signed
+        #[serde(default = "default_timestamp_header")]
+        timestamp_header: String,
+    },
+}
+
+/// Where an API key is sent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiKeyLocation {
+    #[default]
+    Header,
+    Query,
+}
+
+/// Client authentication at an OAuth2 token endpoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ClientAuthMethod {
+    /// HTTP Basic with the client ID and secret
+    #[default]
+    Basic,
+    /// `client_id` and `client_secret` form fields
+    Body,
+}
+
+/// A scenario run once at the start of every run against an environment. The
+/// token found in its response is sent with the requests that follow, in place
+/// of the environment's auth.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct AuthBootstrap {
+    /// Scenario that logs in; its first example is run
+    pub scenario_id: Uuid,
+    /// JSONPath to the token in the response body
+    #[serde(default = "default_token_path")]
+    pub token_path: String,
+    /// Header the token is sent in
+    #[serde(default = "default_token_header")]
+    pub header: String,
+    /// Text put before the token in the header value
+    #[serde(default = "default_token_prefix")]
+    pub prefix: String,
+}
+
+fn default_token_path() -> String {
+    "$.token".to_string()
+}
+
+fn default_token_header() -> String {
+    "Authorization".to_string()
+}
+
+fn default_token_prefix() -> String {
+    "Bearer ".to_string()
+}
+
+fn default_signature_header() -> String {
+    "X-Signature".to_string()
+}
+
+fn default_timestamp_header() -> String {
+    "X-Timestamp".to_string()
+}
+
+impl RequestAuth {
+    /// The credential that is encrypted at rest and masked in responses
+    pub fn secret(&self) -> Option<&str> {
+        match self {
+            Self::None => None,
+            Self::Basic { password, .. } => Some(password),
+            Self::ApiKey { value, .. } => Some(value),
+            Self::OAuth2ClientCredentials { client_secret, .. } => Some(client_secret),
+            Self::Hmac { secret, .. } => Some(secret),
+        }
+    }
+
+    pub fn secret_mut(&mut self) -> Option<&mut String> {
+        match self {
+            Self::None => None,
+            Self::Basic { password, .. } => Some(password),
+            Self::ApiKey { value, .. } => Some(value),
+            Self::OAuth2ClientCredentials { client_secret, .. } => Some(client_secret),
+            Self::Hmac { secret, .. } => Some(secret),
+        }
+    }
+
+    /// A copy with the secret replaced by `masked`
+    pub fn masked(&self, masked: &str) -> Self {
+        let mut auth = self.clone();
+        if let Some(secret) = auth.secret_mut() {
+            *secret = masked.to_string();
+        }
+        auth
+    }
+}
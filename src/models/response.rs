@@ -11,8 +11,12 @@ pub struct Response {
     pub scenario_id: Uuid,
     pub example_index: i32,
 
+    // Request sent, secrets redacted
+    pub request_data: Option<serde_json::Value>, // JSONB: SentRequest
+
     // Actual response data
     pub response_data: Option<serde_json::Value>,
+    pub response_headers: Option<serde_json::Value>, // JSONB: { name: [values] }
     pub response_status: i16,
 
     // Test result
@@ -22,6 +26,9 @@ pub struct Response {
     // Timing
     pub request_time: OffsetDateTime,
     pub request_duration_ms: Option<i32>,
+    pub timing: Option<serde_json::Value>, // JSONB: RequestTiming
+    pub redirects: Option<serde_json::Value>, // JSONB: [RedirectHop]
+    pub workflow_steps: Option<serde_json::Value>, // JSONB: [WorkflowStepResult]
 }
 
 #[derive(Debug, Deserialize)]
@@ -29,9 +36,14 @@ pub struct CreateResponse {
     pub api_id: Uuid,
     pub scenario_id: Uuid,
     pub example_index: i32,
+    pub request_data: Option<serde_json::Value>,
     pub response_data: Option<serde_json::Value>,
+    pub response_headers: Option<serde_json::Value>,
     pub response_status: i16,
     pub pass: bool,
     pub error_message: Option<String>,
     pub request_duration_ms: Option<i32>,
+    pub timing: Option<serde_json::Value>,
+    pub redirects: Option<serde_json::Value>,
+    pub workflow_steps: Option<serde_json::Value>,
 }
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use std::collections::BTreeMap;
 use time::OffsetDateTime;
+use utoipa::ToSchema;
 use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -10,12 +12,80 @@ pub struct Scenario {
     pub title: String,
     pub description: Option<String>,
     pub tags: Vec<String>,
-    pub steps: serde_json::Value, // JSONB: [{keyword, keywordType, text}]
+    pub kind: ScenarioKind,
+    pub background_steps: serde_json::Value, // JSONB: steps run before `steps`, same shape
+    pub steps: serde_json::Value,            // JSONB: [{keyword, keywordType, text}]
     pub examples: serde_json::Value, // JSONB: [{example, expected_response_body, expected_status_code}]
+    pub response_schema: Option<serde_json::Value>, // JSONB: overrides the API schema
+    /// How expected bodies are compared, for examples that don't set their own
+    #[sqlx(json(nullable))]
+    pub body_match: Option<BodyMatch>,
+    /// Variables overriding project, collection and environment variables
+    #[sqlx(json)]
+    pub variables: BTreeMap<String, String>,
     pub created_at: OffsetDateTime,
     pub updated_at: OffsetDateTime,
 }
 
+/// Whether a scenario calls its own API or several APIs of its project
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, ToSchema, sqlx::Type,
+)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
+pub enum ScenarioKind {
+    /// Every example sends one request to the scenario's API
+    #[default]
+    Single,
+    /// Every example sends one request per `call "<API>"` step, in order,
+    /// and passes when all of them do
+    Workflow,
+}
+
+impl ScenarioKind {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "single" => Some(Self::Single),
+            "workflow" => Some(Self::Workflow),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Single => "single",
+            Self::Workflow => "workflow",
+        }
+    }
+}
+
+/// How an expected response body is compared with the response
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct BodyMatch {
+    #[serde(default)]
+    pub mode: MatchMode,
+    /// Paths left out of the comparison, such as `$.id` or
+    /// `$.items[*].created_at`; a bare field name is left out at any depth
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ignore_paths: Vec<String>,
+}
+
+/// How strictly objects and arrays of an expected body must match
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchMode {
+    /// Extra fields are allowed and each expected array item matches some item
+    #[default]
+    Subset,
+    /// No extra fields, and arrays match item by item
+    Exact,
+    /// Extra fields are allowed and expected array items appear in order
+    Ordered,
+    /// Extra fields are allowed and arrays have exactly the expected items,
+    /// in any order
+    ExactLength,
+}
+
 /// Gherkin step structure with optional doc string and data table
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GherkinStep {
@@ -28,6 +98,9 @@ pub struct GherkinStep {
     /// Data table embedded in the step
     #[serde(skip_serializing_if = "Option::is_none")]
     pub data_table: Option<Vec<serde_json::Value>>,
+    /// Cells of the data table as written, header row first
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data_table_cells: Option<Vec<Vec<String>>>,
 }
 
 /// Test example with expected results
@@ -36,6 +109,9 @@ pub struct TestExample {
     pub example: serde_json::Value, // dynamic test data
     pub expected_response_body: serde_json::Value,
     pub expected_status_code: i16,
+    /// Overrides the scenario's body matching for this example
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body_match: Option<BodyMatch>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -43,8 +119,14 @@ pub struct CreateScenario {
     pub title: String,
     pub description: Option<String>,
     pub tags: Option<Vec<String>>,
+    pub kind: ScenarioKind,
+    /// Steps of the feature's Background, run before `steps`
+    pub background_steps: Vec<GherkinStep>,
     pub steps: Vec<GherkinStep>,
     pub examples: Vec<TestExample>,
+    pub response_schema: Option<serde_json::Value>,
+    pub body_match: Option<BodyMatch>,
+    pub variables: BTreeMap<String, String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -52,6 +134,14 @@ pub struct UpdateScenario {
     pub title: Option<String>,
     pub description: Option<String>,
     pub tags: Option<Vec<String>>,
+    pub kind: Option<ScenarioKind>,
+    pub background_steps: Option<Vec<GherkinStep>>,
     pub steps: Option<Vec<GherkinStep>>,
     pub examples: Option<Vec<TestExample>>,
+    /// Replaces the schema when present; `Some(None)` falls back to the API's
+    pub response_schema: Option<Option<serde_json::Value>>,
+    /// Replaces the body matching when present; `Some(None)` restores subset matching
+    pub body_match: Option<Option<BodyMatch>>,
+    /// Replaces all variables when present
+    pub variables: Option<BTreeMap<String, String>>,
 }
@@ -0,0 +1,63 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use utoipa::ToSchema;
+
+/// TLS, proxy and name resolution settings for test requests against an
+/// environment
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct TransportSettings {
+    /// PEM certificates trusted in addition to the system roots, e.g. an
+    /// internal CA
+    #[serde(default)]
+    pub ca_bundle: Option<String>,
+    /// PEM client certificate (chain) for mutual TLS
+    #[serde(default)]
+    pub client_cert: Option<String>,
+    /// PEM private key of the client certificate
+    #[serde(default)]
+    pub client_key: Option<String>,
+    /// Accept any server certificate and hostname. Only for test hosts.
+    #[serde(default)]
+    pub insecure_skip_verify: bool,
+    /// Proxy for every request, e.g. `http://proxy.corp.example:3128`
+    #[serde(default)]
+    pub proxy_url: Option<String>,
+    #[serde(default)]
+    pub proxy_username: Option<String>,
+    #[serde(default)]
+    pub proxy_password: Option<String>,
+    /// Hosts resolved to a fixed IP address instead of through DNS
+    #[serde(default)]
+    pub dns_overrides: BTreeMap<String, String>,
+    #[serde(default)]
+    pub http_version: HttpVersion,
+}
+
+/// HTTP version used for test requests
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum HttpVersion {
+    /// HTTP/2 when the server offers it over TLS, else HTTP/1.1
+    #[default]
+    Auto,
+    /// HTTP/1.1 only
+    Http1,
+    /// HTTP/2 without negotiation, including over plain HTTP
+    Http2,
+}
+
+impl TransportSettings {
+    /// The secrets encrypted at rest and masked in responses
+    pub fn secrets_mut(&mut self) -> [&mut Option<String>; 2] {
+        [&mut self.client_key, &mut self.proxy_password]
+    }
+
+    /// A copy with the secrets replaced by `masked`
+    pub fn masked(&self, masked: &str) -> Self {
+        let mut settings = self.clone();
+        for secret in settings.secrets_mut().into_iter().flatten() {
+            *secret = masked.to_string();
+        }
+        settings
+    }
+}
@@ -3,6 +3,8 @@ use std::collections::HashMap;
 use time::OffsetDateTime;
 use uuid::Uuid;
 
+use crate::services::CookieJarScope;
+
 /// Job status enum
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -66,6 +68,13 @@ pub struct TestJobConfig {
     pub auth_token: Option<String>,
     /// Custom headers
     pub custom_headers: HashMap<String, String>,
+    /// Seed for generated values, chosen when the job is queued so a requeued
+    /// job reproduces them; absent in jobs queued before seeds existed
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// How long cookies set by responses are kept
+    #[serde(default)]
+    pub cookie_jar: CookieJarScope,
 }
 
 impl Default for TestJobConfig {
@@ -74,6 +83,8 @@ impl Default for TestJobConfig {
             timeout_seconds: 30,
             auth_token: None,
             custom_headers: HashMap::new(),
+            seed: None,
+            cookie_jar: CookieJarScope::default(),
         }
     }
 }
@@ -208,6 +219,8 @@ mod tests {
                 timeout_seconds: 60,
                 auth_token: Some("token123".to_string()),
                 custom_headers: HashMap::new(),
+                seed: Some(42),
+                cookie_jar: CookieJarScope::Run,
             },
         );
 
@@ -217,5 +230,7 @@ mod tests {
         assert_eq!(deserialized.id, job.id);
         assert_eq!(deserialized.job_type, TestJobType::Api);
         assert_eq!(deserialized.config.timeout_seconds, 60);
+        assert_eq!(deserialized.config.seed, Some(42));
+        assert_eq!(deserialized.config.cookie_jar, CookieJarScope::Run);
     }
 }
@@ -224,7 +224,7 @@ impl JobQueue for RedisQueue {
         }
 
         // Sort by created_at descending
-        jobs.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        jobs.sort_by_key(|j| std::cmp::Reverse(j.created_at));
 
         Ok(jobs)
     }
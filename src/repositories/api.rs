@@ -6,10 +6,15 @@ use sea_orm::{
 use uuid::Uuid;
 
 use crate::entity::api::{self, ActiveModel, Column, Entity as ApiEntity};
+use crate::entity::collection::{Column as CollectionColumn, Entity as CollectionEntity};
 use crate::error::{AppError, AppResult};
 use crate::models::{Api, CreateApi, UpdateApi};
 use crate::repositories::ownership::OwnershipVerifier;
-use crate::repositories::Repository;
+use crate::repositories::request_auth::{auth_from_json, auth_to_json};
+use crate::repositories::{
+    from_json, to_json, EnvironmentRepository, Repository, ScenarioRepository,
+};
+use crate::services::secrets::SecretCipher;
 
 /// API repository for database operations
 pub struct ApiRepository;
@@ -56,6 +61,7 @@ impl ApiRepository {
     /// Create a new API
     pub async fn create(
         db: &DatabaseConnection,
+        cipher: &SecretCipher,
         collection_id: Uuid,
         user_id: Uuid,
         input: &CreateApi,
@@ -63,6 +69,12 @@ impl ApiRepository {
         // Verify collection ownership (which verifies project -> user)
         OwnershipVerifier::verify_collection(db, collection_id, user_id).await?;
 
+        let auth = input
+            .auth
+            .as_ref()
+            .map(|auth| cipher.seal_auth(auth, None))
+            .transpose()?;
+
         let model = ActiveModel {
             id: Set(Uuid::new_v4()),
             collection_id: Set(collection_id),
@@ -71,6 +83,11 @@ impl ApiRepository {
             endpoint: Set(input.endpoint.clone()),
             severity: Set(input.severity.unwrap_or(1)),
             description: Set(input.description.clone()),
+            response_schema: Set(input.response_schema.clone()),
+            sla_ms: Set(input.sla_ms),
+            request_content_type: Set(input.request_content_type.clone()),
+            auth: Set(auth_to_json(auth.as_ref())?),
+            redirect_policy: Set(to_json(input.redirect_policy.as_ref())?),
             created_at: Set(time::OffsetDateTime::now_utc()),
             updated_at: Set(time::OffsetDateTime::now_utc()),
         };
@@ -118,6 +135,18 @@ impl ApiRepository {
         Ok(models.into_iter().map(|m| m.into()).collect())
     }
 
+    /// List every API of a project's collections, oldest first
+    pub async fn list_by_project(db: &DatabaseConnection, project_id: Uuid) -> AppResult<Vec<Api>> {
+        let models = ApiEntity::find()
+            .inner_join(CollectionEntity)
+            .filter(CollectionColumn::ProjectId.eq(project_id))
+            .order_by_asc(Column::CreatedAt)
+            .all(db)
+            .await?;
+
+        Ok(models.into_iter().map(|m| m.into()).collect())
+    }
+
     /// Count APIs for a specific collection
     pub async fn count_by_collection(
         db: &DatabaseConnection,
@@ -138,6 +167,7 @@ impl ApiRepository {
     /// Update API (with ownership check)
     pub async fn update(
         db: &DatabaseConnection,
+        cipher: &SecretCipher,
         id: Uuid,
         user_id: Uuid,
         input: &UpdateApi,
@@ -150,6 +180,7 @@ impl ApiRepository {
         // Verify collection ownership
         OwnershipVerifier::verify_collection(db, model.collection_id, user_id).await?;
 
+        let existing_auth = auth_from_json(model.auth.clone());
         let mut active: ActiveModel = model.into();
 
         if let Some(name) = &input.name {
@@ -167,6 +198,25 @@ impl ApiRepository {
         if let Some(description) = &input.description {
             active.description = Set(Some(description.clone()));
         }
+        if let Some(response_schema) = &input.response_schema {
+            active.response_schema = Set(Some(response_schema.clone()));
+        }
+        if let Some(sla_ms) = input.sla_ms {
+            active.sla_ms = Set(Some(sla_ms));
+        }
+        if let Some(request_content_type) = &input.request_content_type {
+            active.request_content_type = Set(Some(request_content_type.clone()));
+        }
+        if let Some(auth) = &input.auth {
+            let auth = auth
+                .as_ref()
+                .map(|auth| cipher.seal_auth(auth, existing_auth.as_ref()))
+                .transpose()?;
+            active.auth = Set(auth_to_json(auth.as_ref())?);
+        }
+        if let Some(redirect_policy) = &input.redirect_policy {
+            active.redirect_policy = Set(to_json(redirect_policy.as_ref())?);
+        }
         active.updated_at = Set(time::OffsetDateTime::now_utc());
 
         let result = active.update(db).await?;
@@ -182,6 +232,8 @@ impl ApiRepository {
 
         // Verify collection ownership
         OwnershipVerifier::verify_collection(db, model.collection_id, user_id).await?;
+        let scenario_ids = ScenarioRepository::ids_by_api(db, id).await?;
+        EnvironmentRepository::ensure_not_bootstrap(db, &scenario_ids).await?;
 
         let active: ActiveModel = model.into();
         active.delete(db).await?;
@@ -201,6 +253,11 @@ impl From<api::Model> for Api {
             endpoint: m.endpoint,
             severity: m.severity,
             description: m.description,
+            response_schema: m.response_schema,
+            sla_ms: m.sla_ms,
+            request_content_type: m.request_content_type,
+            auth: auth_from_json(m.auth),
+            redirect_policy: from_json(m.redirect_policy),
             created_at: m.created_at,
             updated_at: m.updated_at,
         }
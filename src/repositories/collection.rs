@@ -9,7 +9,7 @@ use crate::entity::collection::{self, ActiveModel, Column, Entity as CollectionE
 use crate::error::{AppError, AppResult};
 use crate::models::{Collection, CreateCollection, UpdateCollection};
 use crate::repositories::ownership::OwnershipVerifier;
-use crate::repositories::Repository;
+use crate::repositories::{EnvironmentRepository, Repository, ScenarioRepository};
 
 /// Collection repository for database operations
 pub struct CollectionRepository;
@@ -68,6 +68,7 @@ impl CollectionRepository {
             project_id: Set(project_id),
             name: Set(input.name.clone()),
             description: Set(input.description.clone()),
+            variables: Set(serde_json::json!(input.variables)),
             created_at: Set(time::OffsetDateTime::now_utc()),
             updated_at: Set(time::OffsetDateTime::now_utc()),
         };
@@ -155,6 +156,9 @@ impl CollectionRepository {
         if let Some(description) = &input.description {
             active.description = Set(Some(description.clone()));
         }
+        if let Some(variables) = &input.variables {
+            active.variables = Set(serde_json::json!(variables));
+        }
         active.updated_at = Set(time::OffsetDateTime::now_utc());
 
         let result = active.update(db).await?;
@@ -170,6 +174,8 @@ impl CollectionRepository {
 
         // Verify project ownership
         OwnershipVerifier::verify_project(db, model.project_id, user_id).await?;
+        let scenario_ids = ScenarioRepository::ids_by_collection(db, id).await?;
+        EnvironmentRepository::ensure_not_bootstrap(db, &scenario_ids).await?;
 
         let active: ActiveModel = model.into();
         active.delete(db).await?;
@@ -186,6 +192,7 @@ impl From<collection::Model> for Collection {
             project_id: m.project_id,
             name: m.name,
             description: m.description,
+            variables: serde_json::from_value(m.variables).unwrap_or_default(),
             created_at: m.created_at,
             updated_at: m.updated_at,
         }
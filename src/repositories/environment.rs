@@ -1,15 +1,21 @@
 use async_trait::async_trait;
+use sea_orm::sea_query::Expr;
 use sea_orm::{
     ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter,
     QueryOrder, QuerySelect, Set,
 };
+use std::collections::BTreeMap;
 use uuid::Uuid;
 
 use crate::entity::environment::{self, ActiveModel, Column, Entity as EnvironmentEntity};
 use crate::error::{AppError, AppResult};
-use crate::models::{CreateEnvironment, Environment, UpdateEnvironment};
+use crate::models::{
+    CreateEnvironment, Environment, EnvironmentVariable, TransportSettings, UpdateEnvironment,
+};
 use crate::repositories::ownership::OwnershipVerifier;
-use crate::repositories::Repository;
+use crate::repositories::request_auth::{auth_from_json, auth_to_json};
+use crate::repositories::{from_json, to_json, Repository};
+use crate::services::secrets::SecretCipher;
 
 /// Environment repository for database operations
 pub struct EnvironmentRepository;
@@ -53,9 +59,10 @@ impl Repository<Environment> for EnvironmentRepository {
 }
 
 impl EnvironmentRepository {
-    /// Create a new environment
+    /// Create a new environment, encrypting secret variables with `cipher`
     pub async fn create(
         db: &DatabaseConnection,
+        cipher: &SecretCipher,
         project_id: Uuid,
         user_id: Uuid,
         input: &CreateEnvironment,
@@ -63,11 +70,27 @@ impl EnvironmentRepository {
         // Verify project ownership
         OwnershipVerifier::verify_project(db, project_id, user_id).await?;
 
+        let variables = cipher.seal_variables(&input.variables, &Default::default())?;
+        let auth = input
+            .auth
+            .as_ref()
+            .map(|auth| cipher.seal_auth(auth, None))
+            .transpose()?;
+        let transport = input
+            .transport
+            .as_ref()
+            .map(|transport| cipher.seal_transport(transport, None))
+            .transpose()?;
+
         let model = ActiveModel {
             id: Set(Uuid::new_v4()),
             project_id: Set(project_id),
             title: Set(input.title.clone()),
             domain_name: Set(input.domain_name.clone()),
+            variables: Set(variables_to_json(&variables)?),
+            auth: Set(auth_to_json(auth.as_ref())?),
+            auth_bootstrap: Set(to_json(input.auth_bootstrap.as_ref())?),
+            transport: Set(to_json(transport.as_ref())?),
             created_at: Set(time::OffsetDateTime::now_utc()),
             updated_at: Set(time::OffsetDateTime::now_utc()),
         };
@@ -132,9 +155,11 @@ impl EnvironmentRepository {
         Ok(count)
     }
 
-    /// Update environment (with ownership check)
+    /// Update environment (with ownership check). Secret variables and auth
+    /// secrets sent back masked keep their stored value.
     pub async fn update(
         db: &DatabaseConnection,
+        cipher: &SecretCipher,
         id: Uuid,
         user_id: Uuid,
         input: &UpdateEnvironment,
@@ -147,6 +172,9 @@ impl EnvironmentRepository {
         // Verify project ownership
         OwnershipVerifier::verify_project(db, model.project_id, user_id).await?;
 
+        let existing = variables_from_json(model.variables.clone());
+        let existing_auth = auth_from_json(model.auth.clone());
+        let existing_transport = from_json::<TransportSettings>(model.transport.clone());
         let mut active: ActiveModel = model.into();
 
         if let Some(title) = &input.title {
@@ -155,12 +183,58 @@ impl EnvironmentRepository {
         if let Some(domain_name) = &input.domain_name {
             active.domain_name = Set(domain_name.clone());
         }
+        if let Some(variables) = &input.variables {
+            let variables = cipher.seal_variables(variables, &existing)?;
+            active.variables = Set(variables_to_json(&variables)?);
+        }
+        if let Some(auth) = &input.auth {
+            let auth = auth
+                .as_ref()
+                .map(|auth| cipher.seal_auth(auth, existing_auth.as_ref()))
+                .transpose()?;
+            active.auth = Set(auth_to_json(auth.as_ref())?);
+        }
+        if let Some(bootstrap) = &input.auth_bootstrap {
+            active.auth_bootstrap = Set(to_json(bootstrap.as_ref())?);
+        }
+        if let Some(transport) = &input.transport {
+            let transport = transport
+                .as_ref()
+                .map(|transport| cipher.seal_transport(transport, existing_transport.as_ref()))
+                .transpose()?;
+            active.transport = Set(to_json(transport.as_ref())?);
+        }
         active.updated_at = Set(time::OffsetDateTime::now_utc());
 
         let result = active.update(db).await?;
         Ok(result.into())
     }
 
+    /// Refuse to delete scenarios that an environment's auth bootstrap runs
+    pub async fn ensure_not_bootstrap(
+        db: &DatabaseConnection,
+        scenario_ids: &[Uuid],
+    ) -> AppResult<()> {
+        if scenario_ids.is_empty() {
+            return Ok(());
+        }
+
+        let environment = EnvironmentEntity::find()
+            .filter(
+                Expr::expr(Expr::cust("auth_bootstrap->>'scenario_id'"))
+                    .is_in(scenario_ids.iter().map(Uuid::to_string)),
+            )
+            .one(db)
+            .await?;
+        match environment {
+            Some(environment) => Err(AppError::Conflict(format!(
+                "Scenario is the auth bootstrap of environment \"{}\"; remove it there first",
+                environment.title
+            ))),
+            None => Ok(()),
+        }
+    }
+
     /// Delete environment (with ownership check)
     pub async fn delete_by_user(db: &DatabaseConnection, id: Uuid, user_id: Uuid) -> AppResult<()> {
         let model = EnvironmentEntity::find_by_id(id)
@@ -186,8 +260,22 @@ impl From<environment::Model> for Environment {
             project_id: m.project_id,
             title: m.title,
             domain_name: m.domain_name,
+            variables: variables_from_json(m.variables),
+            auth: auth_from_json(m.auth),
+            auth_bootstrap: from_json(m.auth_bootstrap),
+            transport: from_json(m.transport),
             created_at: m.created_at,
             updated_at: m.updated_at,
         }
     }
 }
+
+fn variables_to_json(
+    variables: &BTreeMap<String, EnvironmentVariable>,
+) -> AppResult<serde_json::Value> {
+    serde_json::to_value(variables).map_err(|e| AppError::Internal(e.to_string()))
+}
+
+fn variables_from_json(value: serde_json::Value) -> BTreeMap<String, EnvironmentVariable> {
+    serde_json::from_value(value).unwrap_or_default()
+}
@@ -0,0 +1,191 @@
+use std::collections::{HashMap, HashSet};
+
+use bson::{doc, Bson};
+use futures::io::{AsyncReadExt, AsyncWriteExt};
+use futures::TryStreamExt;
+use mongodb::gridfs::{FilesCollectionDocument, GridFsBucket};
+use mongodb::options::GridFsBucketOptions;
+use mongodb::Database;
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::error::{AppError, AppResult};
+use crate::models::Fixture;
+use crate::services::FixtureFile;
+
+/// GridFS bucket holding fixture files, next to `gherkin_documents`
+const BUCKET_NAME: &str = "fixtures";
+
+/// Project fixture files stored in MongoDB GridFS.
+///
+/// Each file carries `project_id`, `content_type` and `sha256` in its
+/// metadata; names are unique within a project.
+pub struct FixtureRepository;
+
+impl FixtureRepository {
+    /// List a project's fixtures ordered by name
+    pub async fn list_by_project(db: &Database, project_id: Uuid) -> AppResult<Vec<Fixture>> {
+        let files =
+            Self::find_files(db, doc! { "metadata.project_id": project_id.to_string() }).await?;
+
+        let mut fixtures: Vec<Fixture> = files.iter().map(Self::to_fixture).collect();
+        fixtures.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(fixtures)
+    }
+
+    /// Store a fixture, replacing any existing fixture with the same name
+    pub async fn upload(
+        db: &Database,
+        project_id: Uuid,
+        name: &str,
+        content_type: Option<String>,
+        bytes: &[u8],
+    ) -> AppResult<Fixture> {
+        let bucket = Self::bucket(db);
+        let previous = Self::find_files(db, Self::name_filter(project_id, name)).await?;
+
+        let metadata = doc! {
+            "project_id": project_id.to_string(),
+            "content_type": content_type.clone(),
+            "sha256": hex::encode(Sha256::digest(bytes)),
+        };
+        let mut stream = bucket
+            .open_upload_stream(name)
+            .metadata(metadata)
+            .await
+            .map_err(|e| AppError::Database(format!("MongoDB GridFS error: {}", e)))?;
+        stream
+            .write_all(bytes)
+            .await
+            .map_err(|e| AppError::Database(format!("MongoDB GridFS write error: {}", e)))?;
+        stream
+            .close()
+            .await
+            .map_err(|e| AppError::Database(format!("MongoDB GridFS write error: {}", e)))?;
+
+        // The old revision is only removed once the new one is complete
+        for file in previous {
+            Self::delete_file(&bucket, file.id).await?;
+        }
+
+        let file = bucket
+            .find_one(doc! { "_id": stream.id().clone() })
+            .await
+            .map_err(|e| AppError::Database(format!("MongoDB GridFS error: {}", e)))?
+            .ok_or_else(|| AppError::Internal("Uploaded fixture not found".to_string()))?;
+
+        Ok(Self::to_fixture(&file))
+    }
+
+    /// Delete a fixture by name
+    pub async fn delete(db: &Database, project_id: Uuid, name: &str) -> AppResult<()> {
+        let files = Self::find_files(db, Self::name_filter(project_id, name)).await?;
+        if files.is_empty() {
+            return Err(AppError::NotFound("Fixture".to_string()));
+        }
+
+        let bucket = Self::bucket(db);
+        for file in files {
+            Self::delete_file(&bucket, file.id).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Load the fixtures of a project with one of `names` for a test run,
+    /// keyed by name; names without a fixture are skipped
+    pub async fn load_named(
+        db: &Database,
+        project_id: Uuid,
+        names: &HashSet<String>,
+    ) -> AppResult<HashMap<String, FixtureFile>> {
+        if names.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let bucket = Self::bucket(db);
+        let names: Vec<&str> = names.iter().map(String::as_str).collect();
+        let files = Self::find_files(
+            db,
+            doc! {
+                "metadata.project_id": project_id.to_string(),
+                "filename": { "$in": names },
+            },
+        )
+        .await?;
+
+        let mut fixtures = HashMap::new();
+        for file in files {
+            let fixture = Self::to_fixture(&file);
+
+            let mut stream = bucket
+                .open_download_stream(file.id)
+                .await
+                .map_err(|e| AppError::Database(format!("MongoDB GridFS error: {}", e)))?;
+            let mut bytes = Vec::with_capacity(file.length as usize);
+            stream
+                .read_to_end(&mut bytes)
+                .await
+                .map_err(|e| AppError::Database(format!("MongoDB GridFS read error: {}", e)))?;
+
+            fixtures.insert(
+                fixture.name.clone(),
+                FixtureFile {
+                    file_name: fixture.name,
+                    content_type: fixture.content_type,
+                    bytes,
+                },
+            );
+        }
+
+        Ok(fixtures)
+    }
+
+    fn bucket(db: &Database) -> GridFsBucket {
+        db.gridfs_bucket(
+            GridFsBucketOptions::builder()
+                .bucket_name(BUCKET_NAME.to_string())
+                .build(),
+        )
+    }
+
+    fn name_filter(project_id: Uuid, name: &str) -> bson::Document {
+        doc! { "metadata.project_id": project_id.to_string(), "filename": name }
+    }
+
+    async fn find_files(
+        db: &Database,
+        filter: bson::Document,
+    ) -> AppResult<Vec<FilesCollectionDocument>> {
+        Self::bucket(db)
+            .find(filter)
+            .await
+            .map_err(|e| AppError::Database(format!("MongoDB GridFS error: {}", e)))?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::Database(format!("MongoDB GridFS error: {}", e)))
+    }
+
+    async fn delete_file(bucket: &GridFsBucket, id: Bson) -> AppResult<()> {
+        bucket
+            .delete(id)
+            .await
+            .map_err(|e| AppError::Database(format!("MongoDB GridFS delete error: {}", e)))
+    }
+
+    fn to_fixture(file: &FilesCollectionDocument) -> Fixture {
+        let metadata = file.metadata.clone().unwrap_or_default();
+        let uploaded_at = time::OffsetDateTime::from_unix_timestamp_nanos(
+            i128::from(file.upload_date.timestamp_millis()) * 1_000_000,
+        )
+        .unwrap_or(time::OffsetDateTime::UNIX_EPOCH);
+
+        Fixture {
+            name: file.filename.clone().unwrap_or_default(),
+            content_type: metadata.get_str("content_type").ok().map(str::to_string),
+            size: file.length as i64,
+            sha256: metadata.get_str("sha256").unwrap_or_default().to_string(),
+            uploaded_at,
+        }
+    }
+}
@@ -1,31 +1,39 @@
 pub mod api;
 pub mod collection;
 pub mod environment;
+pub mod fixture;
 pub mod mongo;
 pub mod ownership;
 pub mod project;
 pub mod refresh_token;
 pub mod report;
+pub mod request_auth;
 pub mod response;
 pub mod scenario;
 pub mod user;
+pub mod variable;
 
 pub use api::ApiRepository;
 pub use collection::CollectionRepository;
 pub use environment::EnvironmentRepository;
+pub use fixture::FixtureRepository;
 pub use ownership::OwnershipVerifier;
 pub use project::ProjectRepository;
 pub use refresh_token::RefreshTokenRepository;
 pub use report::ReportRepository;
+pub use request_auth::RequestAuthRepository;
 pub use response::ResponseRepository;
 pub use scenario::ScenarioRepository;
 pub use user::UserRepository;
+pub use variable::VariableRepository;
 
 use async_trait::async_trait;
 use sea_orm::DatabaseConnection;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 use uuid::Uuid;
 
-use crate::error::AppResult;
+use crate::error::{AppError, AppResult};
 
 /// Base repository trait for common CRUD operations
 #[async_trait]
@@ -45,3 +53,16 @@ where
     /// Count total entities
     async fn count(db: &DatabaseConnection) -> AppResult<u64>;
 }
+
+/// Value for a nullable JSONB column
+pub(crate) fn to_json<T: Serialize>(value: Option<&T>) -> AppResult<Option<serde_json::Value>> {
+    value
+        .map(serde_json::to_value)
+        .transpose()
+        .map_err(|e| AppError::Internal(e.to_string()))
+}
+
+/// Read a nullable JSONB column, `None` when it does not hold a `T`
+pub(crate) fn from_json<T: DeserializeOwned>(value: Option<serde_json::Value>) -> Option<T> {
+    value.and_then(|value| serde_json::from_value(value).ok())
+}
@@ -4,6 +4,7 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::error::{AppError, AppResult};
+use crate::services::{RedirectHop, RequestTiming, SentRequest, WorkflowStepResult};
 
 /// MongoDB repository for document storage
 pub struct MongoRepository;
@@ -25,11 +26,18 @@ pub struct ExecutionLog {
     pub api_id: String,
     pub scenario_id: String,
     pub example_index: i32,
+    pub request: Option<SentRequest>,
     pub response_status: i16,
     pub response_data: Option<serde_json::Value>,
+    pub response_headers: Option<serde_json::Value>,
     pub pass: bool,
     pub error_message: Option<String>,
     pub duration_ms: i64,
+    pub timing: Option<RequestTiming>,
+    #[serde(default)]
+    pub redirects: Vec<RedirectHop>,
+    #[serde(default)]
+    pub workflow_steps: Vec<WorkflowStepResult>,
     pub created_at: bson::DateTime,
 }
 
@@ -63,6 +63,7 @@ impl ProjectRepository {
             user_id: Set(user_id),
             name: Set(input.name.clone()),
             description: Set(input.description.clone()),
+            variables: Set(serde_json::json!(input.variables)),
             created_at: Set(time::OffsetDateTime::now_utc()),
             updated_at: Set(time::OffsetDateTime::now_utc()),
         };
@@ -135,6 +136,9 @@ impl ProjectRepository {
         if let Some(description) = &input.description {
             active.description = Set(Some(description.clone()));
         }
+        if let Some(variables) = &input.variables {
+            active.variables = Set(serde_json::json!(variables));
+        }
         active.updated_at = Set(time::OffsetDateTime::now_utc());
 
         let result = active.update(db).await?;
@@ -166,6 +170,7 @@ impl From<project::Model> for Project {
             user_id: m.user_id,
             name: m.name,
             description: m.description,
+            variables: serde_json::from_value(m.variables).unwrap_or_default(),
             created_at: m.created_at,
             updated_at: m.updated_at,
         }
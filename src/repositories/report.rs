@@ -75,6 +75,7 @@ impl ReportRepository {
             calculated: Set(false),
             pass_rate: Set(None),
             response_count: Set(0),
+            seed: Set(input.seed),
             created_at: Set(time::OffsetDateTime::now_utc()),
             finished_at: Set(None),
         };
@@ -166,6 +167,32 @@ impl ReportRepository {
         Ok(result.into())
     }
 
+    /// Update pass rate and response count after responses are added to a
+    /// report, keeping its finished state
+    pub async fn update_stats(
+        db: &DatabaseConnection,
+        id: Uuid,
+        user_id: Uuid,
+        pass_rate: Decimal,
+        response_count: i32,
+    ) -> AppResult<Report> {
+        let model = ReportEntity::find_by_id(id)
+            .one(db)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Report".to_string()))?;
+
+        // Verify project ownership
+        OwnershipVerifier::verify_project(db, model.project_id, user_id).await?;
+
+        let mut active: ActiveModel = model.into();
+        active.calculated = Set(true);
+        active.pass_rate = Set(Some(pass_rate));
+        active.response_count = Set(response_count);
+
+        let result = active.update(db).await?;
+        Ok(result.into())
+    }
+
     /// Delete report (with ownership check)
     pub async fn delete_by_user(db: &DatabaseConnection, id: Uuid, user_id: Uuid) -> AppResult<()> {
         let model = ReportEntity::find_by_id(id)
@@ -197,6 +224,7 @@ impl From<report::Model> for Report {
             calculated: m.calculated,
             pass_rate: m.pass_rate,
             response_count: m.response_count,
+            seed: m.seed,
             created_at: m.created_at,
             finished_at: m.finished_at,
         }
@@ -0,0 +1,80 @@
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+
+use crate::entity::api::{Column as ApiColumn, Entity as ApiEntity};
+use crate::entity::collection::{Column as CollectionColumn, Entity as CollectionEntity};
+use crate::error::{AppError, AppResult};
+use crate::models::{Api, Environment, RequestAuth, Scenario};
+use crate::repositories::{ApiRepository, Repository, ScenarioRepository};
+use crate::services::secrets::SecretCipher;
+use crate::services::{BootstrapScenario, RunAuth};
+
+/// Loads the auth strategies that apply to runs against an environment
+pub struct RequestAuthRepository;
+
+impl RequestAuthRepository {
+    /// Load the environment's auth and the auth of every API of its project
+    /// that overrides it, decrypted with `cipher`, and its auth bootstrap
+    /// scenario
+    pub async fn load(
+        db: &DatabaseConnection,
+        cipher: &SecretCipher,
+        environment: &Environment,
+    ) -> AppResult<RunAuth> {
+        let apis = ApiEntity::find()
+            .inner_join(CollectionEntity)
+            .filter(CollectionColumn::ProjectId.eq(environment.project_id))
+            .filter(ApiColumn::Auth.is_not_null())
+            .all(db)
+            .await?;
+
+        let apis = apis
+            .into_iter()
+            .filter_map(|api| Some((api.id, auth_from_json(api.auth)?)))
+            .map(|(id, auth)| Ok((id, cipher.open_auth(&auth)?)))
+            .collect::<AppResult<_>>()?;
+
+        let bootstrap = match &environment.auth_bootstrap {
+            Some(config) => {
+                let scenario = <ScenarioRepository as Repository<Scenario>>::find_by_id(
+                    db,
+                    config.scenario_id,
+                )
+                .await
+                .map_err(|e| match e {
+                    AppError::NotFound(_) => {
+                        AppError::NotFound("Auth bootstrap scenario".to_string())
+                    }
+                    e => e,
+                })?;
+                let api =
+                    <ApiRepository as Repository<Api>>::find_by_id(db, scenario.api_id).await?;
+                Some(BootstrapScenario {
+                    config: config.clone(),
+                    scenario,
+                    api,
+                })
+            }
+            None => None,
+        };
+
+        Ok(RunAuth {
+            environment: environment
+                .auth
+                .as_ref()
+                .map(|auth| cipher.open_auth(auth))
+                .transpose()?,
+            apis,
+            bootstrap,
+        })
+    }
+}
+
+pub(crate) fn auth_to_json(auth: Option<&RequestAuth>) -> AppResult<Option<serde_json::Value>> {
+    auth.map(serde_json::to_value)
+        .transpose()
+        .map_err(|e| AppError::Internal(e.to_string()))
+}
+
+pub(crate) fn auth_from_json(value: Option<serde_json::Value>) -> Option<RequestAuth> {
+    value.and_then(|value| serde_json::from_value(value).ok())
+}
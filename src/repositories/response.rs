@@ -65,12 +65,17 @@ impl ResponseRepository {
             api_id: Set(input.api_id),
             scenario_id: Set(input.scenario_id),
             example_index: Set(input.example_index),
+            request_data: Set(input.request_data.clone()),
             response_data: Set(input.response_data.clone()),
+            response_headers: Set(input.response_headers.clone()),
             response_status: Set(input.response_status),
             pass: Set(input.pass),
             error_message: Set(input.error_message.clone()),
             request_time: Set(time::OffsetDateTime::now_utc()),
             request_duration_ms: Set(input.request_duration_ms),
+            timing: Set(input.timing.clone()),
+            redirects: Set(input.redirects.clone()),
+            workflow_steps: Set(input.workflow_steps.clone()),
         };
 
         let result = model.insert(db).await?;
@@ -198,11 +203,16 @@ impl From<response::Model> for Response {
             scenario_id: m.scenario_id,
             example_index: m.example_index,
             response_data: m.response_data,
+            request_data: m.request_data,
+            response_headers: m.response_headers,
             response_status: m.response_status,
             pass: m.pass,
             error_message: m.error_message,
             request_time: m.request_time,
             request_duration_ms: m.request_duration_ms,
+            timing: m.timing,
+            redirects: m.redirects,
+            workflow_steps: m.workflow_steps,
         }
     }
 }
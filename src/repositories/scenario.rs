@@ -3,13 +3,15 @@ use sea_orm::{
     ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter,
     QueryOrder, QuerySelect, Set,
 };
+use std::collections::HashMap;
 use uuid::Uuid;
 
+use crate::entity::api::{Column as ApiColumn, Entity as ApiEntity};
 use crate::entity::scenario::{self, ActiveModel, Column, Entity as ScenarioEntity};
 use crate::error::{AppError, AppResult};
-use crate::models::{CreateScenario, Scenario, UpdateScenario};
+use crate::models::{Api, CreateScenario, Scenario, ScenarioKind, UpdateScenario};
 use crate::repositories::ownership::OwnershipVerifier;
-use crate::repositories::Repository;
+use crate::repositories::{from_json, to_json, EnvironmentRepository, Repository};
 
 /// Scenario repository for database operations
 pub struct ScenarioRepository;
@@ -62,6 +64,8 @@ impl ScenarioRepository {
     ) -> AppResult<Scenario> {
         OwnershipVerifier::verify_api(db, api_id, user_id).await?;
 
+        let background_json = serde_json::to_value(&input.background_steps)
+            .map_err(|e| AppError::Validation(format!("Invalid background steps JSON: {}", e)))?;
         let steps_json = serde_json::to_value(&input.steps)
             .map_err(|e| AppError::Validation(format!("Invalid steps JSON: {}", e)))?;
         let examples_json = serde_json::to_value(&input.examples)
@@ -73,8 +77,13 @@ impl ScenarioRepository {
             title: Set(input.title.clone()),
             description: Set(input.description.clone()),
             tags: Set(input.tags.clone().unwrap_or_default()),
+            kind: Set(input.kind.as_str().to_string()),
+            background_steps: Set(background_json),
             steps: Set(steps_json),
             examples: Set(examples_json),
+            response_schema: Set(input.response_schema.clone()),
+            body_match: Set(to_json(input.body_match.as_ref())?),
+            variables: Set(serde_json::json!(input.variables)),
             created_at: Set(time::OffsetDateTime::now_utc()),
             updated_at: Set(time::OffsetDateTime::now_utc()),
         };
@@ -161,6 +170,15 @@ impl ScenarioRepository {
         if let Some(tags) = &input.tags {
             active.tags = Set(tags.clone());
         }
+        if let Some(kind) = input.kind {
+            active.kind = Set(kind.as_str().to_string());
+        }
+        if let Some(steps) = &input.background_steps {
+            let background_json = serde_json::to_value(steps).map_err(|e| {
+                AppError::Validation(format!("Invalid background steps JSON: {}", e))
+            })?;
+            active.background_steps = Set(background_json);
+        }
         if let Some(steps) = &input.steps {
             let steps_json = serde_json::to_value(steps)
                 .map_err(|e| AppError::Validation(format!("Invalid steps JSON: {}", e)))?;
@@ -171,12 +189,78 @@ impl ScenarioRepository {
                 .map_err(|e| AppError::Validation(format!("Invalid examples JSON: {}", e)))?;
             active.examples = Set(examples_json);
         }
+        if let Some(response_schema) = &input.response_schema {
+            active.response_schema = Set(response_schema.clone());
+        }
+        if let Some(body_match) = &input.body_match {
+            active.body_match = Set(to_json(body_match.as_ref())?);
+        }
+        if let Some(variables) = &input.variables {
+            active.variables = Set(serde_json::json!(variables));
+        }
         active.updated_at = Set(time::OffsetDateTime::now_utc());
 
         let result = active.update(db).await?;
         Ok(result.into())
     }
 
+    /// Every scenario of a collection with its API, in creation order
+    pub async fn list_with_apis_by_collection(
+        db: &DatabaseConnection,
+        collection_id: Uuid,
+    ) -> AppResult<Vec<(Scenario, Api)>> {
+        let apis: HashMap<Uuid, Api> = ApiEntity::find()
+            .filter(ApiColumn::CollectionId.eq(collection_id))
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|m| (m.id, m.into()))
+            .collect();
+        if apis.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let models = ScenarioEntity::find()
+            .filter(Column::ApiId.is_in(apis.keys().copied()))
+            .order_by_asc(Column::CreatedAt)
+            .all(db)
+            .await?;
+
+        Ok(models
+            .into_iter()
+            .filter_map(|m| {
+                let api = apis.get(&m.api_id)?.clone();
+                Some((m.into(), api))
+            })
+            .collect())
+    }
+
+    /// IDs of the scenarios of an API
+    pub async fn ids_by_api(db: &DatabaseConnection, api_id: Uuid) -> AppResult<Vec<Uuid>> {
+        Ok(ScenarioEntity::find()
+            .filter(Column::ApiId.eq(api_id))
+            .select_only()
+            .column(Column::Id)
+            .into_tuple()
+            .all(db)
+            .await?)
+    }
+
+    /// IDs of the scenarios of every API of a collection
+    pub async fn ids_by_collection(
+        db: &DatabaseConnection,
+        collection_id: Uuid,
+    ) -> AppResult<Vec<Uuid>> {
+        Ok(ScenarioEntity::find()
+            .inner_join(ApiEntity)
+            .filter(ApiColumn::CollectionId.eq(collection_id))
+            .select_only()
+            .column(Column::Id)
+            .into_tuple()
+            .all(db)
+            .await?)
+    }
+
     /// Delete scenario (with ownership check)
     pub async fn delete_by_user(db: &DatabaseConnection, id: Uuid, user_id: Uuid) -> AppResult<()> {
         let model = ScenarioEntity::find_by_id(id)
@@ -185,6 +269,7 @@ impl ScenarioRepository {
             .ok_or_else(|| AppError::NotFound("Scenario".to_string()))?;
 
         OwnershipVerifier::verify_api(db, model.api_id, user_id).await?;
+        EnvironmentRepository::ensure_not_bootstrap(db, &[id]).await?;
 
         let active: ActiveModel = model.into();
         active.delete(db).await?;
@@ -202,8 +287,13 @@ impl From<scenario::Model> for Scenario {
             title: m.title,
             description: m.description,
             tags: m.tags,
+            kind: ScenarioKind::parse(&m.kind).unwrap_or_default(),
+            background_steps: m.background_steps,
             steps: m.steps,
             examples: m.examples,
+            response_schema: m.response_schema,
+            body_match: from_json(m.body_match),
+            variables: serde_json::from_value(m.variables).unwrap_or_default(),
             created_at: m.created_at,
             updated_at: m.updated_at,
         }
@@ -0,0 +1,45 @@
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+
+use crate::entity::collection::{Column as CollectionColumn, Entity as CollectionEntity};
+use crate::entity::project::Entity as ProjectEntity;
+use crate::error::{AppError, AppResult};
+use crate::models::Environment;
+use crate::services::secrets::SecretCipher;
+use crate::services::VariableScopes;
+
+/// Loads the variable scopes that apply to runs against an environment
+pub struct VariableRepository;
+
+impl VariableRepository {
+    /// Load project and collection variables of the environment's project,
+    /// and the environment's own variables, decrypted with `cipher`
+    pub async fn load_scopes(
+        db: &DatabaseConnection,
+        cipher: &SecretCipher,
+        environment: &Environment,
+    ) -> AppResult<VariableScopes> {
+        let project = ProjectEntity::find_by_id(environment.project_id)
+            .one(db)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Project".to_string()))?;
+
+        let collections = CollectionEntity::find()
+            .filter(CollectionColumn::ProjectId.eq(environment.project_id))
+            .all(db)
+            .await?;
+
+        Ok(VariableScopes {
+            project: serde_json::from_value(project.variables).unwrap_or_default(),
+            collections: collections
+                .into_iter()
+                .map(|c| {
+                    (
+                        c.id,
+                        serde_json::from_value(c.variables).unwrap_or_default(),
+                    )
+                })
+                .collect(),
+            environment: cipher.open_variables(&environment.variables)?,
+        })
+    }
+}
@@ -0,0 +1,1085 @@
+use jsonpath_rust::JsonPath;
+use regex::Regex;
+use reqwest::header::{HeaderMap, SET_COOKIE};
+use reqwest::Url;
+use serde_json::Value;
+
+use crate::services::body::ResponseBody;
+use crate::services::redirect::RedirectHop;
+
+/// How many of the values matched by a JSONPath must satisfy the comparison
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Quantifier {
+    /// The matched value (or the array of matches if there are several)
+    Single,
+    /// Every matched value (`should all ...`)
+    All,
+    /// At least one matched value (`should any ...`)
+    Any,
+}
+
+/// Comparison applied to a JSONPath match or a header value
+#[derive(Debug, Clone, PartialEq)]
+pub enum Comparison {
+    Equal(Value),
+    NotEqual(Value),
+    GreaterThan(f64),
+    GreaterOrEqual(f64),
+    LessThan(f64),
+    LessOrEqual(f64),
+    /// Substring for strings, element for arrays
+    Contains(Value),
+    /// Length of an array, string or object
+    Length(usize),
+    Exists,
+    NotExists,
+}
+
+impl Comparison {
+    /// Parse the text following `should`, e.g. `be greater than 0`
+    pub(crate) fn parse(text: &str) -> Option<Self> {
+        let number = |s: &str| s.trim().parse::<f64>().ok();
+
+        if text == "exist" {
+            return Some(Comparison::Exists);
+        }
+        if text == "not exist" {
+            return Some(Comparison::NotExists);
+        }
+        if let Some(r) = text.strip_prefix("not equal ") {
+            return Some(Comparison::NotEqual(parse_literal(r)));
+        }
+        if let Some(r) = text.strip_prefix("not be ") {
+            return Some(Comparison::NotEqual(parse_literal(r)));
+        }
+        if let Some(r) = text.strip_prefix("equal ") {
+            return Some(Comparison::Equal(parse_literal(r)));
+        }
+        if let Some(r) = text.strip_prefix("contain ") {
+            return Some(Comparison::Contains(parse_literal(r)));
+        }
+        if let Some(r) = text.strip_prefix("have length ") {
+            return Some(Comparison::Length(r.trim().parse().ok()?));
+        }
+        if let Some(r) = text.strip_prefix("be greater than or equal to ") {
+            return number(r).map(Comparison::GreaterOrEqual);
+        }
+        if let Some(r) = text.strip_prefix("be greater than ") {
+            return number(r).map(Comparison::GreaterThan);
+        }
+        if let Some(r) = text.strip_prefix("be less than or equal to ") {
+            return number(r).map(Comparison::LessOrEqual);
+        }
+        if let Some(r) = text.strip_prefix("be less than ") {
+            return number(r).map(Comparison::LessThan);
+        }
+        if let Some(r) = text.strip_prefix("be at least ") {
+            return number(r).map(Comparison::GreaterOrEqual);
+        }
+        if let Some(r) = text.strip_prefix("be at most ") {
+            return number(r).map(Comparison::LessOrEqual);
+        }
+        if let Some(r) = text.strip_prefix("be ") {
+            return Some(Comparison::Equal(parse_literal(r)));
+        }
+
+        None
+    }
+
+    /// Human-readable form used in failure messages
+    pub(crate) fn describe(&self) -> String {
+        match self {
+            Comparison::Equal(v) => format!("equal {}", v),
+            Comparison::NotEqual(v) => format!("not equal {}", v),
+            Comparison::GreaterThan(n) => format!("be greater than {}", n),
+            Comparison::GreaterOrEqual(n) => format!("be at least {}", n),
+            Comparison::LessThan(n) => format!("be less than {}", n),
+            Comparison::LessOrEqual(n) => format!("be at most {}", n),
+            Comparison::Contains(v) => format!("contain {}", v),
+            Comparison::Length(len) => format!("have length {}", len),
+            Comparison::Exists => "exist".to_string(),
+            Comparison::NotExists => "not exist".to_string(),
+        }
+    }
+}
+
+/// Typed assertion on the response body, parsed from an Outcome step such as
+/// `"$.items[*].price" should all be greater than 0`
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonAssertion {
+    pub path: String,
+    pub quantifier: Quantifier,
+    pub comparison: Comparison,
+}
+
+impl JsonAssertion {
+    /// Parse an assertion from step text. Returns `None` unless the first
+    /// quoted string is a JSONPath followed by `should`.
+    pub fn parse(text: &str) -> Option<Self> {
+        let (path, rest) = Self::split_path(text)?;
+        let rest = rest.trim().strip_prefix("should ")?.trim();
+
+        let (quantifier, rest) = if let Some(r) = rest.strip_prefix("all ") {
+            (Quantifier::All, r.trim())
+        } else if let Some(r) = rest.strip_prefix("any ") {
+            (Quantifier::Any, r.trim())
+        } else {
+            (Quantifier::Single, rest)
+        };
+
+        let comparison = Comparison::parse(rest)?;
+
+        Some(Self {
+            path,
+            quantifier,
+            comparison,
+        })
+    }
+
+    /// Check the assertion against a response body
+    pub fn check(&self, body: &Value) -> Result<(), String> {
+        let matches = body
+            .query(&self.path)
+            .map_err(|e| format!("Invalid JSONPath {}: {}", self.path, e))?;
+
+        match &self.comparison {
+            Comparison::Exists if matches.is_empty() => {
+                return Err(format!("Expected {} to exist", self.path))
+            }
+            Comparison::NotExists if !matches.is_empty() => {
+                return Err(format!("Expected {} not to exist", self.path))
+            }
+            Comparison::Exists | Comparison::NotExists => return Ok(()),
+            _ if matches.is_empty() => {
+                return Err(format!(
+                    "{} matched nothing in the response body",
+                    self.path
+                ))
+            }
+            _ => {}
+        }
+
+        match self.quantifier {
+            Quantifier::Single => {
+                let value = match matches.as_slice() {
+                    [single] => (*single).clone(),
+                    many => Value::Array(many.iter().map(|v| (*v).clone()).collect()),
+                };
+                self.compare(&value)
+            }
+            Quantifier::All => matches.iter().try_for_each(|value| self.compare(value)),
+            Quantifier::Any => {
+                if matches.iter().any(|value| self.compare(value).is_ok()) {
+                    Ok(())
+                } else {
+                    Err(format!(
+                        "No value at {} satisfies {}",
+                        self.path,
+                        self.comparison.describe()
+                    ))
+                }
+            }
+        }
+    }
+
+    /// Split `"$.path" rest` into the path and the remaining text
+    fn split_path(text: &str) -> Option<(String, &str)> {
+        let (_, path, rest) = split_quoted(text)?;
+
+        if !path.starts_with('$') {
+            return None;
+        }
+
+        Some((path.to_string(), rest))
+    }
+
+    fn compare(&self, actual: &Value) -> Result<(), String> {
+        let ok = match &self.comparison {
+            Comparison::Equal(expected) => values_equal(actual, expected),
+            Comparison::NotEqual(expected) => !values_equal(actual, expected),
+            Comparison::GreaterThan(n) => self.number(actual)? > *n,
+            Comparison::GreaterOrEqual(n) => self.number(actual)? >= *n,
+            Comparison::LessThan(n) => self.number(actual)? < *n,
+            Comparison::LessOrEqual(n) => self.number(actual)? <= *n,
+            Comparison::Contains(expected) => match (actual, expected) {
+                (Value::String(a), Value::String(e)) => a.contains(e.as_str()),
+                (Value::Array(items), e) => items.iter().any(|item| values_equal(item, e)),
+                _ => false,
+            },
+            Comparison::Length(len) => match actual {
+                Value::Array(items) => items.len() == *len,
+                Value::String(s) => s.chars().count() == *len,
+                Value::Object(obj) => obj.len() == *len,
+                _ => false,
+            },
+            Comparison::Exists | Comparison::NotExists => true,
+        };
+
+        if ok {
+            Ok(())
+        } else {
+            Err(format!(
+                "Expected {} to {}, got {}",
+                self.path,
+                self.comparison.describe(),
+                actual
+            ))
+        }
+    }
+
+    fn number(&self, actual: &Value) -> Result<f64, String> {
+        actual
+            .as_f64()
+            .ok_or_else(|| format!("Expected {} to be a number, got {}", self.path, actual))
+    }
+}
+
+/// Assertion on a response header, parsed from an Outcome step such as
+/// `header "Content-Type" should contain "application/json"`
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeaderAssertion {
+    /// Header name (case-insensitive)
+    pub name: String,
+    pub comparison: Comparison,
+}
+
+impl HeaderAssertion {
+    /// Parse an assertion from step text. Returns `None` unless the first
+    /// quoted string follows the word `header` and is followed by `should`.
+    pub fn parse(text: &str) -> Option<Self> {
+        let (name, rest) = split_subject(text, "header")?;
+        let comparison = Comparison::parse(rest)?;
+
+        Some(Self { name, comparison })
+    }
+
+    /// Check the assertion against the response headers. Repeated headers
+    /// are compared as one comma-separated value.
+    pub fn check(&self, headers: &HeaderMap) -> Result<(), String> {
+        let values: Vec<String> = headers
+            .get_all(self.name.as_str())
+            .iter()
+            .map(|v| String::from_utf8_lossy(v.as_bytes()).into_owned())
+            .collect();
+
+        match &self.comparison {
+            Comparison::Exists if values.is_empty() => {
+                return Err(format!("Expected header {} to exist", self.name))
+            }
+            Comparison::NotExists if !values.is_empty() => {
+                return Err(format!("Expected header {} not to exist", self.name))
+            }
+            Comparison::Exists | Comparison::NotExists => return Ok(()),
+            _ if values.is_empty() => return Err(format!("Response has no {} header", self.name)),
+            _ => {}
+        }
+
+        let actual = values.join(", ");
+
+        if compare_text(&actual, &self.comparison) {
+            Ok(())
+        } else {
+            Err(format!(
+                "Expected header {} to {}, got \"{}\"",
+                self.name,
+                self.comparison.describe(),
+                actual
+            ))
+        }
+    }
+}
+
+/// Check applied to a cookie set by the response
+#[derive(Debug, Clone, PartialEq)]
+pub enum CookieCheck {
+    Exists,
+    NotExists,
+    /// Cookie value equals the given text
+    Value(String),
+    /// Flag attribute such as `HttpOnly` or `Secure` is present (or absent)
+    Flag {
+        name: String,
+        present: bool,
+    },
+    /// Attribute such as `SameSite`, `Path` or `Domain` has the given value
+    Attribute {
+        name: String,
+        value: String,
+    },
+}
+
+/// Assertion on a `Set-Cookie` response header, parsed from an Outcome step
+/// such as `cookie "session" should be HttpOnly` or
+/// `cookie "session" should have SameSite "Strict"`
+#[derive(Debug, Clone, PartialEq)]
+pub struct CookieAssertion {
+    pub name: String,
+    pub check: CookieCheck,
+}
+
+impl CookieAssertion {
+    /// Parse an assertion from step text. Returns `None` unless the first
+    /// quoted string follows the word `cookie` and is followed by `should`.
+    pub fn parse(text: &str) -> Option<Self> {
+        let (name, rest) = split_subject(text, "cookie")?;
+
+        let check = if rest == "exist" || rest == "be set" {
+            CookieCheck::Exists
+        } else if rest == "not exist" || rest == "not be set" {
+            CookieCheck::NotExists
+        } else if let Some(r) = rest
+            .strip_prefix("have value ")
+            .or_else(|| rest.strip_prefix("equal "))
+        {
+            CookieCheck::Value(literal_text(&parse_literal(r)))
+        } else if let Some(r) = rest.strip_prefix("not be ") {
+            CookieCheck::Flag {
+                name: r.trim().to_string(),
+                present: false,
+            }
+        } else if let Some(r) = rest.strip_prefix("be ") {
+            CookieCheck::Flag {
+                name: r.trim().to_string(),
+                present: true,
+            }
+        } else if let Some(r) = rest.strip_prefix("have ") {
+            let (attribute, value) = r.trim().split_once(char::is_whitespace)?;
+            CookieCheck::Attribute {
+                name: attribute.to_string(),
+                value: literal_text(&parse_literal(value)),
+            }
+        } else {
+            return None;
+        };
+
+        Some(Self { name, check })
+    }
+
+    /// Check the assertion against the `Set-Cookie` headers of a response.
+    /// When the same cookie is set more than once, the last one wins.
+    pub fn check(&self, headers: &HeaderMap) -> Result<(), String> {
+        let cookie = headers
+            .get_all(SET_COOKIE)
+            .iter()
+            .filter_map(|v| SetCookie::parse(&String::from_utf8_lossy(v.as_bytes())))
+            .rfind(|c| c.name == self.name);
+
+        let cookie = match (&self.check, cookie) {
+            (CookieCheck::NotExists, None) => return Ok(()),
+            (CookieCheck::NotExists, Some(_)) => {
+                return Err(format!("Expected cookie {} not to be set", self.name))
+            }
+            (_, None) => return Err(format!("Response does not set cookie {}", self.name)),
+            (_, Some(cookie)) => cookie,
+        };
+
+        match &self.check {
+            CookieCheck::Exists | CookieCheck::NotExists => Ok(()),
+            CookieCheck::Value(expected) if cookie.value == *expected => Ok(()),
+            CookieCheck::Value(expected) => Err(format!(
+                "Expected cookie {} to have value \"{}\", got \"{}\"",
+                self.name, expected, cookie.value
+            )),
+            CookieCheck::Flag { name, present } => {
+                if cookie.attribute(name).is_some() == *present {
+                    Ok(())
+                } else if *present {
+                    Err(format!("Expected cookie {} to be {}", self.name, name))
+                } else {
+                    Err(format!("Expected cookie {} not to be {}", self.name, name))
+                }
+            }
+            CookieCheck::Attribute { name, value } => match cookie.attribute(name) {
+                Some(Some(actual)) if actual.eq_ignore_ascii_case(value) => Ok(()),
+                Some(Some(actual)) => Err(format!(
+                    "Expected cookie {} to have {} \"{}\", got \"{}\"",
+                    self.name, name, value, actual
+                )),
+                _ => Err(format!(
+                    "Expected cookie {} to have {} \"{}\", but it is not set",
+                    self.name, name, value
+                )),
+            },
+        }
+    }
+}
+
+/// Assertion on the cookie jar once the response's cookies are stored, such
+/// as `the cookie jar should contain "session"`, `the cookie jar should not
+/// contain "session"` or `the cookie jar should contain "theme" with value "dark"`
+#[derive(Debug, Clone, PartialEq)]
+pub struct CookieJarAssertion {
+    pub name: String,
+    pub present: bool,
+    /// Expected value, when `present`
+    pub value: Option<String>,
+}
+
+impl CookieJarAssertion {
+    /// Check the assertion against the jar's value for the cookie
+    pub fn check(&self, actual: Option<&str>) -> Result<(), String> {
+        match (actual, self.present, &self.value) {
+            (None, false, _) => Ok(()),
+            (Some(_), false, _) => Err(format!(
+                "Expected the cookie jar not to contain {}",
+                self.name
+            )),
+            (None, true, _) => Err(format!("Expected the cookie jar to contain {}", self.name)),
+            (Some(actual), true, Some(expected)) if actual != expected => Err(format!(
+                "Expected cookie {} in the jar to have value \"{}\", got \"{}\"",
+                self.name, expected, actual
+            )),
+            (Some(_), true, _) => Ok(()),
+        }
+    }
+}
+
+/// Assertion on the redirects followed before the final response, such as
+/// `the final URL should be "/home"`, `the request should be redirected 2
+/// times` or `redirect 1 should be 302 to "/login"`. Expected URLs starting
+/// with `/` are compared with the path and query only.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RedirectAssertion {
+    FinalUrl {
+        expected: String,
+        contains: bool,
+    },
+    /// Number of redirects followed; `should not be redirected` is zero
+    Count(usize),
+    /// One hop, numbered from 1
+    Hop {
+        number: usize,
+        status: Option<i16>,
+        location: Option<String>,
+    },
+}
+
+impl RedirectAssertion {
+    /// Check the assertion against the redirects followed and the URL of the
+    /// final response
+    pub fn check(&self, redirects: &[RedirectHop], final_url: &Url) -> Result<(), String> {
+        match self {
+            Self::FinalUrl { expected, contains } => {
+                let matched = if *contains {
+                    final_url.as_str().contains(expected.as_str())
+                } else {
+                    url_matches(final_url.as_str(), expected)
+                };
+                if matched {
+                    Ok(())
+                } else {
+                    Err(format!(
+                        "Expected the final URL to {} \"{}\", got \"{}\"",
+                        if *contains { "contain" } else { "be" },
+                        expected,
+                        final_url
+                    ))
+                }
+            }
+            Self::Count(expected) if redirects.len() != *expected => Err(format!(
+                "Expected {} redirects, got {}",
+                expected,
+                redirects.len()
+            )),
+            Self::Count(_) => Ok(()),
+            Self::Hop {
+                number,
+                status,
+                location,
+            } => {
+                let hop = redirects.get(number - 1).ok_or_else(|| {
+                    format!(
+                        "Expected redirect {}, but the request was redirected {} times",
+                        number,
+                        redirects.len()
+                    )
+                })?;
+                if let Some(status) = status {
+                    if hop.status != *status {
+                        return Err(format!(
+                            "Expected redirect {} to have status {}, got {}",
+                            number, status, hop.status
+                        ));
+                    }
+                }
+                if let Some(location) = location {
+                    if !url_matches(&hop.location, location) {
+                        return Err(format!(
+                            "Expected redirect {} to go to \"{}\", got \"{}\"",
+                            number, location, hop.location
+                        ));
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Compare a URL with an expected one, by path and query when the expected
+/// URL starts with `/`
+fn url_matches(actual: &str, expected: &str) -> bool {
+    if !expected.starts_with('/') {
+        return actual == expected;
+    }
+
+    match Url::parse(actual) {
+        Ok(url) => {
+            let path = match url.query() {
+                Some(query) => format!("{}?{}", url.path(), query),
+                None => url.path().to_string(),
+            };
+            path == expected
+        }
+        Err(_) => false,
+    }
+}
+
+/// Assertion on the raw response body, whatever its content type
+#[derive(Debug, Clone)]
+pub enum BodyAssertion {
+    /// `the response body should contain "pong"`
+    Text(Comparison),
+    /// `the response body should match "^pong$"` (or `should not match`)
+    Matches { regex: Regex, expected: bool },
+    /// `xpath "/user/name" should equal "Alice"`, for XML bodies
+    XPath {
+        expression: String,
+        comparison: Comparison,
+    },
+    /// `the response body sha256 should be "<hex digest>"`
+    Sha256(String),
+    /// `the response body size should be greater than 1024 bytes`
+    Size(Comparison),
+}
+
+impl BodyAssertion {
+    /// Parse an assertion from step text. Returns `None` unless the subject
+    /// before `should` is an XPath, or the response body, text, size or hash.
+    pub fn parse(text: &str) -> Option<Self> {
+        if let Some((expression, rest)) = split_subject(text, "xpath") {
+            return Some(Self::XPath {
+                expression,
+                comparison: Comparison::parse(rest)?,
+            });
+        }
+
+        let (subject, rest) = text.split_once(" should ")?;
+        let subject = subject.trim().to_lowercase();
+        let rest = rest.trim();
+        if !subject.contains("response") {
+            return None;
+        }
+
+        if subject.ends_with("sha256") || subject.ends_with("sha-256") {
+            let digest = rest
+                .strip_prefix("be ")
+                .or_else(|| rest.strip_prefix("equal "))?;
+            return Some(Self::Sha256(
+                literal_text(&parse_literal(digest)).to_lowercase(),
+            ));
+        }
+
+        if subject.ends_with("size") {
+            let rest = rest.strip_suffix("bytes").unwrap_or(rest).trim();
+            return Comparison::parse(rest).map(Self::Size);
+        }
+
+        if subject.ends_with("body") || subject.ends_with("text") {
+            for (prefix, expected) in [("not match ", false), ("match ", true)] {
+                if let Some(pattern) = rest.strip_prefix(prefix) {
+                    let regex = Regex::new(&literal_text(&parse_literal(pattern))).ok()?;
+                    return Some(Self::Matches { regex, expected });
+                }
+            }
+            return Comparison::parse(rest).map(Self::Text);
+        }
+
+        None
+    }
+
+    /// Check the assertion against the raw response body
+    pub fn check(&self, body: &ResponseBody) -> Result<(), String> {
+        match self {
+            Self::Text(comparison) => {
+                let text = Self::text(body)?;
+                let ok = match comparison {
+                    Comparison::Exists => !text.is_empty(),
+                    Comparison::NotExists => text.is_empty(),
+                    _ => compare_text(text, comparison),
+                };
+                if ok {
+                    Ok(())
+                } else {
+                    Err(format!(
+                        "Expected response body to {}, got \"{}\"",
+                        comparison.describe(),
+                        excerpt(text)
+                    ))
+                }
+            }
+            Self::Matches { regex, expected } => {
+                let text = Self::text(body)?;
+                if regex.is_match(text) == *expected {
+                    Ok(())
+                } else {
+                    Err(format!(
+                        "Expected response body {}to match /{}/, got \"{}\"",
+                        if *expected { "" } else { "not " },
+                        regex.as_str(),
+                        excerpt(text)
+                    ))
+                }
+            }
+            Self::XPath {
+                expression,
+                comparison,
+            } => {
+                let value = Self::evaluate_xpath(body, expression)?;
+                match (comparison, value) {
+                    (Comparison::Exists, Some(_)) | (Comparison::NotExists, None) => Ok(()),
+                    (Comparison::Exists, None) => {
+                        Err(format!("Expected XPath {} to match a node", expression))
+                    }
+                    (Comparison::NotExists, Some(_)) => {
+                        Err(format!("Expected XPath {} to match nothing", expression))
+                    }
+                    (_, None) => Err(format!("XPath {} matched nothing", expression)),
+                    (_, Some(actual)) if compare_text(&actual, comparison) => Ok(()),
+                    (_, Some(actual)) => Err(format!(
+                        "Expected XPath {} to {}, got \"{}\"",
+                        expression,
+                        comparison.describe(),
+                        excerpt(&actual)
+                    )),
+                }
+            }
+            Self::Sha256(expected) => {
+                let actual = body.sha256();
+                if actual == *expected {
+                    Ok(())
+                } else {
+                    Err(format!(
+                        "Expected response body SHA-256 {}, got {}",
+                        expected, actual
+                    ))
+                }
+            }
+            Self::Size(comparison) => {
+                let size = body.bytes.len().to_string();
+                if compare_text(&size, comparison) {
+                    Ok(())
+                } else {
+                    Err(format!(
+                        "Expected response body size to {} bytes, got {} bytes",
+                        comparison.describe(),
+                        size
+                    ))
+                }
+            }
+        }
+    }
+
+    fn text(body: &ResponseBody) -> Result<&str, String> {
+        body.text().ok_or_else(|| {
+            format!(
+                "Response body is not text ({} bytes of {})",
+                body.bytes.len(),
+                body.content_type
+                    .as_deref()
+                    .unwrap_or("unknown content type")
+            )
+        })
+    }
+
+    /// Evaluate an XPath against an XML body. Node sets yield the string
+    /// value of their first node, or `None` when empty.
+    fn evaluate_xpath(body: &ResponseBody, expression: &str) -> Result<Option<String>, String> {
+        let package = sxd_document::parser::parse(Self::text(body)?)
+            .map_err(|e| format!("Response body is not valid XML: {:?}", e))?;
+        let document = package.as_document();
+
+        let value = sxd_xpath::evaluate_xpath(&document, expression)
+            .map_err(|e| format!("Invalid XPath {}: {}", expression, e))?;
+
+        Ok(match value {
+            sxd_xpath::Value::Nodeset(nodes) => {
+                nodes.document_order_first().map(|node| node.string_value())
+            }
+            sxd_xpath::Value::String(s) => Some(s),
+            sxd_xpath::Value::Number(n) => Some(n.to_string()),
+            sxd_xpath::Value::Boolean(b) => Some(b.to_string()),
+        })
+    }
+}
+
+/// A parsed `Set-Cookie` header value
+#[derive(Debug)]
+struct SetCookie {
+    name: String,
+    value: String,
+    /// Attributes in order, e.g. `("Path", Some("/"))`, `("HttpOnly", None)`
+    attributes: Vec<(String, Option<String>)>,
+}
+
+impl SetCookie {
+    fn parse(header: &str) -> Option<Self> {
+        let mut parts = header.split(';');
+        let (name, value) = parts.next()?.split_once('=')?;
+        let name = name.trim();
+        if name.is_empty() {
+            return None;
+        }
+
+        let attributes = parts
+            .map(str::trim)
+            .filter(|part| !part.is_empty())
+            .map(|part| match part.split_once('=') {
+                Some((k, v)) => (k.trim().to_string(), Some(v.trim().to_string())),
+                None => (part.to_string(), None),
+            })
+            .collect();
+
+        Some(Self {
+            name: name.to_string(),
+            value: value.trim().trim_matches('"').to_string(),
+            attributes,
+        })
+    }
+
+    /// Look up an attribute by case-insensitive name. The outer `Option` is
+    /// whether it is present, the inner one its value.
+    fn attribute(&self, name: &str) -> Option<Option<&str>> {
+        self.attributes
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_deref())
+    }
+}
+
+/// Split `<word> "subject" should rest` into the subject and the text after
+/// `should`. The word directly before the quoted subject must be `word`.
+fn split_subject<'a>(text: &'a str, word: &str) -> Option<(String, &'a str)> {
+    let (before, subject, rest) = split_quoted(text)?;
+    let before = before.trim_end();
+    let preceding = before.rsplit(char::is_whitespace).next()?;
+    if !preceding.eq_ignore_ascii_case(word) || subject.is_empty() {
+        return None;
+    }
+
+    let rest = rest.trim().strip_prefix("should ")?.trim();
+    Some((subject.to_string(), rest))
+}
+
+/// Split text around its first quoted string: `(before, quoted, after)`
+pub(crate) fn split_quoted(text: &str) -> Option<(&str, &str, &str)> {
+    let start = text.find(['"', '\''])?;
+    let quote = text[start..].chars().next()?;
+    let after = &text[start + 1..];
+    let end = after.find(quote)?;
+
+    Some((&text[..start], &after[..end], &after[end + 1..]))
+}
+
+/// Compare text (a header, an XPath result, a body) against a comparison.
+/// Numeric comparisons fail when the text is not a number.
+fn compare_text(actual: &str, comparison: &Comparison) -> bool {
+    let number = || actual.trim().parse::<f64>().ok();
+
+    match comparison {
+        Comparison::Equal(expected) => actual == literal_text(expected),
+        Comparison::NotEqual(expected) => actual != literal_text(expected),
+        Comparison::Contains(expected) => actual.contains(&literal_text(expected)),
+        Comparison::Length(len) => actual.chars().count() == *len,
+        Comparison::GreaterThan(n) => number().is_some_and(|v| v > *n),
+        Comparison::GreaterOrEqual(n) => number().is_some_and(|v| v >= *n),
+        Comparison::LessThan(n) => number().is_some_and(|v| v < *n),
+        Comparison::LessOrEqual(n) => number().is_some_and(|v| v <= *n),
+        Comparison::Exists | Comparison::NotExists => true,
+    }
+}
+
+/// Shorten text quoted in failure messages
+fn excerpt(text: &str) -> String {
+    const MAX_CHARS: usize = 200;
+
+    if text.chars().count() <= MAX_CHARS {
+        text.to_string()
+    } else {
+        let short: String = text.chars().take(MAX_CHARS).collect();
+        format!("{}...", short)
+    }
+}
+
+/// Text form of an expected value, for comparing against header text
+fn literal_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Parse an expected value: quoted text is a string, anything else is
+/// read as JSON and falls back to a plain string
+fn parse_literal(text: &str) -> Value {
+    let text = text.trim();
+
+    for quote in ['"', '\''] {
+        if text.len() >= 2 && text.starts_with(quote) && text.ends_with(quote) {
+            return Value::String(text[1..text.len() - 1].to_string());
+        }
+    }
+
+    serde_json::from_str(text).unwrap_or_else(|_| Value::String(text.to_string()))
+}
+
+/// JSON equality that treats `3` and `3.0` as the same number
+fn values_equal(actual: &Value, expected: &Value) -> bool {
+    match (actual, expected) {
+        (Value::Number(a), Value::Number(e)) => a.as_f64() == e.as_f64(),
+        _ => actual == expected,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_assertions() {
+        assert_eq!(
+            JsonAssertion::parse(r#""$.items[*].price" should all be greater than 0"#),
+            Some(JsonAssertion {
+                path: "$.items[*].price".to_string(),
+                quantifier: Quantifier::All,
+                comparison: Comparison::GreaterThan(0.0),
+            })
+        );
+        assert_eq!(
+            JsonAssertion::parse(r#""$.meta.total" should equal 3"#),
+            Some(JsonAssertion {
+                path: "$.meta.total".to_string(),
+                quantifier: Quantifier::Single,
+                comparison: Comparison::Equal(json!(3)),
+            })
+        );
+        assert_eq!(
+            JsonAssertion::parse(r#"the value "$.name" should be "Alice""#).map(|a| a.comparison),
+            Some(Comparison::Equal(json!("Alice")))
+        );
+        assert_eq!(
+            JsonAssertion::parse(r#""$.deleted_at" should not exist"#).map(|a| a.comparison),
+            Some(Comparison::NotExists)
+        );
+
+        // Not a JSONPath assertion
+        assert_eq!(JsonAssertion::parse(r#"the response contains "id""#), None);
+        assert_eq!(JsonAssertion::parse(r#""$.id" is returned"#), None);
+    }
+
+    #[test]
+    fn test_check_assertions() {
+        let body = json!({
+            "items": [{ "price": 10 }, { "price": 2.5 }],
+            "meta": { "total": 3, "tags": ["new", "sale"] },
+            "name": "Alice"
+        });
+
+        let check = |text: &str| JsonAssertion::parse(text).unwrap().check(&body);
+
+        assert!(check(r#""$.items[*].price" should all be greater than 0"#).is_ok());
+        assert!(check(r#""$.items[*].price" should all be greater than 5"#).is_err());
+        assert!(check(r#""$.items[*].price" should any be greater than 5"#).is_ok());
+        assert!(check(r#""$.meta.total" should equal 3"#).is_ok());
+        assert!(check(r#""$.meta.total" should equal 3.0"#).is_ok());
+        assert!(check(r#""$.meta.total" should equal 4"#).is_err());
+        assert!(check(r#""$.meta.tags" should contain "sale""#).is_ok());
+        assert!(check(r#""$.meta.tags" should have length 2"#).is_ok());
+        assert!(check(r#""$.name" should contain "lic""#).is_ok());
+        assert!(check(r#""$.name" should be at least 1"#).is_err());
+        assert!(check(r#""$.missing" should not exist"#).is_ok());
+        assert!(check(r#""$.missing" should equal 1"#).is_err());
+    }
+
+    #[test]
+    fn test_substring_does_not_leak_across_fields() {
+        // A plain substring match on the serialized body would accept this
+        let body = json!({ "status": "inactive" });
+        let assertion = JsonAssertion::parse(r#""$.status" should equal "active""#).unwrap();
+
+        assert!(assertion.check(&body).is_err());
+    }
+
+    #[test]
+    fn test_header_assertions() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "content-type",
+            "application/json; charset=utf-8".parse().unwrap(),
+        );
+        headers.insert("content-length", "42".parse().unwrap());
+
+        let check = |text: &str| HeaderAssertion::parse(text).unwrap().check(&headers);
+
+        assert!(check(r#"header "Content-Type" should contain "application/json""#).is_ok());
+        assert!(check(r#"the header "content-type" should contain "text/html""#).is_err());
+        assert!(check(r#"header "Content-Length" should be greater than 0"#).is_ok());
+        assert!(check(r#"header "Content-Length" should equal 42"#).is_ok());
+        assert!(check(r#"header "X-Request-Id" should not exist"#).is_ok());
+        assert!(check(r#"header "X-Request-Id" should equal "abc""#).is_err());
+
+        // Not a header assertion
+        assert_eq!(
+            HeaderAssertion::parse(r#"I set header "X-Api-Key" to "secret""#),
+            None
+        );
+        assert_eq!(HeaderAssertion::parse(r#""$.header" should exist"#), None);
+    }
+
+    #[test]
+    fn test_cookie_assertions() {
+        let mut headers = HeaderMap::new();
+        headers.append(
+            SET_COOKIE,
+            "session=abc123; Path=/; HttpOnly; Secure; SameSite=Strict"
+                .parse()
+                .unwrap(),
+        );
+        headers.append(SET_COOKIE, "theme=dark; Path=/".parse().unwrap());
+
+        let check = |text: &str| CookieAssertion::parse(text).unwrap().check(&headers);
+
+        assert!(check(r#"cookie "session" should be HttpOnly"#).is_ok());
+        assert!(check(r#"cookie "session" should be Secure"#).is_ok());
+        assert!(check(r#"cookie "session" should have SameSite "Strict""#).is_ok());
+        assert!(check(r#"cookie "session" should have SameSite Lax"#).is_err());
+        assert!(check(r#"cookie "session" should have value "abc123""#).is_ok());
+        assert!(check(r#"cookie "theme" should be HttpOnly"#).is_err());
+        assert!(check(r#"cookie "theme" should not be Secure"#).is_ok());
+        assert!(check(r#"cookie "tracking" should not be set"#).is_ok());
+        assert!(check(r#"cookie "tracking" should exist"#).is_err());
+    }
+
+    #[test]
+    fn test_cookie_jar_assertions() {
+        let assertion = |present: bool, value: Option<&str>| CookieJarAssertion {
+            name: "session".to_string(),
+            present,
+            value: value.map(str::to_string),
+        };
+
+        assert!(assertion(true, None).check(Some("abc")).is_ok());
+        assert!(assertion(true, None).check(None).is_err());
+        assert!(assertion(true, Some("abc")).check(Some("abc")).is_ok());
+        assert!(assertion(true, Some("abc")).check(Some("xyz")).is_err());
+        assert!(assertion(false, None).check(None).is_ok());
+        assert!(assertion(false, None).check(Some("abc")).is_err());
+    }
+
+    #[test]
+    fn test_redirect_assertions() {
+        let redirects = vec![
+            RedirectHop {
+                status: 302,
+                url: "https://example.com/login".to_string(),
+                location: "https://example.com/sso?next=%2F".to_string(),
+            },
+            RedirectHop {
+                status: 301,
+                url: "https://example.com/sso?next=%2F".to_string(),
+                location: "https://example.com/home".to_string(),
+            },
+        ];
+        let url: Url = "https://example.com/home".parse().unwrap();
+        let check = |assertion: RedirectAssertion| assertion.check(&redirects, &url);
+        let final_url = |expected: &str, contains| RedirectAssertion::FinalUrl {
+            expected: expected.to_string(),
+            contains,
+        };
+        let hop = |number, status, location: Option<&str>| RedirectAssertion::Hop {
+            number,
+            status,
+            location: location.map(str::to_string),
+        };
+
+        assert!(check(final_url("/home", false)).is_ok());
+        assert!(check(final_url("https://example.com/home", false)).is_ok());
+        assert!(check(final_url("example.com", true)).is_ok());
+        assert!(check(final_url("/login", false)).is_err());
+        assert!(check(RedirectAssertion::Count(2)).is_ok());
+        assert!(check(RedirectAssertion::Count(1)).is_err());
+        assert!(check(RedirectAssertion::Count(0)).is_err());
+        assert!(check(hop(1, Some(302), Some("/sso?next=%2F"))).is_ok());
+        assert!(check(hop(2, Some(301), None)).is_ok());
+        assert!(check(hop(2, None, Some("/home"))).is_ok());
+        assert!(check(hop(1, Some(301), Some("/sso?next=%2F"))).is_err());
+        assert!(check(hop(3, Some(302), None)).is_err());
+    }
+
+    #[test]
+    fn test_text_body_assertions() {
+        let body = ResponseBody::new(
+            b"<html><body>Service Unavailable</body></html>".to_vec(),
+            Some("text/html".to_string()),
+        );
+        let check = |text: &str| BodyAssertion::parse(text).unwrap().check(&body);
+
+        assert!(check(r#"the response body should contain "Unavailable""#).is_ok());
+        assert!(check(r#"the response text should match "<body>\w+ \w+</body>""#).is_ok());
+        assert!(check(r#"the response body should not match "(?i)error""#).is_ok());
+        assert!(check(r#"the response body should equal "pong""#).is_err());
+
+        // Not a body assertion
+        assert!(BodyAssertion::parse("the response time should be under 300 ms").is_none());
+        assert!(BodyAssertion::parse(r#"the response should contain "id""#).is_none());
+    }
+
+    #[test]
+    fn test_xpath_assertions() {
+        let body = ResponseBody::new(
+            br#"<users><user id="1"><name>Alice</name></user><user id="2"><name>Bob</name></user></users>"#
+                .to_vec(),
+            Some("application/xml".to_string()),
+        );
+        let check = |text: &str| BodyAssertion::parse(text).unwrap().check(&body);
+
+        assert!(check(r#"xpath "/users/user[1]/name" should equal "Alice""#).is_ok());
+        assert!(check(r#"xpath "/users/user[2]/@id" should be 2"#).is_ok());
+        assert!(check(r#"xpath "count(/users/user)" should be greater than 1"#).is_ok());
+        assert!(check(r#"xpath "/users/admin" should not exist"#).is_ok());
+        assert!(check(r#"xpath "/users/user[1]/name" should equal "Bob""#).is_err());
+
+        let not_xml = ResponseBody::new(b"pong".to_vec(), None);
+        let err = BodyAssertion::parse(r#"xpath "/a" should exist"#)
+            .unwrap()
+            .check(&not_xml)
+            .unwrap_err();
+        assert!(err.contains("not valid XML"), "{}", err);
+    }
+
+    #[test]
+    fn test_binary_body_assertions() {
+        let body = ResponseBody::new(
+            b"abc".to_vec(),
+            Some("application/octet-stream".to_string()),
+        );
+        let check = |text: &str| BodyAssertion::parse(text).unwrap().check(&body);
+
+        assert!(check(
+            r#"the response body sha256 should be "BA7816BF8F01CFEA414140DE5DAE2223B00361A396177A9CB410FF61F20015AD""#
+        )
+        .is_ok());
+        assert!(check(r#"the response body sha256 should be "00""#).is_err());
+        assert!(check("the response body size should be 3 bytes").is_ok());
+        assert!(check("the response body size should be greater than 1024 bytes").is_err());
+
+        let binary = ResponseBody::new(vec![0xff, 0xfe], None);
+        assert!(
+            BodyAssertion::parse(r#"the response body should contain "a""#)
+                .unwrap()
+                .check(&binary)
+                .is_err()
+        );
+    }
+}
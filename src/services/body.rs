@@ -0,0 +1,194 @@
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+
+use crate::models::Scenario;
+
+/// Bodies longer than this are truncated in stored text representations
+const MAX_STORED_TEXT: usize = 64 * 1024;
+
+/// A file that request bodies can reference by name, e.g. `@avatar.png`
+#[derive(Debug, Clone)]
+pub struct FixtureFile {
+    pub file_name: String,
+    pub content_type: Option<String>,
+    pub bytes: Vec<u8>,
+}
+
+/// Names that steps and examples of `scenarios` may reference fixtures by,
+/// as `fixture "name"` or `@name`. Every quoted text and every example value
+/// is a candidate, so that only fixtures which exist under one of these names
+/// need to be loaded for a run.
+pub fn fixture_references<'a>(
+    scenarios: impl IntoIterator<Item = &'a Scenario>,
+) -> HashSet<String> {
+    fn collect(value: &Value, examples: bool, names: &mut HashSet<String>) {
+        match value {
+            Value::String(text) => {
+                let quoted = text
+                    .split('"')
+                    .skip(1)
+                    .step_by(2)
+                    .chain(text.split('\'').skip(1).step_by(2));
+                for (name, candidate) in quoted
+                    .map(|name| (name, true))
+                    .chain([(text.as_str(), examples)])
+                {
+                    let name = match name.strip_prefix('@') {
+                        Some(escaped) if escaped.starts_with('@') => continue,
+                        Some(name) => name,
+                        None if candidate => name,
+                        None => continue,
+                    };
+                    if !name.is_empty() {
+                        names.insert(name.to_string());
+                    }
+                }
+            }
+            Value::Array(items) => items.iter().for_each(|v| collect(v, examples, names)),
+            Value::Object(fields) => fields.values().for_each(|v| collect(v, examples, names)),
+            _ => {}
+        }
+    }
+
+    let mut names = HashSet::new();
+    for scenario in scenarios {
+        collect(&scenario.background_steps, false, &mut names);
+        collect(&scenario.steps, false, &mut names);
+        collect(&scenario.examples, true, &mut names);
+    }
+    names
+}
+
+/// Raw response body with its content type, as received
+#[derive(Debug, Clone, Default)]
+pub struct ResponseBody {
+    pub bytes: Vec<u8>,
+    pub content_type: Option<String>,
+}
+
+impl ResponseBody {
+    pub fn new(bytes: Vec<u8>, content_type: Option<String>) -> Self {
+        Self {
+            bytes,
+            content_type,
+        }
+    }
+
+    /// The body as text, if it is valid UTF-8
+    pub fn text(&self) -> Option<&str> {
+        std::str::from_utf8(&self.bytes).ok()
+    }
+
+    /// The body parsed as JSON, or `Null` if it is not JSON
+    pub fn json(&self) -> Value {
+        serde_json::from_slice(&self.bytes).unwrap_or(Value::Null)
+    }
+
+    /// Lowercase hex SHA-256 digest of the body
+    pub fn sha256(&self) -> String {
+        hex::encode(Sha256::digest(&self.bytes))
+    }
+
+    /// Representation stored in `response_data` (see [`stored_body_value`])
+    pub fn to_stored_value(&self) -> Value {
+        stored_body_value(&self.bytes, self.content_type.as_deref())
+    }
+}
+
+/// Stored representation of a body: JSON bodies as-is, other bodies as
+/// `{ content_type, size, sha256 }` plus their (truncated) text when they are
+/// UTF-8
+pub fn stored_body_value(bytes: &[u8], content_type: Option<&str>) -> Value {
+    if bytes.is_empty() {
+        return Value::Null;
+    }
+    if let Ok(value) = serde_json::from_slice::<Value>(bytes) {
+        return value;
+    }
+
+    let mut stored = json!({
+        "content_type": content_type,
+        "size": bytes.len(),
+        "sha256": hex::encode(Sha256::digest(bytes)),
+    });
+
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        let mut end = text.len().min(MAX_STORED_TEXT);
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        stored["text"] = Value::String(text[..end].to_string());
+        stored["truncated"] = Value::Bool(end < text.len());
+    }
+
+    stored
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stored_value() {
+        let body = ResponseBody::new(br#"{"id":1}"#.to_vec(), None);
+        assert_eq!(body.to_stored_value(), json!({ "id": 1 }));
+
+        let body = ResponseBody::new(b"pong".to_vec(), Some("text/plain".to_string()));
+        let stored = body.to_stored_value();
+        assert_eq!(stored["text"], "pong");
+        assert_eq!(stored["size"], 4);
+        assert_eq!(stored["truncated"], false);
+        assert_eq!(stored["sha256"], body.sha256());
+
+        // Binary bodies keep only their size and digest
+        let body = ResponseBody::new(vec![0xff, 0xd8, 0xff], Some("image/jpeg".to_string()));
+        let stored = body.to_stored_value();
+        assert_eq!(stored["content_type"], "image/jpeg");
+        assert_eq!(stored["size"], 3);
+        assert!(stored.get("text").is_none());
+
+        assert_eq!(ResponseBody::default().to_stored_value(), Value::Null);
+    }
+
+    #[test]
+    fn test_fixture_references() {
+        let now = time::OffsetDateTime::now_utc();
+        let scenario = Scenario {
+            id: uuid::Uuid::new_v4(),
+            api_id: uuid::Uuid::new_v4(),
+            title: "upload".to_string(),
+            description: None,
+            tags: Vec::new(),
+            kind: Default::default(),
+            background_steps: json!([{ "text": "the request body is fixture \"user.json\"" }]),
+            steps: json!([
+                { "text": "I send the fixture '<file>'" },
+                { "text": "the form fields are", "data_table": [{ "avatar": "@avatar.png", "handle": "@@ada" }] }
+            ]),
+            examples: json!([{ "example": { "file": "report.pdf" }, "expected_status_code": 200 }]),
+            response_schema: None,
+            body_match: None,
+            variables: Default::default(),
+            created_at: now,
+            updated_at: now,
+        };
+
+        let names = fixture_references([&scenario]);
+        for name in ["user.json", "avatar.png", "report.pdf"] {
+            assert!(names.contains(name), "{}", name);
+        }
+        // Step text itself and escaped `@@` values are not names
+        assert!(!names.contains("the form fields are"));
+        assert!(!names.contains("@ada"));
+    }
+
+    #[test]
+    fn test_sha256() {
+        let body = ResponseBody::new(b"abc".to_vec(), None);
+        assert_eq!(
+            body.sha256(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+}
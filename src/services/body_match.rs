@@ -0,0 +1,535 @@
+use regex::Regex;
+use serde_json::Value;
+use std::fmt::{self, Write};
+use std::sync::LazyLock;
+
+use crate::models::{BodyMatch, MatchMode};
+
+static ISO8601: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^\d{4}-(0[1-9]|1[0-2])-(0[1-9]|[12]\d|3[01])(T([01]\d|2[0-3]):[0-5]\d(:[0-5]\d(\.\d+)?)?(Z|[+-]([01]\d|2[0-3])(:?[0-5]\d)?)?)?$",
+    )
+    .expect("valid ISO 8601 pattern")
+});
+
+/// A value matcher written as a string in an expected body, such as `"<any-uuid>"`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Matcher {
+    Any,
+    String,
+    Number,
+    Boolean,
+    Array,
+    Object,
+    Uuid,
+    Iso8601,
+}
+
+impl Matcher {
+    fn parse(text: &str) -> Option<Self> {
+        match text {
+            "<any>" => Some(Self::Any),
+            "<any-string>" => Some(Self::String),
+            "<any-number>" => Some(Self::Number),
+            "<any-boolean>" => Some(Self::Boolean),
+            "<any-array>" => Some(Self::Array),
+            "<any-object>" => Some(Self::Object),
+            "<any-uuid>" => Some(Self::Uuid),
+            "<any-iso8601>" => Some(Self::Iso8601),
+            _ => None,
+        }
+    }
+
+    fn matches(self, value: &Value) -> bool {
+        match self {
+            Self::Any => true,
+            Self::String => value.is_string(),
+            Self::Number => value.is_number(),
+            Self::Boolean => value.is_boolean(),
+            Self::Array => value.is_array(),
+            Self::Object => value.is_object(),
+            Self::Uuid => value
+                .as_str()
+                .is_some_and(|s| uuid::Uuid::parse_str(s).is_ok()),
+            Self::Iso8601 => value.as_str().is_some_and(|s| ISO8601.is_match(s)),
+        }
+    }
+}
+
+/// One step of a path through a JSON document
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PathKey {
+    Field(String),
+    Index(usize),
+}
+
+/// One segment of an ignore path
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Key(PathKey),
+    /// `.*`, any field of an object
+    AnyField,
+    /// `[*]`, any item of an array
+    AnyIndex,
+    /// `..`, any number of levels
+    Descend,
+}
+
+/// A path left out of a body comparison: `$.id`, `$.items[*].created_at`,
+/// `$..updated_at`, or a bare field name ignored at any depth
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IgnorePath(Vec<Segment>);
+
+impl IgnorePath {
+    pub fn parse(path: &str) -> Result<Self, String> {
+        let path = path.trim();
+        let invalid = || format!("Invalid ignore path \"{}\"", path);
+        let rest = match path.strip_prefix('$') {
+            Some(rest) => rest.to_string(),
+            None if !path.is_empty() => format!("..{}", path),
+            None => return Err(invalid()),
+        };
+
+        let mut segments = Vec::new();
+        let mut chars = rest.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '.' => {
+                    if chars.next_if_eq(&'.').is_some() {
+                        segments.push(Segment::Descend);
+                    }
+                    let mut name = String::new();
+                    while let Some(c) = chars.next_if(|c| *c != '.' && *c != '[') {
+                        name.push(c);
+                    }
+                    match name.as_str() {
+                        "" if segments.last() == Some(&Segment::Descend) => {}
+                        "" => return Err(invalid()),
+                        "*" => segments.push(Segment::AnyField),
+                        _ => segments.push(Segment::Key(PathKey::Field(name))),
+                    }
+                }
+                '[' => {
+                    let mut inner = String::new();
+                    loop {
+                        match chars.next() {
+                            Some(']') => break,
+                            Some(c) => inner.push(c),
+                            None => return Err(invalid()),
+                        }
+                    }
+                    let quoted = inner
+                        .strip_prefix('\'')
+                        .and_then(|s| s.strip_suffix('\''))
+                        .or_else(|| inner.strip_prefix('"').and_then(|s| s.strip_suffix('"')));
+                    segments.push(match (inner.as_str(), quoted) {
+                        (_, Some(name)) => Segment::Key(PathKey::Field(name.to_string())),
+                        ("*", None) => Segment::AnyIndex,
+                        (index, None) => {
+                            Segment::Key(PathKey::Index(index.parse().map_err(|_| invalid())?))
+                        }
+                    });
+                }
+                _ => return Err(invalid()),
+            }
+        }
+
+        if segments.is_empty() || segments.last() == Some(&Segment::Descend) {
+            return Err(invalid());
+        }
+        Ok(Self(segments))
+    }
+
+    fn matches(&self, path: &[PathKey]) -> bool {
+        fn matches(segments: &[Segment], path: &[PathKey]) -> bool {
+            match (segments.split_first(), path.split_first()) {
+                (None, None) => true,
+                (Some((Segment::Descend, rest)), _) => {
+                    (0..=path.len()).any(|skip| matches(rest, &path[skip..]))
+                }
+                (Some((segment, rest)), Some((key, path))) => {
+                    let hit = match (segment, key) {
+                        (Segment::Key(expected), key) => expected == key,
+                        (Segment::AnyField, PathKey::Field(_)) => true,
+                        (Segment::AnyIndex, PathKey::Index(_)) => true,
+                        _ => false,
+                    };
+                    hit && matches(rest, path)
+                }
+                _ => false,
+            }
+        }
+        matches(&self.0, path)
+    }
+}
+
+/// Path of a value being compared, displayed as JSONPath
+struct Location<'a>(&'a [PathKey]);
+
+impl fmt::Display for Location<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_char('$')?;
+        for key in self.0 {
+            match key {
+                PathKey::Field(name) => write!(f, ".{}", name)?,
+                PathKey::Index(index) => write!(f, "[{}]", index)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Compare a response body with an expected body, explaining the first
+/// difference found
+pub fn compare(actual: &Value, expected: &Value, options: &BodyMatch) -> Result<(), String> {
+    let ignored = options
+        .ignore_paths
+        .iter()
+        .map(|path| IgnorePath::parse(path))
+        .collect::<Result<Vec<_>, _>>()?;
+    Comparison {
+        mode: options.mode,
+        ignored: &ignored,
+    }
+    .compare(actual, expected, &mut Vec::new())
+}
+
+struct Comparison<'a> {
+    mode: MatchMode,
+    ignored: &'a [IgnorePath],
+}
+
+impl Comparison<'_> {
+    fn is_ignored(&self, path: &[PathKey]) -> bool {
+        self.ignored.iter().any(|ignored| ignored.matches(path))
+    }
+
+    fn compare(
+        &self,
+        actual: &Value,
+        expected: &Value,
+        path: &mut Vec<PathKey>,
+    ) -> Result<(), String> {
+        if self.is_ignored(path) {
+            return Ok(());
+        }
+
+        if let Some(matcher) = expected.as_str().and_then(Matcher::parse) {
+            if matcher.matches(actual) {
+                return Ok(());
+            }
+            return Err(format!(
+                "{}: expected {}, got {}",
+                Location(path),
+                expected.as_str().unwrap_or_default(),
+                actual
+            ));
+        }
+
+        match (actual, expected) {
+            (Value::Object(actual_obj), Value::Object(expected_obj)) => {
+                if self.mode == MatchMode::Exact {
+                    for key in actual_obj.keys().filter(|k| !expected_obj.contains_key(*k)) {
+                        path.push(PathKey::Field(key.clone()));
+                        let ignored = self.is_ignored(path);
+                        let location = Location(path).to_string();
+                        path.pop();
+                        if !ignored {
+                            return Err(format!("{}: unexpected field", location));
+                        }
+                    }
+                }
+                for (key, expected_value) in expected_obj {
+                    path.push(PathKey::Field(key.clone()));
+                    let result = match actual_obj.get(key) {
+                        Some(actual_value) => self.compare(actual_value, expected_value, path),
+                        None if self.is_ignored(path) => Ok(()),
+                        None => Err(format!("{}: missing field", Location(path))),
+                    };
+                    path.pop();
+                    result?;
+                }
+                Ok(())
+            }
+            (Value::Array(actual_arr), Value::Array(expected_arr)) => {
+                self.compare_arrays(actual_arr, expected_arr, path)
+            }
+            _ if actual == expected => Ok(()),
+            _ => Err(format!(
+                "{}: expected {}, got {}",
+                Location(path),
+                expected,
+                actual
+            )),
+        }
+    }
+
+    fn compare_arrays(
+        &self,
+        actual: &[Value],
+        expected: &[Value],
+        path: &mut Vec<PathKey>,
+    ) -> Result<(), String> {
+        if matches!(self.mode, MatchMode::Exact | MatchMode::ExactLength)
+            && actual.len() != expected.len()
+        {
+            return Err(format!(
+                "{}: expected {} items, got {}",
+                Location(path),
+                expected.len(),
+                actual.len()
+            ));
+        }
+
+        match self.mode {
+            // Item by item
+            MatchMode::Exact => {
+                for (index, (actual_item, expected_item)) in actual.iter().zip(expected).enumerate()
+                {
+                    path.push(PathKey::Index(index));
+                    let result = self.compare(actual_item, expected_item, path);
+                    path.pop();
+                    result?;
+                }
+                Ok(())
+            }
+            // Expected items in the same order, other items allowed between them
+            MatchMode::Ordered => {
+                let mut next = 0;
+                for expected_item in expected {
+                    let found = (next..actual.len())
+                        .find(|&index| self.item_matches(actual, index, expected_item, path));
+                    match found {
+                        Some(index) => next = index + 1,
+                        None => {
+                            return Err(format!(
+                                "{}: no item matching {} after index {}",
+                                Location(path),
+                                expected_item,
+                                next
+                            ))
+                        }
+                    }
+                }
+                Ok(())
+            }
+            // Every expected item matches an item of its own, in any order
+            MatchMode::ExactLength => {
+                // Each pair is compared once; the matching below only reads
+                // this table
+                let candidates: Vec<Vec<usize>> = expected
+                    .iter()
+                    .map(|expected_item| {
+                        (0..actual.len())
+                            .filter(|&index| self.item_matches(actual, index, expected_item, path))
+                            .collect()
+                    })
+                    .collect();
+                let mut owners: Vec<Option<usize>> = vec![None; actual.len()];
+                for (item, expected_item) in expected.iter().enumerate() {
+                    let mut seen = vec![false; actual.len()];
+                    if !assign(item, &candidates, &mut owners, &mut seen) {
+                        return Err(format!(
+                            "{}: no item left matching {}",
+                            Location(path),
+                            expected_item
+                        ));
+                    }
+                }
+                Ok(())
+            }
+            // Every expected item matches some item
+            MatchMode::Subset => {
+                for expected_item in expected {
+                    if !(0..actual.len())
+                        .any(|index| self.item_matches(actual, index, expected_item, path))
+                    {
+                        return Err(format!(
+                            "{}: no item matching {}",
+                            Location(path),
+                            expected_item
+                        ));
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn item_matches(
+        &self,
+        actual: &[Value],
+        index: usize,
+        expected: &Value,
+        path: &mut Vec<PathKey>,
+    ) -> bool {
+        path.push(PathKey::Index(index));
+        let matched = self.compare(&actual[index], expected, path).is_ok();
+        path.pop();
+        matched
+    }
+}
+
+/// Find an actual item for expected item `item` among its `candidates`,
+/// moving earlier assignments to other items when needed (augmenting path
+/// matching)
+fn assign(
+    item: usize,
+    candidates: &[Vec<usize>],
+    owners: &mut [Option<usize>],
+    seen: &mut [bool],
+) -> bool {
+    for &index in &candidates[item] {
+        if seen[index] {
+            continue;
+        }
+        seen[index] = true;
+        let free = match owners[index] {
+            None => true,
+            Some(owner) => assign(owner, candidates, owners, seen),
+        };
+        if free {
+            owners[index] = Some(item);
+            return true;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn compare_with(mode: MatchMode, actual: &Value, expected: &Value) -> Result<(), String> {
+        compare(
+            actual,
+            expected,
+            &BodyMatch {
+                mode,
+                ignore_paths: Vec::new(),
+            },
+        )
+    }
+
+    #[test]
+    fn test_subset_match() {
+        let actual = json!({ "id": 1, "name": "test", "extra": "field", "tags": ["a", "b"] });
+
+        assert!(compare_with(
+            MatchMode::Subset,
+            &actual,
+            &json!({ "id": 1, "name": "test" })
+        )
+        .is_ok());
+        assert!(compare_with(MatchMode::Subset, &actual, &json!({ "tags": ["b"] })).is_ok());
+        assert_eq!(
+            compare_with(MatchMode::Subset, &actual, &json!({ "id": 2 })).unwrap_err(),
+            "$.id: expected 2, got 1"
+        );
+        assert_eq!(
+            compare_with(MatchMode::Subset, &actual, &json!({ "missing": 1 })).unwrap_err(),
+            "$.missing: missing field"
+        );
+    }
+
+    #[test]
+    fn test_array_modes() {
+        let actual = json!([1, 2, 3]);
+
+        assert!(compare_with(MatchMode::Subset, &actual, &json!([3, 1])).is_ok());
+        assert!(compare_with(MatchMode::Ordered, &actual, &json!([1, 3])).is_ok());
+        assert!(compare_with(MatchMode::Ordered, &actual, &json!([3, 1])).is_err());
+        assert!(compare_with(MatchMode::ExactLength, &actual, &json!([3, 1, 2])).is_ok());
+        assert_eq!(
+            compare_with(MatchMode::ExactLength, &actual, &json!([3, 1])).unwrap_err(),
+            "$: expected 2 items, got 3"
+        );
+        assert!(compare_with(MatchMode::Exact, &actual, &json!([1, 2, 3])).is_ok());
+        assert_eq!(
+            compare_with(MatchMode::Exact, &actual, &json!([1, 3, 2])).unwrap_err(),
+            "$[1]: expected 3, got 2"
+        );
+
+        // Each expected item needs an item of its own, even when a looser
+        // expected item was matched first
+        let actual = json!([{ "id": 1, "tag": "x" }, { "id": 2 }]);
+        let expected = json!([{}, { "tag": "x" }]);
+        assert!(compare_with(MatchMode::ExactLength, &actual, &expected).is_ok());
+        let expected = json!([{ "tag": "x" }, { "tag": "x" }]);
+        assert!(compare_with(MatchMode::ExactLength, &actual, &expected).is_err());
+
+        // Large arrays where every item competes for the same matches
+        let actual = Value::Array((0..500).map(|id| json!({ "id": id })).collect());
+        let expected = Value::Array((0..500).map(|_| json!({})).collect());
+        assert!(compare_with(MatchMode::ExactLength, &actual, &expected).is_ok());
+        let expected = Value::Array((0..500).rev().map(|id| json!({ "id": id })).collect());
+        assert!(compare_with(MatchMode::ExactLength, &actual, &expected).is_ok());
+    }
+
+    #[test]
+    fn test_exact_rejects_extra_fields_unless_ignored() {
+        let actual =
+            json!({ "id": "9d3e", "name": "Ada", "items": [{ "sku": "A", "created_at": "x" }] });
+        let expected = json!({ "name": "Ada", "items": [{ "sku": "A" }] });
+
+        assert_eq!(
+            compare_with(MatchMode::Exact, &actual, &expected).unwrap_err(),
+            "$.id: unexpected field"
+        );
+
+        let options = BodyMatch {
+            mode: MatchMode::Exact,
+            ignore_paths: vec!["$.id".to_string(), "created_at".to_string()],
+        };
+        assert!(compare(&actual, &expected, &options).is_ok());
+
+        // Ignored paths are not compared even when expected
+        let options = BodyMatch {
+            mode: MatchMode::Subset,
+            ignore_paths: vec!["$.items[*].sku".to_string()],
+        };
+        assert!(compare(&actual, &json!({ "items": [{ "sku": "B" }] }), &options).is_ok());
+    }
+
+    #[test]
+    fn test_value_matchers() {
+        let actual = json!({
+            "id": "5f0c1f4e-8a0b-4c39-9d77-3b4c2a1e6f10",
+            "created_at": "2026-10-17T09:30:00.123Z",
+            "count": 3,
+        });
+        let expected = json!({
+            "id": "<any-uuid>",
+            "created_at": "<any-iso8601>",
+            "count": "<any-number>",
+        });
+        assert!(compare_with(MatchMode::Exact, &actual, &expected).is_ok());
+
+        let actual = json!({ "id": "42", "created_at": "yesterday", "count": "3" });
+        assert_eq!(
+            compare_with(MatchMode::Subset, &actual, &expected).unwrap_err(),
+            "$.id: expected <any-uuid>, got \"42\""
+        );
+        assert!(compare_with(
+            MatchMode::Subset,
+            &actual,
+            &json!({ "created_at": "<any-iso8601>" })
+        )
+        .is_err());
+
+        // Other strings in angle brackets are compared as text
+        assert!(compare_with(MatchMode::Subset, &json!("<user_id>"), &json!("<user_id>")).is_ok());
+    }
+
+    #[test]
+    fn test_parse_ignore_paths() {
+        assert!(IgnorePath::parse("$.items[*].created_at").is_ok());
+        assert!(IgnorePath::parse("$..id").is_ok());
+        assert!(IgnorePath::parse("$['odd key'][0]").is_ok());
+        assert!(IgnorePath::parse("updated_at").is_ok());
+        for path in ["", "$", "$.", "$..", "$[x]", "$.a[0"] {
+            assert!(IgnorePath::parse(path).is_err(), "{}", path);
+        }
+    }
+}
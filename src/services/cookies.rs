@@ -0,0 +1,292 @@
+use cookie::Cookie;
+use reqwest::header::{HeaderMap, HeaderValue, COOKIE, SET_COOKIE};
+use reqwest::{Request, Url};
+use serde::{Deserialize, Serialize};
+use std::sync::{Mutex, PoisonError};
+use time::OffsetDateTime;
+use utoipa::ToSchema;
+
+/// How long cookies set by responses are kept and sent back
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CookieJarScope {
+    /// Each example starts without cookies, unless its scenario is tagged
+    /// `@cookies`, which keeps them across the scenario's examples
+    #[default]
+    Example,
+    /// Kept across the examples of each scenario
+    Scenario,
+    /// Kept across every scenario of the run, in execution order
+    Run,
+}
+
+/// A change to the cookie jar made by a step before the request is sent
+#[derive(Debug, Clone, PartialEq)]
+pub enum CookieAction {
+    /// `cookie "session" is "abc"`
+    Set { name: String, value: String },
+    /// `cookie "session" is removed`
+    Remove(String),
+    /// `the cookie jar is cleared`
+    Clear,
+}
+
+/// Cookies kept between requests, sent back the way a browser would: by
+/// domain, path, `Secure` and expiry
+#[derive(Debug, Default)]
+pub struct CookieJar {
+    cookies: Mutex<Vec<StoredCookie>>,
+}
+
+#[derive(Debug, Clone)]
+struct StoredCookie {
+    name: String,
+    value: String,
+    /// Lowercase host or domain, without a leading dot
+    domain: String,
+    /// Set without a `Domain` attribute, so sent to that exact host only
+    host_only: bool,
+    path: String,
+    secure: bool,
+    expires: Option<OffsetDateTime>,
+}
+
+impl StoredCookie {
+    fn is_expired(&self, now: OffsetDateTime) -> bool {
+        self.expires.is_some_and(|at| at <= now)
+    }
+
+    fn matches(&self, url: &Url) -> bool {
+        let Some(host) = url.host_str().map(str::to_lowercase) else {
+            return false;
+        };
+        let domain_ok = if self.host_only {
+            host == self.domain
+        } else {
+            domain_match(&host, &self.domain)
+        };
+
+        domain_ok && path_match(url.path(), &self.path) && (!self.secure || is_secure(url))
+    }
+}
+
+impl CookieJar {
+    /// Store the cookies a response to `url` sets; an expired cookie removes
+    /// the stored one
+    pub fn store(&self, url: &Url, headers: &HeaderMap) {
+        let Some(host) = url.host_str().map(str::to_lowercase) else {
+            return;
+        };
+        let now = OffsetDateTime::now_utc();
+
+        for header in headers.get_all(SET_COOKIE) {
+            let Ok(header) = header.to_str() else {
+                continue;
+            };
+            let Ok(cookie) = Cookie::parse(header) else {
+                continue;
+            };
+
+            let (domain, host_only) = match cookie.domain().map(str::to_lowercase) {
+                // A response cannot set cookies for another site
+                Some(domain) if !domain_match(&host, &domain) => continue,
+                Some(domain) => (domain, false),
+                None => (host.clone(), true),
+            };
+            let path = match cookie.path() {
+                Some(path) if path.starts_with('/') => path.to_string(),
+                _ => default_path(url.path()),
+            };
+            let expires = match cookie.max_age() {
+                Some(max_age) => Some(now + max_age),
+                None => cookie.expires_datetime(),
+            };
+
+            self.insert(StoredCookie {
+                name: cookie.name().to_string(),
+                value: cookie.value().to_string(),
+                domain,
+                host_only,
+                path,
+                secure: cookie.secure().unwrap_or(false),
+                expires,
+            });
+        }
+    }
+
+    /// Apply a step's change, setting cookies for the host of `url`
+    pub fn apply(&self, action: &CookieAction, url: &Url) {
+        match action {
+            CookieAction::Set { name, value } => {
+                if let Some(host) = url.host_str() {
+                    self.insert(StoredCookie {
+                        name: name.clone(),
+                        value: value.clone(),
+                        domain: host.to_lowercase(),
+                        host_only: true,
+                        path: "/".to_string(),
+                        secure: false,
+                        expires: None,
+                    });
+                }
+            }
+            CookieAction::Remove(name) => self.lock().retain(|c| c.name != *name),
+            CookieAction::Clear => self.lock().clear(),
+        }
+    }
+
+    /// Value of a live cookie, the most recently set when several share the name
+    pub fn get(&self, name: &str) -> Option<String> {
+        let now = OffsetDateTime::now_utc();
+        self.lock()
+            .iter()
+            .rev()
+            .find(|c| c.name == name && !c.is_expired(now))
+            .map(|c| c.value.clone())
+    }
+
+    /// Add the cookies that match the request's URL to its `Cookie` header,
+    /// after any cookies a step put there
+    pub fn add_to(&self, request: &mut Request) {
+        let now = OffsetDateTime::now_utc();
+        let mut cookies: Vec<StoredCookie> = self
+            .lock()
+            .iter()
+            .filter(|c| !c.is_expired(now) && c.matches(request.url()))
+            .cloned()
+            .collect();
+        if cookies.is_empty() {
+            return;
+        }
+        // Longer paths first (RFC 6265 section 5.4)
+        cookies.sort_by_key(|c| std::cmp::Reverse(c.path.len()));
+
+        let mut pairs: Vec<String> = request
+            .headers()
+            .get(COOKIE)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| vec![v.to_string()])
+            .unwrap_or_default();
+        pairs.extend(cookies.iter().map(|c| format!("{}={}", c.name, c.value)));
+
+        if let Ok(value) = HeaderValue::from_str(&pairs.join("; ")) {
+            request.headers_mut().insert(COOKIE, value);
+        }
+    }
+
+    fn insert(&self, cookie: StoredCookie) {
+        let now = OffsetDateTime::now_utc();
+        let mut cookies = self.lock();
+        let same = |c: &StoredCookie| {
+            c.name == cookie.name && c.domain == cookie.domain && c.path == cookie.path
+        };
+        cookies.retain(|c| !same(c) && !c.is_expired(now));
+        if !cookie.is_expired(now) {
+            cookies.push(cookie);
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<StoredCookie>> {
+        self.cookies.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Whether `host` is `domain` or one of its subdomains
+fn domain_match(host: &str, domain: &str) -> bool {
+    host == domain
+        || (host.ends_with(domain)
+            && host[..host.len() - domain.len()].ends_with('.')
+            && host.parse::<std::net::IpAddr>().is_err())
+}
+
+/// Whether `path` is `cookie_path` or below it
+fn path_match(path: &str, cookie_path: &str) -> bool {
+    path == cookie_path
+        || (path.starts_with(cookie_path)
+            && (cookie_path.ends_with('/') || path[cookie_path.len()..].starts_with('/')))
+}
+
+/// Directory of the request path, for cookies set without a `Path`
+fn default_path(path: &str) -> String {
+    match path.rfind('/') {
+        Some(0) | None => "/".to_string(),
+        Some(end) => path[..end].to_string(),
+    }
+}
+
+/// `Secure` cookies go over HTTPS, and to loopback hosts as browsers allow
+fn is_secure(url: &Url) -> bool {
+    url.scheme() == "https" || matches!(url.host_str(), Some("localhost" | "127.0.0.1" | "[::1]"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(s: &str) -> Url {
+        Url::parse(s).unwrap()
+    }
+
+    fn set_cookies(values: &[&str]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for value in values {
+            headers.append(SET_COOKIE, HeaderValue::from_str(value).unwrap());
+        }
+        headers
+    }
+
+    fn cookie_header(jar: &CookieJar, to: &str) -> Option<String> {
+        let mut request = Request::new(reqwest::Method::GET, url(to));
+        jar.add_to(&mut request);
+        request
+            .headers()
+            .get(COOKIE)
+            .map(|v| v.to_str().unwrap().to_string())
+    }
+
+    #[test]
+    fn test_jar_follows_domain_path_and_expiry() {
+        let jar = CookieJar::default();
+        jar.store(
+            &url("https://api.example.com/auth/login"),
+            &set_cookies(&[
+                "session=s1; Path=/; HttpOnly; Secure",
+                "scoped=x",
+                "shared=y; Domain=.example.com; Path=/",
+                "foreign=z; Domain=other.com",
+                "gone=1; Max-Age=0",
+            ]),
+        );
+
+        assert_eq!(
+            cookie_header(&jar, "https://api.example.com/auth/me").as_deref(),
+            Some("scoped=x; session=s1; shared=y")
+        );
+        // Host-only and Secure cookies stay on their host and scheme
+        assert_eq!(
+            cookie_header(&jar, "http://www.example.com/").as_deref(),
+            Some("shared=y")
+        );
+        assert_eq!(jar.get("foreign"), None);
+        assert_eq!(jar.get("gone"), None);
+
+        // A later response deletes a cookie by expiring it
+        jar.store(
+            &url("https://api.example.com/auth/logout"),
+            &set_cookies(&["session=; Path=/; Expires=Thu, 01 Jan 1970 00:00:00 GMT"]),
+        );
+        assert_eq!(jar.get("session"), None);
+
+        let host = url("https://api.example.com/");
+        jar.apply(
+            &CookieAction::Set {
+                name: "theme".to_string(),
+                value: "dark".to_string(),
+            },
+            &host,
+        );
+        assert_eq!(jar.get("theme").as_deref(), Some("dark"));
+        jar.apply(&CookieAction::Clear, &host);
+        assert_eq!(cookie_header(&jar, "https://api.example.com/"), None);
+    }
+}
@@ -0,0 +1,237 @@
+use rand::distr::Alphanumeric;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use sha2::{Digest, Sha256};
+use std::sync::{Mutex, PoisonError};
+use time::format_description::well_known::{Rfc2822, Rfc3339};
+use time::{Duration, OffsetDateTime};
+use uuid::Uuid;
+
+/// Largest run seed, so seeds survive JSON clients that use doubles
+pub const MAX_SEED: u64 = (1 << 53) - 1;
+
+/// Longest value `{{random_string:N}}` produces
+const MAX_RANDOM_STRING_LEN: usize = 4096;
+
+/// Pick a seed for a run that was not given one
+pub fn random_seed() -> u64 {
+    rand::rng().random_range(0..=MAX_SEED)
+}
+
+/// Expands `{{generator}}` tokens into fresh values:
+///
+/// - `{{uuid}}` -- a UUID v4
+/// - `{{now}}`, `{{now:FORMAT}}` -- the current time as `rfc3339` (default),
+///   `rfc2822`, `date`, `unix` or `unix_ms`
+/// - `{{timestamp}}` -- the current Unix time in seconds
+/// - `{{random_int:MIN:MAX}}` -- an integer in `MIN..=MAX`
+/// - `{{random_email}}` -- an address at `example.com`
+/// - `{{random_string:N}}` -- `N` alphanumeric characters (default 16)
+///
+/// `now` and `timestamp` take an offset such as `+1d`, `-30m` or `+90s`, as in
+/// `{{now+1h:date}}` or `{{timestamp-1d}}`. Unknown or malformed tokens are
+/// left as they are.
+///
+/// Random values come from the run seed, the scenario and the example index,
+/// so running with the same seed reproduces them; times follow the clock.
+#[derive(Debug)]
+pub struct Generators {
+    rng: Mutex<ChaCha8Rng>,
+    /// Time `now` and `timestamp` are relative to, fixed for one example
+    now: OffsetDateTime,
+}
+
+impl Generators {
+    /// Generators for one example of a scenario in a run with `seed`
+    pub fn for_example(seed: u64, scenario_id: Uuid, example_index: i32) -> Self {
+        let mut hasher = Sha256::new();
+        hasher.update(seed.to_be_bytes());
+        hasher.update(scenario_id.as_bytes());
+        hasher.update(example_index.to_be_bytes());
+
+        Self {
+            rng: Mutex::new(ChaCha8Rng::from_seed(hasher.finalize().into())),
+            now: OffsetDateTime::now_utc(),
+        }
+    }
+
+    /// Replace every known `{{...}}` token in `text`
+    pub fn expand(&self, text: &str) -> String {
+        let mut result = String::with_capacity(text.len());
+        let mut rest = text;
+
+        while let Some(start) = rest.find("{{") {
+            let Some(len) = rest[start + 2..].find("}}") else {
+                break;
+            };
+            let token = &rest[start + 2..start + 2 + len];
+            result.push_str(&rest[..start]);
+            match self.generate(token.trim()) {
+                Some(value) => result.push_str(&value),
+                None => result.push_str(&rest[start..start + len + 4]),
+            }
+            rest = &rest[start + len + 4..];
+        }
+
+        result.push_str(rest);
+        result
+    }
+
+    /// Value for one token, `None` when it is not a valid generator
+    fn generate(&self, token: &str) -> Option<String> {
+        let (name, args) = match token.split_once(':') {
+            Some((name, args)) => (name, Some(args)),
+            None => (token, None),
+        };
+        let mut rng = self.rng.lock().unwrap_or_else(PoisonError::into_inner);
+
+        if let Some(offset) = name.strip_prefix("now") {
+            let time = self.now + parse_offset(offset)?;
+            return format_time(time, args.unwrap_or("rfc3339"));
+        }
+        if let Some(offset) = name.strip_prefix("timestamp") {
+            if args.is_some() {
+                return None;
+            }
+            return Some(
+                (self.now + parse_offset(offset)?)
+                    .unix_timestamp()
+                    .to_string(),
+            );
+        }
+
+        match (name, args) {
+            ("uuid", None) => Some(
+                uuid::Builder::from_random_bytes(rng.random())
+                    .into_uuid()
+                    .to_string(),
+            ),
+            ("random_int", Some(args)) => {
+                let (min, max) = args.split_once(':')?;
+                let (min, max) = (
+                    min.trim().parse::<i64>().ok()?,
+                    max.trim().parse::<i64>().ok()?,
+                );
+                (min <= max).then(|| rng.random_range(min..=max).to_string())
+            }
+            ("random_email", None) => {
+                let local: String = random_chars(&mut rng, 12).to_lowercase();
+                Some(format!("user-{}@example.com", local))
+            }
+            ("random_string", args) => {
+                let len = match args {
+                    Some(len) => len.trim().parse::<usize>().ok()?,
+                    None => 16,
+                };
+                (len <= MAX_RANDOM_STRING_LEN).then(|| random_chars(&mut rng, len))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Default for Generators {
+    /// Generators from a random seed
+    fn default() -> Self {
+        Self::for_example(random_seed(), Uuid::nil(), 0)
+    }
+}
+
+/// Parse an offset such as `+1d` or `-30m`; empty means no offset
+fn parse_offset(offset: &str) -> Option<Duration> {
+    if offset.is_empty() {
+        return Some(Duration::ZERO);
+    }
+
+    let (sign, rest) = match offset.split_at(1) {
+        ("+", rest) => (1, rest),
+        ("-", rest) => (-1, rest),
+        _ => return None,
+    };
+    let unit_at = rest.find(|c: char| !c.is_ascii_digit())?;
+    let amount = sign * rest[..unit_at].parse::<i64>().ok()?;
+
+    match &rest[unit_at..] {
+        "s" => Some(Duration::seconds(amount)),
+        "m" => Some(Duration::minutes(amount)),
+        "h" => Some(Duration::hours(amount)),
+        "d" => Some(Duration::days(amount)),
+        _ => None,
+    }
+}
+
+fn format_time(time: OffsetDateTime, format: &str) -> Option<String> {
+    match format.trim() {
+        "rfc3339" => time.format(&Rfc3339).ok(),
+        "rfc2822" => time.format(&Rfc2822).ok(),
+        "date" => Some(time.date().to_string()),
+        "unix" => Some(time.unix_timestamp().to_string()),
+        "unix_ms" => Some((time.unix_timestamp_nanos() / 1_000_000).to_string()),
+        _ => None,
+    }
+}
+
+fn random_chars(rng: &mut ChaCha8Rng, len: usize) -> String {
+    rng.sample_iter(Alphanumeric)
+        .take(len)
+        .map(char::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_generators() {
+        let generators = Generators::for_example(7, Uuid::nil(), 0);
+
+        let id = generators.expand("{{uuid}}");
+        assert_eq!(Uuid::parse_str(&id).unwrap().get_version_num(), 4);
+        assert_ne!(generators.expand("{{uuid}}"), id);
+
+        let n: i64 = generators.expand("{{ random_int:1:3 }}").parse().unwrap();
+        assert!((1..=3).contains(&n));
+        assert_eq!(generators.expand("{{random_string:12}}").len(), 12);
+        assert!(generators
+            .expand("{{random_email}}")
+            .ends_with("@example.com"));
+
+        let now = generators.now;
+        assert_eq!(
+            generators.expand("{{timestamp+1d}}"),
+            (now.unix_timestamp() + 86_400).to_string()
+        );
+        assert_eq!(
+            generators.expand("{{now-1h:unix}}"),
+            (now.unix_timestamp() - 3_600).to_string()
+        );
+        assert!(OffsetDateTime::parse(&generators.expand("{{now:rfc3339}}"), &Rfc3339).is_ok());
+
+        // Unknown and malformed tokens stay as they are
+        assert_eq!(
+            generators.expand("{{name}} {{random_int:9:1}} {{now+1w}} {{uuid"),
+            "{{name}} {{random_int:9:1}} {{now+1w}} {{uuid"
+        );
+    }
+
+    #[test]
+    fn test_same_seed_reproduces_values() {
+        let scenario_id = Uuid::new_v4();
+        let text = "{{uuid}}/{{random_string}}/{{random_int:0:1000000}}";
+
+        let first = Generators::for_example(42, scenario_id, 1).expand(text);
+        assert_eq!(
+            Generators::for_example(42, scenario_id, 1).expand(text),
+            first
+        );
+        assert_ne!(
+            Generators::for_example(43, scenario_id, 1).expand(text),
+            first
+        );
+        assert_ne!(
+            Generators::for_example(42, scenario_id, 2).expand(text),
+            first
+        );
+    }
+}
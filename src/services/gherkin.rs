@@ -2,6 +2,7 @@ use gherkin::{Feature, GherkinEnv, StepType};
 use serde::{Deserialize, Serialize};
 
 use crate::error::{AppError, AppResult};
+use crate::models::GherkinStep;
 
 /// Parsed Gherkin step with optional doc string and data table
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -15,6 +16,22 @@ pub struct ParsedStep {
     /// Data table embedded in the step
     #[serde(skip_serializing_if = "Option::is_none")]
     pub data_table: Option<Vec<serde_json::Value>>,
+    /// Cells of the data table as written, header row first
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data_table_cells: Option<Vec<Vec<String>>>,
+}
+
+impl From<ParsedStep> for GherkinStep {
+    fn from(s: ParsedStep) -> Self {
+        Self {
+            keyword: s.keyword,
+            keyword_type: s.keyword_type,
+            text: s.text,
+            doc_string: s.doc_string,
+            data_table: s.data_table,
+            data_table_cells: s.data_table_cells,
+        }
+    }
 }
 
 /// Parsed example row (test data + expected result)
@@ -106,6 +123,7 @@ impl GherkinService {
                     text: step.value.clone(),
                     doc_string,
                     data_table,
+                    data_table_cells: step.table.as_ref().map(|table| table.rows.clone()),
                 }
             })
             .collect()
@@ -138,7 +156,7 @@ impl GherkinService {
     }
 
     /// Parse a cell value into a JSON value with smart type detection
-    fn parse_cell_value(value: &str) -> serde_json::Value {
+    pub fn parse_cell_value(value: &str) -> serde_json::Value {
         // Try parsing as JSON literal first (for arrays, objects, null)
         if value.starts_with('[') || value.starts_with('{') || value == "null" {
             if let Ok(json) = serde_json::from_str::<serde_json::Value>(value) {
@@ -366,6 +384,12 @@ Feature: API with Data Tables
             table[1].get("active").and_then(|v| v.as_bool()),
             Some(false)
         );
+
+        // The cells are kept as written too, header row first
+        let cells = step.data_table_cells.as_ref().unwrap();
+        assert_eq!(cells.len(), 3);
+        assert_eq!(cells[0], ["email", "role", "active"]);
+        assert_eq!(cells[2][2], "false");
     }
 
     #[test]
@@ -0,0 +1,133 @@
+use crate::models::{Api, Scenario};
+
+/// When a hook scenario runs, from its `@before` or `@after` tag
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hook {
+    /// Before each example of the other scenarios of its collection
+    Before,
+    /// After each example, even when a before hook or the example failed
+    After,
+}
+
+impl Hook {
+    /// The hook a scenario is tagged as, `None` for regular scenarios
+    pub fn of(scenario: &Scenario) -> Option<Self> {
+        scenario.tags.iter().find_map(|tag| {
+            let tag = tag.trim_start_matches('@');
+            if tag.eq_ignore_ascii_case("before") {
+                Some(Self::Before)
+            } else if tag.eq_ignore_ascii_case("after") {
+                Some(Self::After)
+            } else {
+                None
+            }
+        })
+    }
+}
+
+/// A hook scenario with the API it calls
+#[derive(Debug, Clone)]
+pub struct HookScenario {
+    pub scenario: Scenario,
+    pub api: Api,
+}
+
+/// Hook scenarios of the collection a run tests, in creation order
+#[derive(Debug, Clone, Default)]
+pub struct ScenarioHooks {
+    pub before: Vec<HookScenario>,
+    pub after: Vec<HookScenario>,
+}
+
+impl ScenarioHooks {
+    /// Sort scenarios into before and after hooks, leaving out the others,
+    /// and order each by creation
+    pub fn new(scenarios: impl IntoIterator<Item = HookScenario>) -> Self {
+        let mut hooks = Self::default();
+        for hook in scenarios {
+            match Hook::of(&hook.scenario) {
+                Some(Hook::Before) => hooks.before.push(hook),
+                Some(Hook::After) => hooks.after.push(hook),
+                None => {}
+            }
+        }
+        for list in [&mut hooks.before, &mut hooks.after] {
+            list.sort_by_key(|hook| (hook.scenario.created_at, hook.scenario.id));
+        }
+        hooks
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.before.is_empty() && self.after.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn tagged(tags: &[&str]) -> Scenario {
+        let now = time::OffsetDateTime::now_utc();
+        Scenario {
+            id: Uuid::new_v4(),
+            api_id: Uuid::new_v4(),
+            title: "hook".to_string(),
+            description: None,
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            kind: Default::default(),
+            background_steps: serde_json::json!([]),
+            steps: serde_json::json!([]),
+            examples: serde_json::json!([]),
+            response_schema: None,
+            body_match: None,
+            variables: Default::default(),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    #[test]
+    fn test_hook_from_tags() {
+        assert_eq!(
+            Hook::of(&tagged(&["@smoke", "@Before"])),
+            Some(Hook::Before)
+        );
+        assert_eq!(Hook::of(&tagged(&["after"])), Some(Hook::After));
+        assert_eq!(Hook::of(&tagged(&["@smoke", "@beforehand"])), None);
+    }
+
+    #[test]
+    fn test_hooks_in_creation_order() {
+        let api = |scenario: Scenario| HookScenario {
+            api: Api {
+                id: scenario.api_id,
+                collection_id: Uuid::new_v4(),
+                name: "hook".to_string(),
+                http_method: "POST".to_string(),
+                endpoint: "/hook".to_string(),
+                severity: 1,
+                description: None,
+                response_schema: None,
+                sla_ms: None,
+                request_content_type: None,
+                auth: None,
+                redirect_policy: None,
+                created_at: scenario.created_at,
+                updated_at: scenario.updated_at,
+            },
+            scenario,
+        };
+        let first = tagged(&["@before"]);
+        let second = Scenario {
+            created_at: first.created_at + time::Duration::seconds(1),
+            ..tagged(&["@before"])
+        };
+        let regular = tagged(&["@smoke"]);
+
+        let hooks = ScenarioHooks::new([second.clone(), regular, first.clone()].map(api));
+        let ids: Vec<Uuid> = hooks.before.iter().map(|hook| hook.scenario.id).collect();
+        assert_eq!(ids, [first.id, second.id]);
+        assert!(hooks.after.is_empty());
+    }
+}
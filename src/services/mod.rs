@@ -1,7 +1,40 @@
+pub mod assertion;
 pub mod auth;
+pub mod body;
+pub mod body_match;
+pub mod cookies;
+pub mod generators;
 pub mod gherkin;
+pub mod hooks;
+pub mod redirect;
+pub mod request_auth;
+pub mod schema;
+pub mod secrets;
+pub mod steps;
 pub mod test_runner;
+pub mod timing;
+pub mod transcript;
+pub mod transport;
+pub mod variables;
+pub mod workflow;
 
+pub use assertion::{
+    BodyAssertion, Comparison, CookieAssertion, CookieCheck, CookieJarAssertion, HeaderAssertion,
+    JsonAssertion, Quantifier, RedirectAssertion,
+};
 pub use auth::{AuthService, Claims};
+pub use body::{FixtureFile, ResponseBody};
+pub use cookies::{CookieAction, CookieJar, CookieJarScope};
+pub use generators::{random_seed, Generators, MAX_SEED};
 pub use gherkin::{GherkinService, ParsedExample, ParsedFeature, ParsedScenario, ParsedStep};
+pub use hooks::{Hook, HookScenario, ScenarioHooks};
+pub use redirect::RedirectHop;
+pub use request_auth::{BootstrapScenario, RunAuth, TokenCache};
+pub use schema::ResponseSchema;
+pub use secrets::{OpenedVariables, SecretCipher};
+pub use steps::{PatternSyntax, StepKind, StepRegistry};
 pub use test_runner::{TestConfig, TestResult, TestRunner};
+pub use timing::RequestTiming;
+pub use transcript::SentRequest;
+pub use variables::{ResolvedVariable, VariableScope, VariableScopes};
+pub use workflow::WorkflowStepResult;
@@ -0,0 +1,154 @@
+use reqwest::header::{
+    HeaderMap, AUTHORIZATION, CONTENT_LENGTH, CONTENT_TYPE, COOKIE, LOCATION, PROXY_AUTHORIZATION,
+    TRANSFER_ENCODING,
+};
+use reqwest::{Method, Request, StatusCode, Url};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// A redirect followed on the way to the final response
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct RedirectHop {
+    /// Status of the redirect response
+    pub status: i16,
+    /// URL that answered with the redirect, secret query parameters redacted
+    pub url: String,
+    /// Absolute URL the response pointed to, secret query parameters redacted
+    pub location: String,
+}
+
+/// Target of a redirect response, `None` when the response is not a redirect
+/// or its `Location` is not an HTTP(S) URL
+pub fn location(status: StatusCode, headers: &HeaderMap, url: &Url) -> Option<Url> {
+    if !matches!(status.as_u16(), 301 | 302 | 303 | 307 | 308) {
+        return None;
+    }
+
+    let location = headers.get(LOCATION)?.to_str().ok()?;
+    url.join(location)
+        .ok()
+        .filter(|target| matches!(target.scheme(), "http" | "https"))
+}
+
+/// What is kept of a request to send it again to a redirect's target
+pub struct Resend {
+    method: Method,
+    url: Url,
+    headers: HeaderMap,
+    /// Buffered body; `None` inside when the body is streamed and cannot be
+    /// sent twice, such as a multipart form
+    body: Option<Option<Vec<u8>>>,
+}
+
+impl Resend {
+    pub fn new(request: &Request) -> Self {
+        Self {
+            method: request.method().clone(),
+            url: request.url().clone(),
+            headers: request.headers().clone(),
+            body: request
+                .body()
+                .map(|body| body.as_bytes().map(<[u8]>::to_vec)),
+        }
+    }
+
+    /// The request for the next hop, as browsers send it: 303, and 301 or 302
+    /// after a POST, become a GET without a body, while 307 and 308 repeat the
+    /// method and body. Credentials are dropped when the target is on another
+    /// origin, and cookies always, so the jar supplies those of the new URL.
+    /// `None` when a streamed body would have to be sent again.
+    pub fn to(&self, status: StatusCode, target: Url) -> Option<Request> {
+        let becomes_get = match status.as_u16() {
+            303 => self.method != Method::HEAD,
+            301 | 302 => self.method == Method::POST,
+            _ => false,
+        };
+
+        let mut headers = self.headers.clone();
+        headers.remove(COOKIE);
+        if target.origin() != self.url.origin() {
+            headers.remove(AUTHORIZATION);
+            headers.remove(PROXY_AUTHORIZATION);
+        }
+
+        let (method, body) = if becomes_get {
+            for name in [CONTENT_TYPE, CONTENT_LENGTH, TRANSFER_ENCODING] {
+                headers.remove(name);
+            }
+            (Method::GET, None)
+        } else {
+            match &self.body {
+                Some(None) => return None,
+                Some(Some(bytes)) => (self.method.clone(), Some(bytes.clone())),
+                None => (self.method.clone(), None),
+            }
+        };
+
+        let mut request = Request::new(method, target);
+        *request.headers_mut() = headers;
+        *request.body_mut() = body.map(Into::into);
+        Some(request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    fn post(url: &str) -> Request {
+        let mut request = Request::new(Method::POST, url.parse().unwrap());
+        let headers = request.headers_mut();
+        headers.insert(AUTHORIZATION, HeaderValue::from_static("Bearer abc"));
+        headers.insert(COOKIE, HeaderValue::from_static("session=1"));
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        *request.body_mut() = Some(b"{\"a\":1}".to_vec().into());
+        request
+    }
+
+    #[test]
+    fn test_location() {
+        let url: Url = "https://example.com/a/login?next=1".parse().unwrap();
+        let mut headers = HeaderMap::new();
+        headers.insert(LOCATION, HeaderValue::from_static("../home"));
+
+        assert_eq!(
+            location(StatusCode::FOUND, &headers, &url)
+                .unwrap()
+                .as_str(),
+            "https://example.com/home"
+        );
+        assert!(location(StatusCode::OK, &headers, &url).is_none());
+        assert!(location(StatusCode::NOT_MODIFIED, &headers, &url).is_none());
+
+        headers.insert(LOCATION, HeaderValue::from_static("ftp://example.com/file"));
+        assert!(location(StatusCode::MOVED_PERMANENTLY, &headers, &url).is_none());
+    }
+
+    #[test]
+    fn test_resend_rewrites_method_and_drops_credentials() {
+        let resend = Resend::new(&post("https://example.com/login"));
+
+        let next = resend
+            .to(
+                StatusCode::FOUND,
+                "https://example.com/home".parse().unwrap(),
+            )
+            .unwrap();
+        assert_eq!(next.method(), Method::GET);
+        assert!(next.body().is_none());
+        assert!(next.headers().get(CONTENT_TYPE).is_none());
+        assert!(next.headers().get(COOKIE).is_none());
+        assert_eq!(next.headers()[AUTHORIZATION], "Bearer abc");
+
+        let next = resend
+            .to(
+                StatusCode::TEMPORARY_REDIRECT,
+                "https://other.example.com/login".parse().unwrap(),
+            )
+            .unwrap();
+        assert_eq!(next.method(), Method::POST);
+        assert_eq!(next.body().unwrap().as_bytes(), Some(&b"{\"a\":1}"[..]));
+        assert!(next.headers().get(AUTHORIZATION).is_none());
+    }
+}
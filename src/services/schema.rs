@@ -0,0 +1,78 @@
+use jsonschema::Validator;
+use serde_json::Value;
+
+/// Compiled JSON Schema (draft 2020-12) for validating response bodies
+#[derive(Debug, Clone)]
+pub struct ResponseSchema {
+    validator: Validator,
+}
+
+impl ResponseSchema {
+    /// Compile a schema document. Errors describe why the schema is invalid.
+    pub fn compile(schema: &Value) -> Result<Self, String> {
+        let validator = jsonschema::draft202012::new(schema)
+            .map_err(|e| format!("Invalid response schema: {}", e))?;
+
+        Ok(Self { validator })
+    }
+
+    /// Validate a response body, reporting every violation with its path
+    pub fn validate(&self, body: &Value) -> Result<(), String> {
+        let violations: Vec<String> = self
+            .validator
+            .iter_errors(body)
+            .map(|error| {
+                let path = error.instance_path().as_str();
+                let path = if path.is_empty() { "/" } else { path };
+                format!("{}: {}", path, error)
+            })
+            .collect();
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(format!(
+                "Response body does not match schema: {}",
+                violations.join("; ")
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_reports_every_violation() {
+        let schema = ResponseSchema::compile(&json!({
+            "type": "object",
+            "required": ["id", "items"],
+            "properties": {
+                "id": { "type": "integer" },
+                "items": { "type": "array", "items": { "type": "string" } }
+            }
+        }))
+        .unwrap();
+
+        assert!(schema
+            .validate(&json!({ "id": 1, "items": ["a", "b"] }))
+            .is_ok());
+
+        let err = schema
+            .validate(&json!({ "id": "1", "items": ["a", 2] }))
+            .unwrap_err();
+        assert!(err.contains("/id:"), "{}", err);
+        assert!(err.contains("/items/1:"), "{}", err);
+
+        let err = schema.validate(&json!({})).unwrap_err();
+        assert!(err.contains("/: \"id\" is a required property"), "{}", err);
+    }
+
+    #[test]
+    fn test_invalid_schema_is_rejected() {
+        assert!(ResponseSchema::compile(&json!({ "type": "not-a-type" })).is_err());
+        assert!(ResponseSchema::compile(&json!({ "minimum": "ten" })).is_err());
+    }
+}
@@ -0,0 +1,1539 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::{Arc, LazyLock};
+use utoipa::ToSchema;
+
+use crate::error::{AppError, AppResult};
+use crate::models::{BodyEncoding, GherkinStep, RedirectPolicy, MAX_REDIRECT_HOPS};
+use crate::services::assertion::{
+    BodyAssertion, CookieAssertion, CookieJarAssertion, HeaderAssertion, JsonAssertion,
+    RedirectAssertion,
+};
+use crate::services::cookies::CookieAction;
+use crate::services::gherkin::GherkinService;
+use crate::services::test_runner::{StepContext, TestRunner, VariableCapture};
+use crate::services::workflow::CALL_STEP;
+
+/// Which steps a definition applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StepKind {
+    /// Given and When steps, which build the request
+    Request,
+    /// Then steps, which check the response
+    Outcome,
+}
+
+impl StepKind {
+    pub fn of(step: &GherkinStep) -> Self {
+        if step.keyword_type == "Outcome" {
+            Self::Outcome
+        } else {
+            Self::Request
+        }
+    }
+}
+
+/// How a definition's pattern is written
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PatternSyntax {
+    /// `the status should be {int}`, with `(optional)` text and `a/b`
+    /// alternatives
+    CucumberExpression,
+    Regex,
+}
+
+/// Value of one step parameter
+#[derive(Debug, Clone, PartialEq)]
+pub enum StepArg {
+    Int(i64),
+    Float(f64),
+    Text(String),
+}
+
+/// Converts the text matched by a parameter into its value
+pub type ParameterTransform = fn(&str) -> StepArg;
+
+/// Applies a matched step to the context, or explains why it cannot
+pub type StepHandler =
+    Box<dyn Fn(&mut StepContext, &StepMatch<'_>) -> Result<(), String> + Send + Sync>;
+
+/// Regex of a cucumber expression, with the type name and transform of each group
+type CompiledExpression = (String, Vec<String>, Vec<Option<ParameterTransform>>);
+
+/// A `{name}` parameter usable in cucumber expressions
+#[derive(Debug, Clone)]
+pub struct ParameterType {
+    pub name: String,
+    /// Regex the parameter matches; it has no capture groups of its own
+    pub regex: String,
+    transform: ParameterTransform,
+}
+
+/// A step matched by a definition
+#[derive(Debug)]
+pub struct StepMatch<'a> {
+    /// Step text the definition matched, trimmed and without a trailing `:`
+    pub text: &'a str,
+    pub args: Vec<StepArg>,
+    /// Example row the step runs for
+    pub example: &'a serde_json::Value,
+}
+
+impl StepMatch<'_> {
+    /// Text of parameter `index`, empty when it is not text
+    pub fn string(&self, index: usize) -> &str {
+        match self.args.get(index) {
+            Some(StepArg::Text(text)) => text,
+            _ => "",
+        }
+    }
+
+    pub fn int(&self, index: usize) -> Result<i64, String> {
+        match self.args.get(index) {
+            Some(StepArg::Int(n)) => Ok(*n),
+            Some(StepArg::Text(text)) => Err(format!("{} is not a valid integer", text)),
+            _ => Err(format!("Parameter {} is not an integer", index + 1)),
+        }
+    }
+
+    pub fn float(&self, index: usize) -> Result<f64, String> {
+        match self.args.get(index) {
+            Some(StepArg::Float(n)) => Ok(*n),
+            Some(StepArg::Int(n)) => Ok(*n as f64),
+            _ => Err(format!("Parameter {} is not a number", index + 1)),
+        }
+    }
+}
+
+/// A step pattern and the handler applied to the steps it matches
+pub struct StepDefinition {
+    pub kind: StepKind,
+    pub pattern: String,
+    pub syntax: PatternSyntax,
+    pub description: String,
+    /// Parameter type of each capture, empty for regex groups
+    pub parameters: Vec<String>,
+    regex: Regex,
+    transforms: Vec<Option<ParameterTransform>>,
+    /// Matched against the step as written, before `<name>` placeholders are
+    /// replaced by their values
+    before_substitution: bool,
+    handler: StepHandler,
+}
+
+impl StepDefinition {
+    /// Match this definition against the text as written, so a step such as
+    /// `I save "$.id" as <user_id>` keeps its placeholder
+    pub fn before_substitution(&mut self) -> &mut Self {
+        self.before_substitution = true;
+        self
+    }
+
+    /// Parameters of the step, `None` when it does not match
+    fn matches(&self, text: &str) -> Option<Vec<StepArg>> {
+        let captures = self.regex.captures(text)?;
+        let args = captures
+            .iter()
+            .skip(1)
+            .zip(&self.transforms)
+            .map(|(capture, transform)| {
+                let text = capture.map_or("", |c| c.as_str());
+                match transform {
+                    Some(transform) => transform(text),
+                    None => StepArg::Text(text.to_string()),
+                }
+            })
+            .collect();
+        Some(args)
+    }
+
+    pub fn handle(&self, context: &mut StepContext, step: &StepMatch<'_>) -> Result<(), String> {
+        (self.handler)(context, step)
+    }
+}
+
+impl fmt::Debug for StepDefinition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StepDefinition")
+            .field("kind", &self.kind)
+            .field("pattern", &self.pattern)
+            .field("syntax", &self.syntax)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Step definitions a run matches its steps against. The first definition
+/// of the step's kind that matches, in registration order, handles it; a
+/// step no definition matches is undefined.
+#[derive(Debug)]
+pub struct StepRegistry {
+    parameter_types: Vec<ParameterType>,
+    definitions: Vec<StepDefinition>,
+}
+
+impl Default for StepRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StepRegistry {
+    /// A registry with the standard `{int}`, `{float}`, `{word}`, `{string}`
+    /// and `{}` parameter types and no steps
+    pub fn new() -> Self {
+        let standard: [(&str, &str, ParameterTransform); 5] = [
+            ("int", r"-?\d+", int_arg),
+            ("float", r"-?\d*\.?\d+", float_arg),
+            ("word", r"[^\s]+", text_arg),
+            ("string", r#""[^"]*"|'[^']*'"#, unquoted_arg),
+            ("", r".*", text_arg),
+        ];
+
+        Self {
+            parameter_types: standard
+                .into_iter()
+                .map(|(name, regex, transform)| ParameterType {
+                    name: name.to_string(),
+                    regex: regex.to_string(),
+                    transform,
+                })
+                .collect(),
+            definitions: Vec::new(),
+        }
+    }
+
+    /// The built-in steps, shared by every run
+    pub fn builtin() -> Arc<Self> {
+        static BUILTIN: LazyLock<Arc<StepRegistry>> =
+            LazyLock::new(|| Arc::new(StepRegistry::with_builtin_steps()));
+        BUILTIN.clone()
+    }
+
+    pub fn definitions(&self) -> &[StepDefinition] {
+        &self.definitions
+    }
+
+    pub fn parameter_types(&self) -> &[ParameterType] {
+        &self.parameter_types
+    }
+
+    /// Add a `{name}` parameter type. Its regex must not capture.
+    pub fn define_parameter_type(
+        &mut self,
+        name: &str,
+        regex: &str,
+        transform: ParameterTransform,
+    ) -> AppResult<()> {
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(AppError::Validation(format!(
+                "Invalid parameter type name \"{}\"",
+                name
+            )));
+        }
+        if self.parameter_type(name).is_some() {
+            return Err(AppError::Validation(format!(
+                "Parameter type {{{}}} is already defined",
+                name
+            )));
+        }
+        let compiled = Regex::new(regex)
+            .map_err(|e| AppError::Validation(format!("Invalid regex for {{{}}}: {}", name, e)))?;
+        if compiled.captures_len() > 1 {
+            return Err(AppError::Validation(format!(
+                "The regex for {{{}}} must use non-capturing groups",
+                name
+            )));
+        }
+
+        self.parameter_types.push(ParameterType {
+            name: name.to_string(),
+            regex: regex.to_string(),
+            transform,
+        });
+        Ok(())
+    }
+
+    /// Add a definition written as a cucumber expression, matched case-insensitively
+    pub fn register(
+        &mut self,
+        kind: StepKind,
+        expression: &str,
+        description: &str,
+        handler: impl Fn(&mut StepContext, &StepMatch<'_>) -> Result<(), String> + Send + Sync + 'static,
+    ) -> AppResult<&mut StepDefinition> {
+        let (regex, parameters, transforms) = self.compile_expression(expression)?;
+        let regex = Regex::new(&regex).map_err(|e| {
+            AppError::Validation(format!("Invalid step expression \"{}\": {}", expression, e))
+        })?;
+
+        Ok(self.push(StepDefinition {
+            kind,
+            pattern: expression.to_string(),
+            syntax: PatternSyntax::CucumberExpression,
+            description: description.to_string(),
+            parameters,
+            regex,
+            transforms,
+            before_substitution: false,
+            handler: Box::new(handler),
+        }))
+    }
+
+    /// Add a definition written as a regex; its capture groups are passed as text
+    pub fn register_regex(
+        &mut self,
+        kind: StepKind,
+        regex: &str,
+        description: &str,
+        handler: impl Fn(&mut StepContext, &StepMatch<'_>) -> Result<(), String> + Send + Sync + 'static,
+    ) -> AppResult<&mut StepDefinition> {
+        let compiled = Regex::new(regex).map_err(|e| {
+            AppError::Validation(format!("Invalid step regex \"{}\": {}", regex, e))
+        })?;
+        let groups = compiled.captures_len() - 1;
+
+        Ok(self.push(StepDefinition {
+            kind,
+            pattern: regex.to_string(),
+            syntax: PatternSyntax::Regex,
+            description: description.to_string(),
+            parameters: vec![String::new(); groups],
+            regex: compiled,
+            transforms: vec![None; groups],
+            before_substitution: false,
+            handler: Box::new(handler),
+        }))
+    }
+
+    /// The definition handling a step, its matched text and its parameters.
+    /// Definitions matching before substitution see `raw`, the others `text`.
+    pub fn find<'t>(
+        &self,
+        kind: StepKind,
+        raw: &'t str,
+        text: &'t str,
+    ) -> Option<(&StepDefinition, &'t str, Vec<StepArg>)> {
+        let (raw, text) = (normalize(raw), normalize(text));
+
+        self.definitions
+            .iter()
+            .filter(|definition| definition.kind == kind)
+            .find_map(|definition| {
+                let input = if definition.before_substitution {
+                    raw
+                } else {
+                    text
+                };
+                definition
+                    .matches(input)
+                    .map(|args| (definition, input, args))
+            })
+    }
+
+    fn push(&mut self, definition: StepDefinition) -> &mut StepDefinition {
+        self.definitions.push(definition);
+        self.definitions
+            .last_mut()
+            .expect("definition was just added")
+    }
+
+    fn parameter_type(&self, name: &str) -> Option<&ParameterType> {
+        self.parameter_types.iter().find(|t| t.name == name)
+    }
+
+    /// Translate a cucumber expression into an anchored, case-insensitive
+    /// regex, with the parameter types of its groups
+    fn compile_expression(&self, expression: &str) -> AppResult<CompiledExpression> {
+        let invalid = |reason: &str| {
+            AppError::Validation(format!(
+                "Invalid step expression \"{}\": {}",
+                expression, reason
+            ))
+        };
+
+        let mut regex = String::from("(?i)^");
+        let mut parameters = Vec::new();
+        let mut transforms = Vec::new();
+        let mut literal = String::new();
+        let mut chars = expression.chars();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => {
+                    let escaped = chars.next().ok_or_else(|| invalid("trailing \\"))?;
+                    regex.push_str(&literal_regex(&std::mem::take(&mut literal)));
+                    regex.push_str(&regex::escape(&escaped.to_string()));
+                }
+                '{' => {
+                    let name = take_until(&mut chars, '}').ok_or_else(|| invalid("unclosed {"))?;
+                    let parameter = self
+                        .parameter_type(&name)
+                        .ok_or_else(|| invalid(&format!("unknown parameter type {{{}}}", name)))?;
+                    regex.push_str(&literal_regex(&std::mem::take(&mut literal)));
+                    regex.push_str(&format!("({})", parameter.regex));
+                    parameters.push(name);
+                    transforms.push(Some(parameter.transform));
+                }
+                '(' => {
+                    let optional =
+                        take_until(&mut chars, ')').ok_or_else(|| invalid("unclosed ("))?;
+                    if optional.contains(['{', '/']) {
+                        return Err(invalid(
+                            "optional text cannot hold parameters or alternatives",
+                        ));
+                    }
+                    regex.push_str(&literal_regex(&std::mem::take(&mut literal)));
+                    regex.push_str(&format!("(?:{})?", regex::escape(&optional)));
+                }
+                _ => literal.push(c),
+            }
+        }
+
+        regex.push_str(&literal_regex(&literal));
+        regex.push('$');
+        Ok((regex, parameters, transforms))
+    }
+
+    /// The parameter types and steps every run understands
+    fn with_builtin_steps() -> Self {
+        let mut registry = Self::new();
+        let custom: [(&str, &str, ParameterTransform); 6] = [
+            ("name", r#""[^"]*"|'[^']*'|[^\s"']+"#, unquoted_arg),
+            (
+                "path",
+                r#""[^"]*"|'[^']*'|(?:/|https?://|<|\{\{)[^\s"']*"#,
+                unquoted_arg,
+            ),
+            ("jsonpath", r#""\$[^"]*"|'\$[^']*'"#, unquoted_arg),
+            (
+                "duration",
+                r"\d+(?:\.\d+)?\s*(?:ms|milliseconds?|s|secs?|seconds?)",
+                duration_arg,
+            ),
+            (
+                "method",
+                "GET|POST|PUT|PATCH|DELETE|HEAD|OPTIONS",
+                method_arg,
+            ),
+            (
+                "encoding",
+                "(?:an? )?(?:json|application/json|form|urlencoded|form-urlencoded\
+                 |x-www-form-urlencoded|application/x-www-form-urlencoded\
+                 |multipart|multipart form|multipart/form-data)",
+                encoding_arg,
+            ),
+        ];
+        for (name, regex, transform) in custom {
+            registry
+                .define_parameter_type(name, regex, transform)
+                .expect("valid built-in parameter type");
+        }
+
+        register_request_steps(&mut registry).expect("valid built-in request steps");
+        register_outcome_steps(&mut registry).expect("valid built-in outcome steps");
+        registry
+    }
+}
+
+fn register_request_steps(registry: &mut StepRegistry) -> AppResult<()> {
+    use StepKind::Request;
+
+    // Workflow scenarios split their steps at call steps before matching the
+    // rest, so this only handles call steps in single-API scenarios
+    registry.register(
+        Request,
+        CALL_STEP,
+        "Start the next call of a workflow scenario, to an API of the project by name or as \"METHOD /path\"",
+        |_, _| Err("Only workflow scenarios can call other APIs".to_string()),
+    )?;
+
+    for expression in [
+        "(the )request body is (the )fixture {string}",
+        "I upload/send (the )fixture {string}",
+    ] {
+        registry.register(
+            Request,
+            expression,
+            "Send a project fixture as the request body",
+            |context, step| {
+                context.request_fixture = Some(non_empty(step.string(0), "Fixture name")?);
+                Ok(())
+            },
+        )?;
+    }
+
+    for expression in [
+        "(the )cookie {string} is {string}",
+        "(the )cookie {string} is set to {string}",
+    ] {
+        registry.register(
+            Request,
+            expression,
+            "Put a cookie in the jar before the request is sent",
+            |context, step| {
+                context.cookie_actions.push(CookieAction::Set {
+                    name: non_empty(step.string(0), "Cookie name")?,
+                    value: step.string(1).to_string(),
+                });
+                Ok(())
+            },
+        )?;
+    }
+    registry.register(
+        Request,
+        "(the )cookie {string} is removed/cleared/deleted",
+        "Remove a cookie from the jar",
+        |context, step| {
+            let name = non_empty(step.string(0), "Cookie name")?;
+            context.cookie_actions.push(CookieAction::Remove(name));
+            Ok(())
+        },
+    )?;
+    for expression in ["(the )cookie jar is cleared", "(the )cookies are cleared"] {
+        registry.register(Request, expression, "Empty the cookie jar", |context, _| {
+            context.cookie_actions.push(CookieAction::Clear);
+            Ok(())
+        })?;
+    }
+
+    for expression in ["redirects are not followed", "(I )do not follow redirects"] {
+        registry.register(
+            Request,
+            expression,
+            "Return redirect responses instead of following them",
+            |context, _| {
+                context.redirect_policy = Some(RedirectPolicy {
+                    follow: false,
+                    ..Default::default()
+                });
+                Ok(())
+            },
+        )?;
+    }
+    for expression in [
+        "at most {int} redirect(s) is/are followed",
+        "(I )follow at most {int} redirect(s)",
+    ] {
+        registry.register(
+            Request,
+            expression,
+            "Follow a limited number of redirects",
+            |context, step| {
+                let hops = u32::try_from(step.int(0)?)
+                    .map_err(|_| "The number of redirects cannot be negative".to_string())?;
+                context.redirect_policy = Some(RedirectPolicy {
+                    follow: true,
+                    max_hops: hops.min(MAX_REDIRECT_HOPS),
+                });
+                Ok(())
+            },
+        )?;
+    }
+    for expression in ["redirects are followed", "(I )follow redirects"] {
+        registry.register(
+            Request,
+            expression,
+            "Follow redirects with the default limit",
+            |context, _| {
+                context.redirect_policy = Some(RedirectPolicy::default());
+                Ok(())
+            },
+        )?;
+    }
+
+    registry.register(
+        Request,
+        "(I set )(the )(request )header {name} to/is/= {}",
+        "Send a request header",
+        set_header,
+    )?;
+    registry.register(
+        Request,
+        "(I set )(the )(request )header {name} with value {}",
+        "Send a request header",
+        set_header,
+    )?;
+    registry.register(
+        Request,
+        "(I set )(the )query param/parameter {name} to/is/=/equals/as {}",
+        "Add a query parameter; a JSON array value repeats it once per item",
+        |context, step| {
+            let name = non_empty(step.string(0), "Query parameter name")?;
+            let value = unquote(step.string(1));
+            match serde_json::from_str::<serde_json::Value>(value) {
+                Ok(serde_json::Value::Array(items)) => {
+                    for item in &items {
+                        context
+                            .query_params
+                            .push((name.clone(), TestRunner::placeholder_value(item)));
+                    }
+                }
+                _ => context.query_params.push((name, value.to_string())),
+            }
+            Ok(())
+        },
+    )?;
+    registry.register(
+        Request,
+        "(I set )(the )path param/parameter {name} to/is/=/equals/as {}",
+        "Fill a {name} segment of the API endpoint",
+        |context, step| {
+            let name = non_empty(step.string(0), "Path parameter name")?;
+            context
+                .path_params
+                .insert(name, unquote(step.string(1)).to_string());
+            Ok(())
+        },
+    )?;
+
+    for expression in [
+        "(the )request/body/payload/form is sent/encoded/submitted as {encoding}",
+        "I send/submit/encode the request/body/payload/form as {encoding}",
+    ] {
+        registry.register(
+            Request,
+            expression,
+            "Encode the request body as JSON, a form or multipart",
+            |context, step| {
+                context.body_encoding = Some(
+                    BodyEncoding::parse(step.string(0))
+                        .ok_or_else(|| format!("Unknown body encoding {}", step.string(0)))?,
+                );
+                Ok(())
+            },
+        )?;
+    }
+
+    registry.register(
+        Request,
+        "(the )request body/payload is",
+        "Send the step's JSON doc string, else the example row, as the request body",
+        |context, step| {
+            if context.request_body.is_none() {
+                context.request_body = Some(step.example.clone());
+            }
+            Ok(())
+        },
+    )?;
+    registry.register(
+        Request,
+        "(the )request body/payload is {}",
+        "Send inline JSON as the request body",
+        |context, step| {
+            let body = serde_json::from_str(step.string(0))
+                .map_err(|e| format!("The request body is not valid JSON: {}", e))?;
+            context.request_body = Some(body);
+            Ok(())
+        },
+    )?;
+    registry.register(
+        Request,
+        "(the )form fields are",
+        "Send the step's data table as form fields",
+        |_, _| Ok(()),
+    )?;
+    registry.register(
+        Request,
+        "I send (the )following items/records/rows",
+        "Send the step's data table as a JSON array, one object per row under the header",
+        table_items,
+    )?;
+    registry.register(
+        Request,
+        "I send (the )following item/record/object/fields",
+        "Send the step's two-column data table of names and values as a JSON object",
+        table_object,
+    )?;
+
+    for expression in [
+        "I send a/an {method} request( with body)",
+        "I send a/an {method} request with body {}",
+        "I send a/an {method} request to {path}( with body)",
+        "I send a/an {method} request to {path} with body {}",
+        "I {method} {path}( with body)",
+        "I {method} {path} with body {}",
+    ] {
+        registry.register(
+            Request,
+            expression,
+            "Describe the request the API defines; `with body` adds inline JSON, or the example row",
+            body_from_text,
+        )?;
+    }
+    for expression in ["I send the request", "the request is sent"] {
+        registry.register(
+            Request,
+            expression,
+            "Describe sending the request the API defines",
+            |_, _| Ok(()),
+        )?;
+    }
+
+    Ok(())
+}
+
+fn register_outcome_steps(registry: &mut StepRegistry) -> AppResult<()> {
+    use StepKind::Outcome;
+
+    registry
+        .register(
+            Outcome,
+            "(I )save {jsonpath} as {}",
+            "Save a response value as a run variable, referenced later as <name>",
+            |context, step| {
+                let name = step
+                    .string(1)
+                    .trim()
+                    .trim_matches(|c| matches!(c, '<' | '>' | '"' | '\''));
+                if name.is_empty() || name.contains(char::is_whitespace) {
+                    return Err(format!("Invalid variable name {}", step.string(1)));
+                }
+                context.captures.push(VariableCapture {
+                    path: step.string(0).to_string(),
+                    name: name.to_string(),
+                });
+                Ok(())
+            },
+        )?
+        .before_substitution();
+
+    registry.register(
+        Outcome,
+        "(the )response (body )should equal/match/be (the )fixture {string}",
+        "Compare the response body with a project fixture",
+        |context, step| {
+            context.expected_fixture = Some(non_empty(step.string(0), "Fixture name")?);
+            Ok(())
+        },
+    )?;
+
+    for (expression, contains) in [
+        ("(the )final URL should be {string}", false),
+        ("(the )final URL should contain {string}", true),
+    ] {
+        registry.register(
+            Outcome,
+            expression,
+            "Check the URL of the final response, by path and query when it starts with /",
+            move |context, step| {
+                context
+                    .redirect_assertions
+                    .push(RedirectAssertion::FinalUrl {
+                        expected: step.string(0).to_string(),
+                        contains,
+                    });
+                Ok(())
+            },
+        )?;
+    }
+    registry.register(
+        Outcome,
+        "(the )request/response should not be redirected",
+        "Check that no redirect was followed",
+        |context, _| {
+            context
+                .redirect_assertions
+                .push(RedirectAssertion::Count(0));
+            Ok(())
+        },
+    )?;
+    registry.register(
+        Outcome,
+        "(the )request/response should be redirected {int} time(s)",
+        "Check the number of redirects followed",
+        |context, step| {
+            let count = usize::try_from(step.int(0)?)
+                .map_err(|_| "The number of redirects cannot be negative".to_string())?;
+            context
+                .redirect_assertions
+                .push(RedirectAssertion::Count(count));
+            Ok(())
+        },
+    )?;
+    registry.register(
+        Outcome,
+        "(the )request/response should be redirected once/twice",
+        "Check the number of redirects followed",
+        |context, step| {
+            let count = if step.text.to_lowercase().ends_with("twice") {
+                2
+            } else {
+                1
+            };
+            context
+                .redirect_assertions
+                .push(RedirectAssertion::Count(count));
+            Ok(())
+        },
+    )?;
+    registry.register(
+        Outcome,
+        "redirect {int} should be {int}",
+        "Check the status of one redirect, numbered from 1",
+        |context, step| redirect_hop(context, step, true, false),
+    )?;
+    registry.register(
+        Outcome,
+        "redirect {int} should be {int} to {string}",
+        "Check the status and target of one redirect",
+        |context, step| redirect_hop(context, step, true, true),
+    )?;
+    registry.register(
+        Outcome,
+        "redirect {int} should go to {string}",
+        "Check the target of one redirect",
+        |context, step| redirect_hop(context, step, false, true),
+    )?;
+
+    registry.register(
+        Outcome,
+        "{jsonpath} should {}",
+        "Check values of the JSON body: equal, contain, match, be greater than, exist and more, \
+         optionally for `all` or `any` matches",
+        |context, step| {
+            context
+                .json_assertions
+                .push(JsonAssertion::parse(step.text).ok_or_else(|| unsupported(step))?);
+            Ok(())
+        },
+    )?;
+    registry.register(
+        Outcome,
+        "(the )(response )header {string} should {}",
+        "Check a response header",
+        |context, step| {
+            context
+                .header_assertions
+                .push(HeaderAssertion::parse(step.text).ok_or_else(|| unsupported(step))?);
+            Ok(())
+        },
+    )?;
+    registry.register(
+        Outcome,
+        "(the )cookie jar should contain {string}",
+        "Check that the jar holds a cookie once the response's cookies are stored",
+        |context, step| cookie_jar(context, step, true, None),
+    )?;
+    registry.register(
+        Outcome,
+        "(the )cookie jar should contain {string} with value {string}",
+        "Check a cookie's value in the jar",
+        |context, step| cookie_jar(context, step, true, Some(step.string(1))),
+    )?;
+    registry.register(
+        Outcome,
+        "(the )cookie jar should not contain {string}",
+        "Check that the jar does not hold a cookie",
+        |context, step| cookie_jar(context, step, false, None),
+    )?;
+    registry.register(
+        Outcome,
+        "(the )(response )cookie {string} should {}",
+        "Check a cookie set by the response: its value and attributes",
+        |context, step| {
+            context
+                .cookie_assertions
+                .push(CookieAssertion::parse(step.text).ok_or_else(|| unsupported(step))?);
+            Ok(())
+        },
+    )?;
+    for (expression, description) in [
+        ("xpath {string} should {}", "Check an XPath of an XML body"),
+        (
+            "(the )response body/text should {}",
+            "Check the raw body: equal, contain or match a regex",
+        ),
+        (
+            "(the )response (body )size should {}",
+            "Check the body size in bytes",
+        ),
+        (
+            "(the )response (body )sha256/SHA-256 should {}",
+            "Check the SHA-256 digest of the body",
+        ),
+    ] {
+        registry.register(Outcome, expression, description, |context, step| {
+            context
+                .body_assertions
+                .push(BodyAssertion::parse(step.text).ok_or_else(|| unsupported(step))?);
+            Ok(())
+        })?;
+    }
+
+    for expression in [
+        "(the )response time should be under/below/within {duration}",
+        "(the )response time should be less than {duration}",
+        "(the )response time should be at most {duration}",
+    ] {
+        registry.register(
+            Outcome,
+            expression,
+            "Limit the response time, overriding the API's SLA",
+            |context, step| {
+                context.max_response_time_ms = Some(step.int(0)?);
+                Ok(())
+            },
+        )?;
+    }
+
+    for expression in [
+        "(the )(response )status (code )should be {int}",
+        "(the )(response )status (code )is {int}",
+        "I should receive/get a/an {int}( response)",
+        "(I )expect {int}( error)",
+        "(I )expect {int}( response)",
+    ] {
+        registry.register(
+            Outcome,
+            expression,
+            "Expect a response status, overriding the example's",
+            |context, step| {
+                let status = step.int(0)?;
+                if !(100..=599).contains(&status) {
+                    return Err(format!("{} is not an HTTP status code", status));
+                }
+                context.expected_status = Some(status as i16);
+                Ok(())
+            },
+        )?;
+    }
+
+    for expression in [
+        "(the )response (body )contains {string}",
+        "it contains {string}",
+        "(the )response should contain/have {string}",
+    ] {
+        registry.register(
+            Outcome,
+            expression,
+            "Check that the serialized body contains text",
+            |context, step| {
+                context
+                    .expected_body_contains
+                    .push(step.string(0).to_string());
+                Ok(())
+            },
+        )?;
+    }
+
+    Ok(())
+}
+
+fn set_header(context: &mut StepContext, step: &StepMatch<'_>) -> Result<(), String> {
+    let name = non_empty(step.string(0), "Header name")?;
+    context
+        .request_headers
+        .insert(name, unquote(step.string(1)).to_string());
+    Ok(())
+}
+
+/// Inline JSON after `with body` becomes the request body; `with body` alone
+/// sends the example row
+fn body_from_text(context: &mut StepContext, step: &StepMatch<'_>) -> Result<(), String> {
+    let Some(index) = step.text.find("with body") else {
+        return Ok(());
+    };
+    if context.request_body.is_some() {
+        return Ok(());
+    }
+
+    let rest = &step.text[index + "with body".len()..];
+    context.request_body = Some(match rest.find(['{', '[']) {
+        Some(start) => serde_json::from_str(&rest[start..])
+            .map_err(|e| format!("The request body is not valid JSON: {}", e))?,
+        None => step.example.clone(),
+    });
+    Ok(())
+}
+
+/// Send a header-first data table as an array of objects with typed values
+fn table_items(context: &mut StepContext, _: &StepMatch<'_>) -> Result<(), String> {
+    let items = match context.data_table_cells.as_deref() {
+        Some([header, rows @ ..]) => rows
+            .iter()
+            .enumerate()
+            .map(|(index, row)| {
+                if row.len() != header.len() {
+                    // Numbered as in the table, the header being row 1
+                    return Err(format!(
+                        "Row {} has {} cells, the header has {}",
+                        index + 2,
+                        row.len(),
+                        header.len()
+                    ));
+                }
+                Ok(header
+                    .iter()
+                    .zip(row)
+                    .map(|(key, cell)| (key.clone(), GherkinService::parse_cell_value(cell)))
+                    .collect())
+            })
+            .collect::<Result<_, _>>()?,
+        // Steps saved before table cells were kept only have the row objects
+        _ => context
+            .setup_data
+            .clone()
+            .ok_or("This step needs a data table with a header row")?,
+    };
+    context.request_body = Some(serde_json::Value::Array(items));
+    context.setup_data = None;
+    Ok(())
+}
+
+/// Send a vertical data table, one name and value per row, as one object
+fn table_object(context: &mut StepContext, _: &StepMatch<'_>) -> Result<(), String> {
+    let rows = context
+        .data_table_cells
+        .as_deref()
+        .ok_or("This step needs a data table of names and values")?;
+    let object = rows
+        .iter()
+        .map(|row| match row.as_slice() {
+            [key, value] => Ok((key.clone(), GherkinService::parse_cell_value(value))),
+            _ => Err("This step expects a two-column table of names and values".to_string()),
+        })
+        .collect::<Result<serde_json::Map<_, _>, _>>()?;
+    context.request_body = Some(serde_json::Value::Object(object));
+    context.setup_data = None;
+    Ok(())
+}
+
+fn redirect_hop(
+    context: &mut StepContext,
+    step: &StepMatch<'_>,
+    status: bool,
+    location: bool,
+) -> Result<(), String> {
+    let number = usize::try_from(step.int(0)?)
+        .ok()
+        .filter(|n| *n > 0)
+        .ok_or_else(|| "Redirects are numbered from 1".to_string())?;
+    let status = if status {
+        Some(i16::try_from(step.int(1)?).map_err(|e| e.to_string())?)
+    } else {
+        None
+    };
+    let location = location.then(|| step.string(step.args.len() - 1).to_string());
+
+    context.redirect_assertions.push(RedirectAssertion::Hop {
+        number,
+        status,
+        location,
+    });
+    Ok(())
+}
+
+fn cookie_jar(
+    context: &mut StepContext,
+    step: &StepMatch<'_>,
+    present: bool,
+    value: Option<&str>,
+) -> Result<(), String> {
+    context.cookie_jar_assertions.push(CookieJarAssertion {
+        name: non_empty(step.string(0), "Cookie name")?,
+        present,
+        value: value.map(str::to_string),
+    });
+    Ok(())
+}
+
+fn unsupported(step: &StepMatch<'_>) -> String {
+    format!("Unsupported assertion: {}", step.text)
+}
+
+fn non_empty(value: &str, what: &str) -> Result<String, String> {
+    if value.is_empty() {
+        return Err(format!("{} cannot be empty", what));
+    }
+    Ok(value.to_string())
+}
+
+fn unquote(value: &str) -> &str {
+    value.trim().trim_matches(|c| c == '\'' || c == '"')
+}
+
+/// Trim a step and drop one trailing `:`, which introduces a doc string or table
+fn normalize(text: &str) -> &str {
+    let text = text.trim();
+    text.strip_suffix(':').unwrap_or(text).trim_end()
+}
+
+/// Collect the characters up to `end`, consuming it; `None` when it is missing
+fn take_until(chars: &mut std::str::Chars<'_>, end: char) -> Option<String> {
+    let mut taken = String::new();
+    for c in chars.by_ref() {
+        if c == end {
+            return Some(taken);
+        }
+        taken.push(c);
+    }
+    None
+}
+
+/// Regex for literal expression text, where `a/b` inside a word matches
+/// either alternative
+fn literal_regex(literal: &str) -> String {
+    literal
+        .split(' ')
+        .map(|word| {
+            if word.contains('/') {
+                let alternatives: Vec<String> = word.split('/').map(regex::escape).collect();
+                format!("(?:{})", alternatives.join("|"))
+            } else {
+                regex::escape(word)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn int_arg(text: &str) -> StepArg {
+    text.parse()
+        .map(StepArg::Int)
+        .unwrap_or_else(|_| StepArg::Text(text.to_string()))
+}
+
+fn float_arg(text: &str) -> StepArg {
+    text.parse()
+        .map(StepArg::Float)
+        .unwrap_or_else(|_| StepArg::Text(text.to_string()))
+}
+
+fn text_arg(text: &str) -> StepArg {
+    StepArg::Text(text.to_string())
+}
+
+fn unquoted_arg(text: &str) -> StepArg {
+    let unquoted = text
+        .strip_prefix('"')
+        .and_then(|t| t.strip_suffix('"'))
+        .or_else(|| text.strip_prefix('\'').and_then(|t| t.strip_suffix('\'')))
+        .unwrap_or(text);
+    StepArg::Text(unquoted.to_string())
+}
+
+/// Milliseconds, from `300 ms`, `250ms` or `1.5 seconds`
+fn duration_arg(text: &str) -> StepArg {
+    let split = text
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let Ok(number) = number.parse::<f64>() else {
+        return StepArg::Text(text.to_string());
+    };
+
+    let millis = if unit.trim().to_lowercase().starts_with("ms")
+        || unit.trim().to_lowercase().starts_with("milli")
+    {
+        number
+    } else {
+        number * 1000.0
+    };
+    StepArg::Int(millis.round() as i64)
+}
+
+fn method_arg(text: &str) -> StepArg {
+    StepArg::Text(text.to_uppercase())
+}
+
+fn encoding_arg(text: &str) -> StepArg {
+    let lower = text.to_lowercase();
+    let name = lower
+        .strip_prefix("a ")
+        .or_else(|| lower.strip_prefix("an "))
+        .unwrap_or(&lower);
+    StepArg::Text(name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::RedirectPolicy;
+
+    /// Apply a step as written, as the runner does without placeholders
+    fn apply(context: &mut StepContext, kind: StepKind, text: &str) -> Result<(), String> {
+        let registry = StepRegistry::builtin();
+        let example = serde_json::json!({});
+        let (definition, text, args) = registry
+            .find(kind, text, text)
+            .ok_or_else(|| format!("Undefined step: {}", text))?;
+        definition.handle(
+            context,
+            &StepMatch {
+                text,
+                args,
+                example: &example,
+            },
+        )
+    }
+
+    #[test]
+    fn test_cucumber_expressions() {
+        let mut registry = StepRegistry::new();
+        assert!(registry
+            .register(StepKind::Outcome, "(the {word} )cart", "", |_, _| Ok(()))
+            .is_err());
+        registry
+            .register(
+                StepKind::Outcome,
+                "(the )cart has {int} item(s) costing/totalling {float}",
+                "",
+                |_, _| Ok(()),
+            )
+            .unwrap();
+
+        let (_, _, args) = registry
+            .find(StepKind::Outcome, "", "The cart has 1 item totalling 9.5:")
+            .unwrap();
+        assert_eq!(args, vec![StepArg::Int(1), StepArg::Float(9.5)]);
+        assert!(registry
+            .find(StepKind::Outcome, "", "cart has 3 items costing 2")
+            .is_some());
+        assert!(registry
+            .find(StepKind::Request, "", "cart has 3 items costing 2")
+            .is_none());
+        assert!(registry
+            .find(StepKind::Outcome, "", "the cart has some items costing 2")
+            .is_none());
+
+        assert!(registry
+            .register(StepKind::Outcome, "{unknown} happens", "", |_, _| Ok(()))
+            .is_err());
+        assert!(registry
+            .define_parameter_type("color", "(red|blue)", text_arg)
+            .is_err());
+        registry
+            .define_parameter_type("color", "red|blue", text_arg)
+            .unwrap();
+        assert!(registry
+            .define_parameter_type("color", "green", text_arg)
+            .is_err());
+    }
+
+    #[test]
+    fn test_builtin_steps() {
+        let mut context = StepContext::default();
+        for text in [
+            r#"the request body is fixture "user.json""#,
+            "I set header X-Trace-Id to 'abc 123'",
+            "query param q is hello world",
+            "path param id is 42",
+            "the request is sent as multipart",
+            r#"the cookie "theme" is set to 'dark'"#,
+            r#"cookie "session" is removed"#,
+            "the cookie jar is cleared",
+            "at most 50 redirects are followed",
+        ] {
+            apply(&mut context, StepKind::Request, text).unwrap();
+        }
+        assert_eq!(context.request_fixture.as_deref(), Some("user.json"));
+        assert_eq!(context.request_headers["X-Trace-Id"], "abc 123");
+        assert_eq!(
+            context.query_params,
+            vec![("q".to_string(), "hello world".to_string())]
+        );
+        assert_eq!(context.path_params["id"], "42");
+        assert_eq!(context.body_encoding, Some(BodyEncoding::Multipart));
+        assert_eq!(
+            context.cookie_actions,
+            vec![
+                CookieAction::Set {
+                    name: "theme".to_string(),
+                    value: "dark".to_string()
+                },
+                CookieAction::Remove("session".to_string()),
+                CookieAction::Clear,
+            ]
+        );
+        assert_eq!(
+            context.redirect_policy,
+            Some(RedirectPolicy {
+                follow: true,
+                max_hops: MAX_REDIRECT_HOPS
+            })
+        );
+
+        for text in [
+            r#"I save "$.data.id" as <user_id>"#,
+            "the response status should be 201",
+            "the response time should be within 1.5 seconds",
+            r#""$.meta.total" should equal 3"#,
+            r#"the response should contain "created""#,
+        ] {
+            apply(&mut context, StepKind::Outcome, text).unwrap();
+        }
+        assert_eq!(
+            context.captures,
+            vec![VariableCapture {
+                path: "$.data.id".to_string(),
+                name: "user_id".to_string(),
+            }]
+        );
+        assert_eq!(context.expected_status, Some(201));
+        assert_eq!(context.max_response_time_ms, Some(1500));
+        assert_eq!(context.json_assertions.len(), 1);
+        assert_eq!(context.expected_body_contains, vec!["created".to_string()]);
+
+        // Steps are matched by kind, and unknown ones are undefined
+        assert!(apply(&mut context, StepKind::Request, "the status should be 200").is_err());
+        assert!(apply(&mut context, StepKind::Request, "a valid user exists").is_err());
+        assert!(apply(&mut context, StepKind::Outcome, "the status should be 99").is_err());
+        assert!(apply(
+            &mut context,
+            StepKind::Outcome,
+            r#"I save "id" as <user_id>"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_extract_status_code() {
+        for (text, status) in [
+            ("status should be 200", 200),
+            ("the status code is 404", 404),
+            ("expect 500 error", 500),
+        ] {
+            let mut context = StepContext::default();
+            apply(&mut context, StepKind::Outcome, text).unwrap();
+            assert_eq!(context.expected_status, Some(status), "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_parse_capture() {
+        for (text, path, name) in [
+            (r#"I save "$.data.id" as <user_id>"#, "$.data.id", "user_id"),
+            (r#"I save "$.token" as token"#, "$.token", "token"),
+        ] {
+            let mut context = StepContext::default();
+            apply(&mut context, StepKind::Outcome, text).unwrap();
+            assert_eq!(
+                context.captures,
+                vec![VariableCapture {
+                    path: path.to_string(),
+                    name: name.to_string(),
+                }]
+            );
+        }
+
+        let mut context = StepContext::default();
+        apply(
+            &mut context,
+            StepKind::Outcome,
+            r#"the response contains "id""#,
+        )
+        .unwrap();
+        assert!(context.captures.is_empty());
+        assert!(apply(
+            &mut context,
+            StepKind::Outcome,
+            r#"I save "id" as <user_id>"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_parse_cookie_action() {
+        for (text, action) in [
+            (
+                r#"cookie "session" is "abc""#,
+                CookieAction::Set {
+                    name: "session".to_string(),
+                    value: "abc".to_string(),
+                },
+            ),
+            (
+                r#"the cookie "theme" is set to 'dark'"#,
+                CookieAction::Set {
+                    name: "theme".to_string(),
+                    value: "dark".to_string(),
+                },
+            ),
+            (
+                r#"cookie "session" is removed"#,
+                CookieAction::Remove("session".to_string()),
+            ),
+            ("the cookie jar is cleared", CookieAction::Clear),
+        ] {
+            let mut context = StepContext::default();
+            apply(&mut context, StepKind::Request, text).unwrap();
+            assert_eq!(context.cookie_actions, vec![action], "{}", text);
+        }
+
+        let mut context = StepContext::default();
+        apply(
+            &mut context,
+            StepKind::Request,
+            r#"header "Cookie" is "a=b""#,
+        )
+        .unwrap();
+        assert!(apply(
+            &mut context,
+            StepKind::Request,
+            r#"cookie "session" is valid"#
+        )
+        .is_err());
+        assert!(context.cookie_actions.is_empty());
+    }
+
+    #[test]
+    fn test_parse_response_time_limit() {
+        for (text, limit) in [
+            ("the response time should be under 300 ms", 300),
+            ("the response time should be less than 250ms", 250),
+            ("response time should be within 1.5 seconds", 1500),
+            ("the response time should be at most 2 s", 2000),
+        ] {
+            let mut context = StepContext::default();
+            apply(&mut context, StepKind::Outcome, text).unwrap();
+            assert_eq!(context.max_response_time_ms, Some(limit), "{}", text);
+        }
+
+        let mut context = StepContext::default();
+        apply(
+            &mut context,
+            StepKind::Outcome,
+            "the response status should be 200",
+        )
+        .unwrap();
+        assert_eq!(context.max_response_time_ms, None);
+    }
+
+    #[test]
+    fn test_parse_body_encoding() {
+        for (text, encoding) in [
+            ("the request is sent as multipart", BodyEncoding::Multipart),
+            (
+                "I submit the form as application/x-www-form-urlencoded:",
+                BodyEncoding::Form,
+            ),
+            ("the body is encoded as a form", BodyEncoding::Form),
+            ("the request is sent as JSON", BodyEncoding::Json),
+        ] {
+            let mut context = StepContext::default();
+            apply(&mut context, StepKind::Request, text).unwrap();
+            assert_eq!(context.body_encoding, Some(encoding), "{}", text);
+        }
+
+        let mut context = StepContext::default();
+        apply(
+            &mut context,
+            StepKind::Outcome,
+            r#"I save "$.id" as <user_id>"#,
+        )
+        .unwrap();
+        assert_eq!(context.body_encoding, None);
+        assert!(apply(&mut context, StepKind::Request, "I think of it as json").is_err());
+    }
+
+    #[test]
+    fn test_request_steps_are_anchored() {
+        let mut context = StepContext::default();
+        for text in [
+            "I send a POST request",
+            "I send a POST request to /login",
+            "I send a GET request to <url>",
+            r#"I POST /api/users with body {"name": "Ada"}"#,
+            "I GET /api/users/me",
+        ] {
+            apply(&mut context, StepKind::Request, text).unwrap();
+        }
+        assert_eq!(
+            context.request_body,
+            Some(serde_json::json!({"name": "Ada"}))
+        );
+
+        for text in [
+            "I post a letter to grandma",
+            "I get a coffee",
+            "I delete nothing",
+            "I send a GET request to the moon",
+        ] {
+            let error = apply(&mut context, StepKind::Request, text).unwrap_err();
+            assert!(error.starts_with("Undefined step"), "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_response_time_and_encoding_parameters() {
+        assert_eq!(duration_arg("300 ms"), StepArg::Int(300));
+        assert_eq!(duration_arg("250ms"), StepArg::Int(250));
+        assert_eq!(duration_arg("2 s"), StepArg::Int(2000));
+
+        for (text, encoding) in [
+            ("the request is sent as JSON", BodyEncoding::Json),
+            (
+                "I submit the form as application/x-www-form-urlencoded:",
+                BodyEncoding::Form,
+            ),
+            ("the body is encoded as a form", BodyEncoding::Form),
+        ] {
+            let mut context = StepContext::default();
+            apply(&mut context, StepKind::Request, text).unwrap();
+            assert_eq!(context.body_encoding, Some(encoding), "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_redirect_and_cookie_jar_steps() {
+        let mut context = StepContext::default();
+        for text in [
+            r#"the final URL should be "/home""#,
+            "the request should be redirected 2 times",
+            "the response should be redirected once",
+            r#"redirect 1 should be 302 to "/sso""#,
+            "redirect 2 should be 301",
+            r#"redirect 2 should go to "/home""#,
+            r#"the cookie jar should contain "theme" with value "dark""#,
+            r#"the cookie jar should not contain "session""#,
+        ] {
+            apply(&mut context, StepKind::Outcome, text).unwrap();
+        }
+
+        assert_eq!(
+            context.redirect_assertions,
+            vec![
+                RedirectAssertion::FinalUrl {
+                    expected: "/home".to_string(),
+                    contains: false
+                },
+                RedirectAssertion::Count(2),
+                RedirectAssertion::Count(1),
+                RedirectAssertion::Hop {
+                    number: 1,
+                    status: Some(302),
+                    location: Some("/sso".to_string())
+                },
+                RedirectAssertion::Hop {
+                    number: 2,
+                    status: Some(301),
+                    location: None
+                },
+                RedirectAssertion::Hop {
+                    number: 2,
+                    status: None,
+                    location: Some("/home".to_string())
+                },
+            ]
+        );
+        assert_eq!(
+            context.cookie_jar_assertions,
+            vec![
+                CookieJarAssertion {
+                    name: "theme".to_string(),
+                    present: true,
+                    value: Some("dark".to_string())
+                },
+                CookieJarAssertion {
+                    name: "session".to_string(),
+                    present: false,
+                    value: None
+                },
+            ]
+        );
+        assert!(apply(&mut context, StepKind::Outcome, "redirect 0 should be 302").is_err());
+    }
+}
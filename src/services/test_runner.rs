@@ -1,11 +1,57 @@
-use reqwest::{Client, Method, Response};
+use jsonpath_rust::JsonPath;
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
+use reqwest::header::HeaderMap;
+use reqwest::multipart::{Form, Part};
+use reqwest::{Client, Method, Request, Response, Url};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
+use std::sync::{Arc, Mutex, PoisonError};
 use std::time::{Duration, Instant};
 use uuid::Uuid;
 
 use crate::error::{AppError, AppResult};
-use crate::models::{Api, Environment, GherkinStep, Scenario, TestExample};
+use crate::models::{
+    Api, BodyEncoding, BodyMatch, Environment, GherkinStep, RedirectPolicy, Scenario, ScenarioKind,
+    TestExample, TransportSettings,
+};
+use crate::repositories::{
+    ApiRepository, FixtureRepository, RequestAuthRepository, ScenarioRepository, VariableRepository,
+};
+use crate::services::assertion::{
+    BodyAssertion, CookieAssertion, CookieJarAssertion, HeaderAssertion, JsonAssertion,
+    RedirectAssertion,
+};
+use crate::services::body::{fixture_references, FixtureFile, ResponseBody};
+use crate::services::body_match;
+use crate::services::cookies::{CookieAction, CookieJar, CookieJarScope};
+use crate::services::generators::{random_seed, Generators};
+use crate::services::hooks::{Hook, HookScenario, ScenarioHooks};
+use crate::services::redirect::{self, RedirectHop, Resend};
+use crate::services::request_auth::{apply_auth, set_header, RunAuth, TokenCache};
+use crate::services::schema::ResponseSchema;
+use crate::services::steps::{StepKind, StepMatch, StepRegistry};
+use crate::services::timing::{RequestTiming, TimingRecorder};
+use crate::services::transcript::{headers_to_json, redact_url, SecretMask, SentRequest};
+use crate::services::transport;
+use crate::services::variables::VariableScopes;
+use crate::services::workflow::{self, WorkflowStepResult};
+use crate::state::AppState;
+
+/// Characters escaped in path parameter values, so a value always stays a
+/// single path segment
+const PATH_SEGMENT: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'/')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'`')
+    .add(b'{')
+    .add(b'}');
 
 /// Configuration for test execution
 #[derive(Debug, Clone)]
@@ -13,6 +59,32 @@ pub struct TestConfig {
     pub timeout: Duration,
     pub auth_token: Option<String>,
     pub custom_headers: HashMap<String, String>,
+    /// Project fixtures, available to `@name` references in multipart bodies
+    /// and to `fixture "name"` steps
+    pub fixtures: HashMap<String, FixtureFile>,
+    /// Project, collection and environment variables; with the scenario's own
+    /// variables they fill `<name>` and `{name}` not bound by an example
+    /// column or run variable. Secret environment values are masked in results.
+    pub variables: VariableScopes,
+    /// Auth strategies of the environment and of APIs overriding it, and the
+    /// environment's auth bootstrap. A run-level `auth_token` replaces them.
+    /// Their secrets are masked in results.
+    pub auth: RunAuth,
+    /// Seed for `{{generator}}` values; running again with the same seed
+    /// reproduces them
+    pub seed: u64,
+    /// How long cookies set by responses are kept and sent back
+    pub cookie_jar: CookieJarScope,
+    /// The environment's TLS, proxy, DNS and HTTP version settings, decrypted
+    pub transport: TransportSettings,
+    /// Step definitions steps are matched against, the built-in ones by default
+    pub steps: Arc<StepRegistry>,
+    /// `@before` and `@after` scenarios of the collection under test, run
+    /// around each example of its other scenarios
+    pub hooks: ScenarioHooks,
+    /// APIs of the project under test, which workflow scenarios call by name
+    /// or by method and endpoint
+    pub apis: Vec<Api>,
 }
 
 impl Default for TestConfig {
@@ -21,10 +93,70 @@ impl Default for TestConfig {
             timeout: Duration::from_secs(30),
             auth_token: None,
             custom_headers: HashMap::new(),
+            fixtures: HashMap::new(),
+            variables: VariableScopes::default(),
+            auth: RunAuth::default(),
+            seed: random_seed(),
+            cookie_jar: CookieJarScope::default(),
+            transport: TransportSettings::default(),
+            steps: StepRegistry::builtin(),
+            hooks: ScenarioHooks::default(),
+            apis: Vec::new(),
         }
     }
 }
 
+impl TestConfig {
+    /// Load what a run of `scenarios` against `environment` needs: the
+    /// project's variables and APIs, the environment's auth and transport, the
+    /// hook scenarios of `collection_id` (`None` to run without hooks) and the
+    /// fixtures all of them reference. The options a run is started with keep
+    /// their defaults.
+    pub async fn load<'a>(
+        state: &AppState,
+        environment: &Environment,
+        collection_id: Option<Uuid>,
+        scenarios: impl IntoIterator<Item = &'a Scenario>,
+    ) -> AppResult<Self> {
+        let cipher = state.secret_cipher();
+        let hooks = match collection_id {
+            Some(collection_id) => ScenarioHooks::new(
+                ScenarioRepository::list_with_apis_by_collection(&state.db, collection_id)
+                    .await?
+                    .into_iter()
+                    .map(|(scenario, api)| HookScenario { scenario, api }),
+            ),
+            None => ScenarioHooks::default(),
+        };
+        let auth = RequestAuthRepository::load(&state.db, &cipher, environment).await?;
+
+        let mut references = fixture_references(scenarios);
+        references.extend(fixture_references(
+            hooks
+                .before
+                .iter()
+                .chain(&hooks.after)
+                .map(|hook| &hook.scenario)
+                .chain(auth.bootstrap.iter().map(|bootstrap| &bootstrap.scenario)),
+        ));
+
+        Ok(Self {
+            fixtures: FixtureRepository::load_named(
+                &state.mongo_db(),
+                environment.project_id,
+                &references,
+            )
+            .await?,
+            variables: VariableRepository::load_scopes(&state.db, &cipher, environment).await?,
+            auth,
+            transport: cipher.open_transport(environment.transport.as_ref())?,
+            hooks,
+            apis: ApiRepository::list_by_project(&state.db, environment.project_id).await?,
+            ..Self::default()
+        })
+    }
+}
+
 /// Result of a single test execution
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TestResult {
@@ -33,30 +165,170 @@ pub struct TestResult {
     pub example_index: i32,
     pub pass: bool,
     pub error_message: Option<String>,
+    /// The request sent, with secrets redacted; absent when it could not be built
+    pub request: Option<SentRequest>,
     pub response_status: i16,
     pub response_data: Option<serde_json::Value>,
+    /// Response headers as `{ name: [values] }`
+    pub response_headers: Option<serde_json::Value>,
+    /// Network time of the request (see `RequestTiming::total_ms`), or the
+    /// elapsed time until it failed
     pub request_duration_ms: i64,
+    /// Per-phase breakdown, absent when the request failed
+    pub timing: Option<RequestTiming>,
+    /// Redirects followed before the final response
+    #[serde(default)]
+    pub redirects: Vec<RedirectHop>,
+    /// Result of each call of a workflow scenario, up to the first that
+    /// failed; the fields above are those of the last one
+    #[serde(default)]
+    pub workflow_steps: Vec<WorkflowStepResult>,
     pub request_time: time::OffsetDateTime,
 }
 
+impl TestResult {
+    /// Redirects as stored with a response, `None` when there were none
+    pub fn stored_redirects(&self) -> Option<serde_json::Value> {
+        if self.redirects.is_empty() {
+            return None;
+        }
+        serde_json::to_value(&self.redirects).ok()
+    }
+
+    /// Workflow call results as stored with a response, `None` for results of
+    /// single-API scenarios
+    pub fn stored_workflow_steps(&self) -> Option<serde_json::Value> {
+        if self.workflow_steps.is_empty() {
+            return None;
+        }
+        serde_json::to_value(&self.workflow_steps).ok()
+    }
+}
+
 /// Context built from Gherkin steps
 #[derive(Debug, Clone, Default)]
 pub struct StepContext {
     pub request_body: Option<serde_json::Value>,
     pub request_headers: HashMap<String, String>,
-    pub query_params: HashMap<String, String>,
+    /// Query parameters in step order; a name may repeat, as in `?tag=a&tag=b`
+    pub query_params: Vec<(String, String)>,
+    /// Body encoding chosen by a step, overriding the API's content type
+    pub body_encoding: Option<BodyEncoding>,
+    /// Fixture named by a request step such as `the request body is fixture "user.json"`
+    pub request_fixture: Option<String>,
+    /// Non-JSON fixture sent as-is instead of an encoded body
+    pub raw_request_body: Option<FixtureFile>,
+    /// Values for `{name}` segments of the endpoint, from `path param id is 42`
     pub path_params: HashMap<String, String>,
     pub expected_status: Option<i16>,
+    /// Fail on a status outside 2xx when no status is expected, as the calls
+    /// of a workflow before its last one do
+    pub expect_success: bool,
     pub expected_body: Option<serde_json::Value>,
+    /// How `expected_body` is compared: the example's, else the scenario's
+    pub body_match: BodyMatch,
     pub expected_body_contains: Vec<String>,
+    /// Fixture named by an outcome step such as `the response body should equal fixture "user.json"`
+    pub expected_fixture: Option<String>,
     /// Data table from step (for setup data)
     pub setup_data: Option<Vec<serde_json::Value>>,
+    /// Cells of the data table of the step being applied, header row first,
+    /// with placeholders substituted
+    pub data_table_cells: Option<Vec<Vec<String>>>,
+    /// Response values to save as run variables once the request completes
+    pub captures: Vec<VariableCapture>,
+    /// Typed JSONPath assertions checked against the parsed response body
+    pub json_assertions: Vec<JsonAssertion>,
+    /// Assertions on response headers
+    pub header_assertions: Vec<HeaderAssertion>,
+    /// Assertions on cookies set by the response
+    pub cookie_assertions: Vec<CookieAssertion>,
+    /// Cookies set or removed by steps before the request is sent
+    pub cookie_actions: Vec<CookieAction>,
+    /// Assertions on the cookie jar after the response's cookies are stored
+    pub cookie_jar_assertions: Vec<CookieJarAssertion>,
+    /// Jar whose cookies are sent with the request and updated from the response
+    pub cookies: Arc<CookieJar>,
+    /// Redirect policy chosen by a step, overriding the API's
+    pub redirect_policy: Option<RedirectPolicy>,
+    /// Assertions on the redirects followed and the final URL
+    pub redirect_assertions: Vec<RedirectAssertion>,
+    /// Text, regex, XPath, digest and size assertions on the raw body
+    pub body_assertions: Vec<BodyAssertion>,
+    /// Response time limit in milliseconds (step, else the API's SLA)
+    pub max_response_time_ms: Option<i64>,
+    /// Schema the response body must conform to (scenario schema, else API schema)
+    pub response_schema: Option<Arc<ResponseSchema>>,
+    /// Effective project to scenario variables, used after example columns and
+    /// run variables
+    pub variables: Arc<HashMap<String, String>>,
+    /// Expands `{{generator}}` tokens after placeholder substitution
+    pub generators: Arc<Generators>,
+}
+
+/// A response value to save into the run variables, e.g. `I save "$.data.id" as <user_id>`
+#[derive(Debug, Clone, PartialEq)]
+pub struct VariableCapture {
+    /// JSONPath evaluated against the response body
+    pub path: String,
+    /// Variable name, referenced later as `<name>`
+    pub name: String,
+}
+
+/// Response received from the target API
+#[derive(Debug)]
+struct HttpResponse {
+    status: i16,
+    headers: HeaderMap,
+    /// URL of the final response, after any redirects
+    url: Url,
+    redirects: Vec<RedirectHop>,
+    /// Body parsed as JSON, `Null` when it is not JSON
+    body: serde_json::Value,
+    raw: ResponseBody,
+    timing: RequestTiming,
+}
+
+/// Per-scenario data shared by all of its examples
+#[derive(Clone)]
+struct ScenarioPlan {
+    /// Background steps followed by the scenario's own
+    steps: Arc<[GherkinStep]>,
+    response_schema: Option<Arc<ResponseSchema>>,
+    variables: Arc<HashMap<String, String>>,
+    /// Jar shared by the examples, `None` when each starts without cookies
+    cookies: Option<Arc<CookieJar>>,
+    /// Calls of a workflow scenario, in order; empty for single-API scenarios
+    workflow: Arc<[WorkflowCallPlan]>,
+    /// Expect any 2xx status instead of the example's status and body
+    expect_success: bool,
+}
+
+/// A workflow call with the API it sends its request to
+#[derive(Clone)]
+struct WorkflowCallPlan {
+    api: Api,
+    plan: ScenarioPlan,
 }
 
 /// Test Runner Service
 pub struct TestRunner {
     client: Client,
     config: TestConfig,
+    /// Connection phases of the request in flight
+    timing: TimingRecorder,
+    /// Masks secret environment values and auth secrets in recorded results.
+    /// Tokens obtained during the run are added as they arrive.
+    mask: Mutex<SecretMask>,
+    /// OAuth2 tokens obtained during this run
+    tokens: TokenCache,
+    /// Header name and value obtained by the auth bootstrap scenario
+    bootstrap_header: Option<(String, String)>,
+    /// Cookies kept across the run when `cookie_jar` is `run`
+    cookies: Arc<CookieJar>,
+    /// Variables captured during this run. A runner is built per run, so values
+    /// saved by one scenario are visible to every scenario executed after it.
+    variables: Mutex<HashMap<String, serde_json::Value>>,
 }
 
 impl TestRunner {
@@ -67,12 +339,103 @@ impl TestRunner {
 
     /// Create a new TestRunner with custom config
     pub fn with_config(config: TestConfig) -> AppResult<Self> {
-        let client = Client::builder()
+        let timing = TimingRecorder::default();
+        let builder = Client::builder()
             .timeout(config.timeout)
+            .dns_resolver(timing.clone())
+            .connector_layer(timing.layer())
+            // Redirects are followed by `execute_request`, which records them
+            .redirect(reqwest::redirect::Policy::none());
+        let client = transport::configure(builder, &config.transport, &timing)?
             .build()
             .map_err(|e| AppError::Internal(format!("Failed to create HTTP client: {e}")))?;
 
-        Ok(Self { client, config })
+        Ok(Self {
+            client,
+            mask: Mutex::new(SecretMask::new(
+                &[
+                    config.variables.environment.secret_values.clone(),
+                    config.auth.secret_values(),
+                ]
+                .concat(),
+            )),
+            tokens: TokenCache::default(),
+            bootstrap_header: None,
+            cookies: Arc::default(),
+            config,
+            timing,
+            variables: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Run the environment's auth bootstrap scenario, if it has one, and send
+    /// the token from its response with every later request that would use
+    /// the environment's auth. Skipped when the run has an `auth_token`.
+    pub async fn bootstrap(&mut self, environment: &Environment) -> AppResult<()> {
+        let Some(bootstrap) = self.config.auth.bootstrap.clone() else {
+            return Ok(());
+        };
+        if self.config.auth_token.is_some() {
+            return Ok(());
+        }
+
+        let result = self
+            .run_single_example(&bootstrap.scenario, &bootstrap.api, environment, 0)
+            .await?;
+        if !result.pass {
+            return Err(AppError::Validation(format!(
+                "Auth bootstrap scenario failed: {}",
+                result.error_message.unwrap_or_default()
+            )));
+        }
+
+        let body = result.response_data.unwrap_or_default();
+        let path = &bootstrap.config.token_path;
+        let matches = body
+            .query(path)
+            .map_err(|e| AppError::Validation(format!("Invalid JSONPath {}: {}", path, e)))?;
+        let token = match matches.as_slice() {
+            [serde_json::Value::String(token)] => token.clone(),
+            [serde_json::Value::Number(token)] => token.to_string(),
+            _ => {
+                return Err(AppError::Validation(format!(
+                    "Auth bootstrap scenario response has no token at {}",
+                    path
+                )))
+            }
+        };
+
+        self.mask
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+            .add(&token);
+        self.bootstrap_header = Some((
+            bootstrap.config.header,
+            format!("{}{}", bootstrap.config.prefix, token),
+        ));
+        Ok(())
+    }
+
+    /// Seed of this run's generated values
+    pub fn seed(&self) -> u64 {
+        self.config.seed
+    }
+
+    /// Get a run variable by name
+    pub fn variable(&self, name: &str) -> Option<serde_json::Value> {
+        self.variables
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(name)
+            .cloned()
+    }
+
+    /// Set a run variable, overwriting any previous value
+    pub fn set_variable(&self, name: &str, value: serde_json::Value) {
+        self.variables
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(name.to_string(), value);
     }
 
     /// Run all examples for a scenario
@@ -82,70 +445,535 @@ impl TestRunner {
         api: &Api,
         environment: &Environment,
     ) -> AppResult<Vec<TestResult>> {
+        let (plan, examples) = self.plan_scenario(scenario, api)?;
+        let hooked = Hook::of(scenario).is_none() && !self.config.hooks.is_empty();
+
+        let mut results = Vec::new();
+
+        for (index, example) in examples.iter().enumerate() {
+            let result = if hooked {
+                self.run_hooked_example(scenario, api, environment, &plan, example, index as i32)
+                    .await
+            } else {
+                self.run_example(scenario, api, environment, &plan, example, index as i32)
+                    .await
+            };
+            results.push(result);
+        }
+
+        Ok(results)
+    }
+
+    /// Run an example between the before and after hooks of its API's
+    /// collection. The hooks share the example's cookie jar. After hooks run
+    /// even when a before hook or the example failed, and their failures fail
+    /// the example.
+    async fn run_hooked_example(
+        &self,
+        scenario: &Scenario,
+        api: &Api,
+        environment: &Environment,
+        plan: &ScenarioPlan,
+        example: &TestExample,
+        example_index: i32,
+    ) -> TestResult {
+        let plan = ScenarioPlan {
+            cookies: Some(plan.cookies.clone().unwrap_or_default()),
+            ..plan.clone()
+        };
+        let hooks = &self.config.hooks;
+        let in_collection = |hook: &&HookScenario| hook.api.collection_id == api.collection_id;
+
+        let mut before_error = None;
+        for hook in hooks.before.iter().filter(in_collection) {
+            if let Err(e) = self.run_hook(hook, environment, &plan).await {
+                before_error = Some(format!(
+                    "Before hook \"{}\" failed: {}",
+                    hook.scenario.title, e
+                ));
+                break;
+            }
+        }
+
+        let mut result = match before_error {
+            Some(error) => TestResult {
+                scenario_id: scenario.id,
+                api_id: api.id,
+                example_index,
+                pass: false,
+                error_message: Some(error),
+                request: None,
+                response_status: 0,
+                response_data: None,
+                response_headers: None,
+                request_duration_ms: 0,
+                timing: None,
+                redirects: Vec::new(),
+                workflow_steps: Vec::new(),
+                request_time: time::OffsetDateTime::now_utc(),
+            },
+            None => {
+                self.run_example(scenario, api, environment, &plan, example, example_index)
+                    .await
+            }
+        };
+
+        for hook in hooks.after.iter().filter(in_collection) {
+            if let Err(e) = self.run_hook(hook, environment, &plan).await {
+                let error = format!("After hook \"{}\" failed: {}", hook.scenario.title, e);
+                result.pass = false;
+                result.error_message = Some(match result.error_message.take() {
+                    Some(message) => format!("{}; {}", message, error),
+                    None => error,
+                });
+            }
+        }
+
+        result
+    }
+
+    /// Run every example of a hook scenario with the cookie jar of the example
+    /// it wraps; a hook without examples runs once, expecting any 2xx status
+    async fn run_hook(
+        &self,
+        hook: &HookScenario,
+        environment: &Environment,
+        plan: &ScenarioPlan,
+    ) -> Result<(), String> {
+        let (hook_plan, mut examples) = self
+            .plan_scenario(&hook.scenario, &hook.api)
+            .map_err(|e| e.to_string())?;
+        let hook_plan = ScenarioPlan {
+            cookies: plan.cookies.clone(),
+            expect_success: hook_plan.expect_success || examples.is_empty(),
+            ..hook_plan
+        };
+        if examples.is_empty() {
+            examples.push(TestExample {
+                example: serde_json::json!({}),
+                expected_response_body: serde_json::Value::Null,
+                expected_status_code: 200,
+                body_match: None,
+            });
+        }
+
+        for (index, example) in examples.iter().enumerate() {
+            let result = self
+                .run_example(
+                    &hook.scenario,
+                    &hook.api,
+                    environment,
+                    &hook_plan,
+                    example,
+                    index as i32,
+                )
+                .await;
+            if !result.pass {
+                return Err(result.error_message.unwrap_or_default());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Run one example of a scenario, e.g. to replay a stored result
+    pub async fn run_single_example(
+        &self,
+        scenario: &Scenario,
+        api: &Api,
+        environment: &Environment,
+        example_index: i32,
+    ) -> AppResult<TestResult> {
+        let (plan, examples) = self.plan_scenario(scenario, api)?;
+        let example = Self::example(&examples, example_index)?;
+
+        Ok(self
+            .run_example(scenario, api, environment, &plan, example, example_index)
+            .await)
+    }
+
+    /// Send a stored request of a single-API scenario again. The request is
+    /// rebuilt from the scenario, which fills in the secrets masked in the
+    /// stored one, and is only sent when it matches the stored request apart
+    /// from the origin, headers the auth strategy sets and `custom_headers`.
+    pub async fn replay_example(
+        &self,
+        scenario: &Scenario,
+        api: &Api,
+        environment: &Environment,
+        example_index: i32,
+        stored: &SentRequest,
+    ) -> AppResult<TestResult> {
+        let (plan, examples) = self.plan_scenario(scenario, api)?;
+        if !plan.workflow.is_empty() {
+            return Err(AppError::Validation(
+                "Workflow results cannot be replayed from their stored request".to_string(),
+            ));
+        }
+        let example = Self::example(&examples, example_index)?;
+
+        let started = (time::OffsetDateTime::now_utc(), Instant::now());
+        let (context, request) =
+            self.prepare_request(scenario, api, environment, &plan, example, example_index);
+        let request = request?;
+        let unsigned = self.masked(SentRequest::capture(&request));
+        let request = self.authorize(api, request).await?;
+        let rebuilt = self.masked(SentRequest::capture(&request));
+
+        // Auth may sign each request anew, e.g. with an HMAC timestamp
+        let differences = rebuilt.differences(stored, |name| {
+            unsigned.headers.get(name) != rebuilt.headers.get(name)
+                || self
+                    .config
+                    .custom_headers
+                    .keys()
+                    .any(|header| header.eq_ignore_ascii_case(name))
+        });
+        if !differences.is_empty() {
+            return Err(AppError::Validation(format!(
+                "The scenario now builds a different request than the stored one ({} differ); run the scenario instead",
+                differences.join(", ")
+            )));
+        }
+
+        Ok(self
+            .complete_request(scenario, api, &context, Ok(request), example_index, started)
+            .await)
+    }
+
+    fn example(examples: &[TestExample], example_index: i32) -> AppResult<&TestExample> {
+        usize::try_from(example_index)
+            .ok()
+            .and_then(|index| examples.get(index))
+            .ok_or_else(|| {
+                AppError::Validation(format!(
+                    "Scenario has no example {} (it has {})",
+                    example_index,
+                    examples.len()
+                ))
+            })
+    }
+
+    /// Parse a scenario's background, steps and examples, resolve the APIs
+    /// a workflow calls, compile response schemas, resolve variables and pick
+    /// the cookie jar
+    fn plan_scenario(
+        &self,
+        scenario: &Scenario,
+        api: &Api,
+    ) -> AppResult<(ScenarioPlan, Vec<TestExample>)> {
+        let background: Vec<GherkinStep> = if scenario.background_steps.is_null() {
+            Vec::new()
+        } else {
+            serde_json::from_value(scenario.background_steps.clone()).map_err(|e| {
+                AppError::Validation(format!("Invalid background steps format: {}", e))
+            })?
+        };
         let steps: Vec<GherkinStep> = serde_json::from_value(scenario.steps.clone())
             .map_err(|e| AppError::Validation(format!("Invalid steps format: {}", e)))?;
 
         let examples: Vec<TestExample> = serde_json::from_value(scenario.examples.clone())
             .map_err(|e| AppError::Validation(format!("Invalid examples format: {}", e)))?;
 
-        let mut results = Vec::new();
+        let cookies = match self.config.cookie_jar {
+            CookieJarScope::Run => Some(self.cookies.clone()),
+            CookieJarScope::Scenario => Some(Arc::default()),
+            CookieJarScope::Example => scenario
+                .tags
+                .iter()
+                .any(|tag| tag.trim_start_matches('@').eq_ignore_ascii_case("cookies"))
+                .then(Arc::default),
+        };
 
-        for (index, example) in examples.iter().enumerate() {
+        let plan = match scenario.kind {
+            ScenarioKind::Single => ScenarioPlan {
+                cookies,
+                ..self.plan_request(
+                    scenario,
+                    api,
+                    [background, steps].concat(),
+                    scenario.response_schema.as_ref(),
+                )?
+            },
+            ScenarioKind::Workflow => {
+                let calls = workflow::split(&steps).map_err(AppError::Validation)?;
+                let last = calls.len() - 1;
+                let workflow = calls
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, call)| {
+                        let api =
+                            workflow::resolve(&call.target, &self.config.apis).map_err(|e| {
+                                AppError::Validation(format!("Call {}: {}", index + 1, e))
+                            })?;
+                        // The scenario's schema, like the example's
+                        // expectations, applies to the last call
+                        let schema = scenario.response_schema.as_ref().filter(|_| index == last);
+                        let plan = ScenarioPlan {
+                            expect_success: index < last,
+                            ..self.plan_request(
+                                scenario,
+                                api,
+                                [background.clone(), call.steps].concat(),
+                                schema,
+                            )?
+                        };
+                        Ok(WorkflowCallPlan {
+                            api: api.clone(),
+                            plan,
+                        })
+                    })
+                    .collect::<AppResult<Vec<_>>>()?;
+
+                ScenarioPlan {
+                    steps: Arc::from([]),
+                    response_schema: None,
+                    variables: Arc::default(),
+                    cookies,
+                    workflow: workflow.into(),
+                    expect_success: false,
+                }
+            }
+        };
+
+        Ok((plan, examples))
+    }
+
+    /// Plan one request of a scenario to `api`: compile the response schema,
+    /// `schema` or else the API's, and resolve the variables of the API's
+    /// collection
+    fn plan_request(
+        &self,
+        scenario: &Scenario,
+        api: &Api,
+        steps: Vec<GherkinStep>,
+        schema: Option<&serde_json::Value>,
+    ) -> AppResult<ScenarioPlan> {
+        // The schema is compiled once and shared by every example
+        let response_schema = schema
+            .or(api.response_schema.as_ref())
+            .map(ResponseSchema::compile)
+            .transpose()
+            .map_err(AppError::Validation)?
+            .map(Arc::new);
+
+        let variables = self
+            .config
+            .variables
+            .effective(api.collection_id, &scenario.variables);
+
+        Ok(ScenarioPlan {
+            steps: steps.into(),
+            response_schema,
+            variables: Arc::new(variables),
+            cookies: None,
+            workflow: Arc::from([]),
+            expect_success: false,
+        })
+    }
+
+    /// Run a single example, calling each API of a workflow in turn
+    async fn run_example(
+        &self,
+        scenario: &Scenario,
+        api: &Api,
+        environment: &Environment,
+        plan: &ScenarioPlan,
+        example: &TestExample,
+        example_index: i32,
+    ) -> TestResult {
+        if plan.workflow.is_empty() {
+            return self
+                .run_request(scenario, api, environment, plan, example, example_index)
+                .await;
+        }
+
+        // The calls share the example's cookie jar and stop at the first failure
+        let cookies = plan.cookies.clone().unwrap_or_default();
+        let mut results: Vec<TestResult> = Vec::new();
+        for call in plan.workflow.iter() {
+            let plan = ScenarioPlan {
+                cookies: Some(cookies.clone()),
+                ..call.plan.clone()
+            };
             let result = self
-                .run_example(scenario, api, environment, &steps, example, index as i32)
+                .run_request(
+                    scenario,
+                    &call.api,
+                    environment,
+                    &plan,
+                    example,
+                    example_index,
+                )
                 .await;
+            let pass = result.pass;
             results.push(result);
+            if !pass {
+                break;
+            }
         }
 
-        Ok(results)
+        let error_message = plan
+            .workflow
+            .iter()
+            .zip(&results)
+            .enumerate()
+            .find(|(_, (_, result))| !result.pass)
+            .map(|(index, (call, result))| {
+                format!(
+                    "Call {} ({} {}) failed: {}",
+                    index + 1,
+                    call.api.http_method,
+                    call.api.endpoint,
+                    result.error_message.as_deref().unwrap_or_default()
+                )
+            });
+        let request_duration_ms = results.iter().map(|r| r.request_duration_ms).sum();
+        let request_time = results[0].request_time;
+        let last = results
+            .last()
+            .cloned()
+            .expect("a workflow makes at least one call");
+
+        TestResult {
+            api_id: api.id,
+            pass: error_message.is_none(),
+            error_message,
+            request_duration_ms,
+            workflow_steps: results.into_iter().map(Into::into).collect(),
+            request_time,
+            ..last
+        }
     }
 
-    /// Run a single example
-    async fn run_example(
+    /// Send the request of one example and check its response
+    async fn run_request(
         &self,
         scenario: &Scenario,
         api: &Api,
         environment: &Environment,
-        steps: &[GherkinStep],
+        plan: &ScenarioPlan,
         example: &TestExample,
         example_index: i32,
     ) -> TestResult {
-        let request_time = time::OffsetDateTime::now_utc();
-        let start = Instant::now();
+        let started = (time::OffsetDateTime::now_utc(), Instant::now());
+        let (context, request) =
+            self.prepare_request(scenario, api, environment, plan, example, example_index);
+        let request = match request {
+            Ok(request) => self.authorize(api, request).await,
+            Err(e) => Err(e),
+        };
+        self.complete_request(scenario, api, &context, request, example_index, started)
+            .await
+    }
 
+    /// Apply the steps of one example and build its request, with the
+    /// cookies of its jar but without auth
+    fn prepare_request(
+        &self,
+        scenario: &Scenario,
+        api: &Api,
+        environment: &Environment,
+        plan: &ScenarioPlan,
+        example: &TestExample,
+        example_index: i32,
+    ) -> (StepContext, AppResult<Request>) {
         // Build context from steps
         let mut context = StepContext {
-            expected_status: Some(example.expected_status_code),
-            expected_body: Some(example.expected_response_body.clone()),
+            expected_status: (!plan.expect_success).then_some(example.expected_status_code),
+            expect_success: plan.expect_success,
+            expected_body: (!plan.expect_success).then(|| example.expected_response_body.clone()),
+            body_match: example
+                .body_match
+                .clone()
+                .or_else(|| scenario.body_match.clone())
+                .unwrap_or_default(),
+            max_response_time_ms: api.sla_ms.map(i64::from),
+            response_schema: plan.response_schema.clone(),
+            variables: plan.variables.clone(),
+            generators: Arc::new(Generators::for_example(
+                self.config.seed,
+                scenario.id,
+                example_index,
+            )),
+            cookies: plan.cookies.clone().unwrap_or_default(),
             ..Default::default()
         };
 
-        // Process steps to build context
-        for step in steps {
-            self.process_step(&mut context, step, &example.example);
-        }
+        // Process steps to build context; the request is not sent when a
+        // step is undefined or invalid
+        let step_errors: Vec<String> = plan
+            .steps
+            .iter()
+            .filter_map(|step| {
+                self.process_step(&mut context, step, &example.example)
+                    .err()
+            })
+            .collect();
+
+        let request = if step_errors.is_empty() {
+            self.resolve_fixtures(&mut context, &example.example)
+                .and_then(|()| self.build_request(api, environment, &context, &example.example))
+                .map(|mut request| {
+                    Self::send_cookies(&context, &mut request);
+                    request
+                })
+        } else {
+            Err(AppError::Validation(step_errors.join("; ")))
+        };
+        (context, request)
+    }
 
-        // Build and execute request
-        let result = self
-            .execute_request(api, environment, &context, &example.example)
-            .await;
+    /// Send a prepared request and check its response, recording what is
+    /// sent even if sending fails
+    async fn complete_request(
+        &self,
+        scenario: &Scenario,
+        api: &Api,
+        context: &StepContext,
+        request: AppResult<Request>,
+        example_index: i32,
+        (request_time, start): (time::OffsetDateTime, Instant),
+    ) -> TestResult {
+        let sent_request = request.as_ref().ok().map(SentRequest::capture);
+        let policy = context
+            .redirect_policy
+            .or(api.redirect_policy)
+            .unwrap_or_default();
+        let result = match request {
+            Ok(request) => {
+                self.execute_request(request, policy, &context.cookies)
+                    .await
+            }
+            Err(e) => Err(e),
+        };
 
         let duration = start.elapsed().as_millis() as i64;
 
-        match result {
-            Ok((status, body)) => {
+        let result = match result {
+            Ok(response) => {
                 // Validate response
-                let validation = self.validate_response(status, &body, &context);
+                let validation = self.validate_response(&response, context);
+
+                // Captures are applied even when validation fails, so the error
+                // reported is the first one encountered
+                let captured = self.apply_captures(&response.body, &context.captures);
+                let outcome = validation.and(captured);
 
                 TestResult {
                     scenario_id: scenario.id,
                     api_id: api.id,
                     example_index,
-                    pass: validation.is_ok(),
-                    error_message: validation.err(),
-                    response_status: status,
-                    response_data: Some(body),
-                    request_duration_ms: duration,
+                    pass: outcome.is_ok(),
+                    error_message: outcome.err(),
+                    request: sent_request,
+                    response_status: response.status,
+                    response_data: Some(response.raw.to_stored_value()),
+                    response_headers: Some(headers_to_json(&response.headers)),
+                    request_duration_ms: response.timing.total_ms,
+                    timing: Some(response.timing),
+                    redirects: response.redirects,
+                    workflow_steps: Vec::new(),
                     request_time,
                 }
             }
@@ -154,110 +982,245 @@ impl TestRunner {
                 api_id: api.id,
                 example_index,
                 pass: false,
-                error_message: Some(e.to_string()),
+                // Transport failures carry the client's message, which the
+                // API-facing Display of an internal error hides
+                error_message: Some(match e {
+                    AppError::Internal(message) => message,
+                    e => e.to_string(),
+                }),
+                request: sent_request,
                 response_status: 0,
                 response_data: None,
+                response_headers: None,
                 request_duration_ms: duration,
+                timing: None,
+                redirects: Vec::new(),
+                workflow_steps: Vec::new(),
                 request_time,
             },
+        };
+
+        self.mask_secrets(result)
+    }
+
+    /// A recorded request with the run's secrets masked
+    fn masked(&self, request: SentRequest) -> SentRequest {
+        self.mask
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .request(request)
+    }
+
+    /// Replace secret environment values in a result before it is recorded
+    fn mask_secrets(&self, mut result: TestResult) -> TestResult {
+        let mask = self.mask.lock().unwrap_or_else(PoisonError::into_inner);
+        if mask.is_empty() {
+            return result;
+        }
+
+        result.request = result.request.map(|r| mask.request(r));
+        result.error_message = result.error_message.map(|e| mask.text(&e));
+        result.response_data = result.response_data.map(|d| mask.json(d));
+        result.response_headers = result.response_headers.map(|h| mask.json(h));
+        for hop in &mut result.redirects {
+            hop.url = mask.text(&hop.url);
+            hop.location = mask.text(&hop.location);
         }
+        result
     }
 
-    /// Process a Gherkin step to build context
+    /// Apply a Gherkin step to the context through the first matching step
+    /// definition. Fails for undefined steps and invalid parameters.
     fn process_step(
         &self,
         context: &mut StepContext,
         step: &GherkinStep,
         example_data: &serde_json::Value,
-    ) {
-        let text = self.substitute_placeholders(&step.text, example_data);
-
-        // Handle doc string - prioritize this for request body
+    ) -> Result<(), String> {
+        let kind = StepKind::of(step);
+        let text = self.substitute_placeholders(&step.text, example_data, context);
+        let (definition, matched, args) = self
+            .config
+            .steps
+            .find(kind, &step.text, &text)
+            .ok_or_else(|| {
+                format!(
+                    "Undefined step: \"{} {}\"",
+                    step.keyword.trim(),
+                    step.text.trim()
+                )
+            })?;
+
+        // A JSON doc string is the request body, whichever step carries it
         if let Some(doc_str) = &step.doc_string {
-            let substituted_doc = self.substitute_placeholders(doc_str, example_data);
-            // Try to parse as JSON for request body
+            let substituted_doc = self.substitute_placeholders(doc_str, example_data, context);
             if let Ok(json) = serde_json::from_str::<serde_json::Value>(&substituted_doc) {
                 context.request_body = Some(json);
             }
         }
 
-        // Handle data table - store for setup/validation
+        // A data table is kept for form fields, with placeholders substituted
         if let Some(table_data) = &step.data_table {
-            // Substitute placeholders in each row
             let processed_table: Vec<serde_json::Value> = table_data
                 .iter()
                 .map(|row| {
                     let row_str = row.to_string();
-                    let substituted = self.substitute_placeholders(&row_str, example_data);
+                    let substituted = self.substitute_placeholders(&row_str, example_data, context);
                     serde_json::from_str(&substituted).unwrap_or(row.clone())
                 })
                 .collect();
             context.setup_data = Some(processed_table);
         }
+        context.data_table_cells = step.data_table_cells.as_ref().map(|rows| {
+            rows.iter()
+                .map(|row| {
+                    row.iter()
+                        .map(|cell| self.substitute_placeholders(cell, example_data, context))
+                        .collect()
+                })
+                .collect()
+        });
+
+        definition.handle(
+            context,
+            &StepMatch {
+                text: matched,
+                args,
+                example: example_data,
+            },
+        )
+    }
+
+    /// Substitute <placeholder> values with example data, then run variables,
+    /// then scoped variables (project to scenario). The first to define a
+    /// name wins. `{{generator}}` tokens are expanded last, so variable
+    /// values may contain them.
+    fn substitute_placeholders(
+        &self,
+        text: &str,
+        example_data: &serde_json::Value,
+        context: &StepContext,
+    ) -> String {
+        let mut result = text.to_string();
+
+        if let Some(obj) = example_data.as_object() {
+            for (key, value) in obj {
+                let placeholder = format!("<{}>", key);
+                result = result.replace(&placeholder, &Self::placeholder_value(value));
+            }
+        }
 
-        // Parse common patterns from text
-        if text.contains("request body")
-            || text.contains("request payload")
-            || text.contains("with body")
-        {
-            // Only set body from text if doc_string didn't already set it
-            if context.request_body.is_none() {
-                // Try to extract JSON from the step text
-                if let Some(json_start) = text.find('{') {
-                    if let Ok(json) = serde_json::from_str::<serde_json::Value>(&text[json_start..])
-                    {
-                        context.request_body = Some(json);
-                    }
-                } else {
-                    // Use example data as body if step mentions body
-                    context.request_body = Some(example_data.clone());
-                }
+        if result.contains('<') {
+            let variables = self
+                .variables
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            for (key, value) in variables.iter() {
+                let placeholder = format!("<{}>", key);
+                result = result.replace(&placeholder, &Self::placeholder_value(value));
             }
         }
 
-        if text.contains("header") {
-            // Pattern: "I set header X-Custom-Header to value"
-            self.parse_header(&text, context);
+        if result.contains('<') {
+            for (key, value) in context.variables.iter() {
+                let placeholder = format!("<{}>", key);
+                result = result.replace(&placeholder, value);
+            }
         }
 
-        if text.contains("query param") || text.contains("query parameter") {
-            // Pattern: "I set query param foo to bar"
-            self.parse_query_param(&text, context);
+        if result.contains("{{") {
+            result = context.generators.expand(&result);
         }
 
-        // Check for status expectations in Then steps
-        if step.keyword_type == "Outcome" {
-            if let Some(status) = self.extract_status_code(&text) {
-                context.expected_status = Some(status);
-            }
+        result
+    }
 
-            // Check for body contains
-            if text.contains("contains") || text.contains("should have") {
-                if let Some(pattern) = self.extract_quoted_string(&text) {
-                    context.expected_body_contains.push(pattern);
+    /// Render a JSON value as placeholder replacement text
+    pub(crate) fn placeholder_value(value: &serde_json::Value) -> String {
+        match value {
+            serde_json::Value::String(s) => s.clone(),
+            serde_json::Value::Number(n) => n.to_string(),
+            serde_json::Value::Bool(b) => b.to_string(),
+            _ => value.to_string(),
+        }
+    }
+
+    /// Save captured response values into the run variables
+    fn apply_captures(
+        &self,
+        body: &serde_json::Value,
+        captures: &[VariableCapture],
+    ) -> Result<(), String> {
+        for capture in captures {
+            let matches = body
+                .query(&capture.path)
+                .map_err(|e| format!("Invalid JSONPath {}: {}", capture.path, e))?;
+
+            let value = match matches.as_slice() {
+                [] => {
+                    return Err(format!(
+                        "Cannot save <{}>: {} matched nothing in the response body",
+                        capture.name, capture.path
+                    ))
                 }
-            }
+                [single] => (*single).clone(),
+                many => serde_json::Value::Array(many.iter().map(|v| (*v).clone()).collect()),
+            };
+
+            self.set_variable(&capture.name, value);
         }
+
+        Ok(())
     }
 
-    /// Substitute <placeholder> values with example data
-    fn substitute_placeholders(&self, text: &str, example_data: &serde_json::Value) -> String {
-        let mut result = text.to_string();
+    /// Look up a project fixture by name
+    fn fixture(&self, name: &str) -> Result<&FixtureFile, AppError> {
+        self.config
+            .fixtures
+            .get(name)
+            .ok_or_else(|| AppError::Validation(format!("Unknown fixture \"{}\"", name)))
+    }
 
-        if let Some(obj) = example_data.as_object() {
-            for (key, value) in obj {
-                let placeholder = format!("<{}>", key);
-                let replacement = match value {
-                    serde_json::Value::String(s) => s.clone(),
-                    serde_json::Value::Number(n) => n.to_string(),
-                    serde_json::Value::Bool(b) => b.to_string(),
-                    _ => value.to_string(),
-                };
-                result = result.replace(&placeholder, &replacement);
+    /// Replace fixture references with their contents. JSON fixtures become
+    /// the request body or expected body, with placeholders substituted;
+    /// other fixtures are sent as-is, or compared by SHA-256 digest.
+    fn resolve_fixtures(
+        &self,
+        context: &mut StepContext,
+        example_data: &serde_json::Value,
+    ) -> Result<(), AppError> {
+        if let Some(name) = context.request_fixture.take() {
+            let fixture = self.fixture(&name)?;
+            match self.fixture_json(fixture, example_data, context) {
+                Some(json) => context.request_body = Some(json),
+                None => context.raw_request_body = Some(fixture.clone()),
             }
         }
 
-        result
+        if let Some(name) = context.expected_fixture.take() {
+            let fixture = self.fixture(&name)?;
+            match self.fixture_json(fixture, example_data, context) {
+                Some(json) => context.expected_body = Some(json),
+                None => context
+                    .body_assertions
+                    .push(BodyAssertion::Sha256(hex::encode(Sha256::digest(
+                        &fixture.bytes,
+                    )))),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Parse a fixture as JSON after substituting placeholders in its text
+    fn fixture_json(
+        &self,
+        fixture: &FixtureFile,
+        example_data: &serde_json::Value,
+        context: &StepContext,
+    ) -> Option<serde_json::Value> {
+        let text = std::str::from_utf8(&fixture.bytes).ok()?;
+        serde_json::from_str(&self.substitute_placeholders(text, example_data, context)).ok()
     }
 
     /// Build request body from example data
@@ -270,7 +1233,7 @@ impl TestRunner {
         if let Some(body) = &context.request_body {
             // Substitute placeholders in body
             let body_str = body.to_string();
-            let substituted = self.substitute_placeholders(&body_str, example_data);
+            let substituted = self.substitute_placeholders(&body_str, example_data, context);
             serde_json::from_str(&substituted).ok()
         } else if !example_data.is_null() && example_data.is_object() {
             // Otherwise use example data directly (excluding expected_* fields)
@@ -286,32 +1249,165 @@ impl TestRunner {
         }
     }
 
-    /// Execute HTTP request
-    async fn execute_request(
+    /// Build form fields from the step data table, else from the request body.
+    /// Arrays become repeated fields.
+    fn build_form_fields(
         &self,
-        api: &Api,
-        environment: &Environment,
         context: &StepContext,
         example_data: &serde_json::Value,
-    ) -> Result<(i16, serde_json::Value), AppError> {
-        // Build URL
-        let endpoint = self.substitute_placeholders(&api.endpoint, example_data);
-        let mut url = format!(
-            "{}{}",
-            environment.domain_name.trim_end_matches('/'),
-            endpoint
-        );
-
-        // Add query params
-        if !context.query_params.is_empty() {
-            let params: Vec<String> = context
-                .query_params
-                .iter()
-                .map(|(k, v)| format!("{}={}", k, v))
-                .collect();
-            url = format!("{}?{}", url, params.join("&"));
+    ) -> Vec<(String, String)> {
+        let mut fields = Vec::new();
+
+        if let Some(rows) = &context.setup_data {
+            for row in rows {
+                match Self::key_value_row(row) {
+                    Some((name, value)) => Self::push_form_field(&mut fields, name, value),
+                    None => {
+                        for (name, value) in row.as_object().into_iter().flatten() {
+                            Self::push_form_field(&mut fields, name, value);
+                        }
+                    }
+                }
+            }
+        } else if let Some(body) = self.build_request_body(context, example_data) {
+            for (name, value) in body.as_object().into_iter().flatten() {
+                Self::push_form_field(&mut fields, name, value);
+            }
         }
 
+        fields
+    }
+
+    /// Read a `| field | value |` (or `name`/`key`) table row as one field
+    fn key_value_row(row: &serde_json::Value) -> Option<(&str, &serde_json::Value)> {
+        let obj = row.as_object()?;
+        if obj.len() != 2 {
+            return None;
+        }
+
+        let value = obj.get("value")?;
+        let name = ["field", "name", "key"]
+            .iter()
+            .find_map(|key| obj.get(*key))?
+            .as_str()?;
+
+        Some((name, value))
+    }
+
+    fn push_form_field(fields: &mut Vec<(String, String)>, name: &str, value: &serde_json::Value) {
+        match value {
+            serde_json::Value::Array(items) => {
+                for item in items {
+                    fields.push((name.to_string(), Self::placeholder_value(item)));
+                }
+            }
+            serde_json::Value::Null => fields.push((name.to_string(), String::new())),
+            _ => fields.push((name.to_string(), Self::placeholder_value(value))),
+        }
+    }
+
+    /// Build a multipart form. Values starting with `@` are uploaded as the
+    /// fixture of that name; a leading `@@` sends a literal `@`.
+    fn build_multipart(&self, fields: Vec<(String, String)>) -> Result<Form, AppError> {
+        let mut form = Form::new();
+
+        for (name, value) in fields {
+            form = if let Some(literal) = value.strip_prefix("@@") {
+                form.text(name, format!("@{}", literal))
+            } else if let Some(fixture_name) = value.strip_prefix('@') {
+                let fixture = self.config.fixtures.get(fixture_name).ok_or_else(|| {
+                    AppError::Validation(format!(
+                        "Unknown fixture @{} for form field {}",
+                        fixture_name, name
+                    ))
+                })?;
+                form.part(name, Self::fixture_part(fixture)?)
+            } else {
+                form.text(name, value)
+            };
+        }
+
+        Ok(form)
+    }
+
+    fn fixture_part(fixture: &FixtureFile) -> Result<Part, AppError> {
+        let part = Part::bytes(fixture.bytes.clone()).file_name(fixture.file_name.clone());
+
+        match &fixture.content_type {
+            Some(content_type) => part.mime_str(content_type).map_err(|e| {
+                AppError::Validation(format!(
+                    "Invalid content type for fixture {}: {}",
+                    fixture.file_name, e
+                ))
+            }),
+            None => Ok(part),
+        }
+    }
+
+    /// Replace `{name}` segments of an endpoint with URL-encoded values from
+    /// path param steps, else example columns, else run variables, else
+    /// scoped variables
+    fn bind_path_params(
+        &self,
+        endpoint: &str,
+        context: &StepContext,
+        example_data: &serde_json::Value,
+    ) -> Result<String, AppError> {
+        let mut path = String::with_capacity(endpoint.len());
+        let mut rest = endpoint;
+
+        while let Some(start) = rest.find('{') {
+            let end = rest[start..].find('}').map(|i| start + i).ok_or_else(|| {
+                AppError::Validation(format!("Unclosed path parameter in endpoint {}", endpoint))
+            })?;
+            let name = &rest[start + 1..end];
+
+            let value = context
+                .path_params
+                .get(name)
+                .cloned()
+                .or_else(|| {
+                    example_data
+                        .get(name)
+                        .filter(|v| !v.is_null())
+                        .map(Self::placeholder_value)
+                })
+                .or_else(|| self.variable(name).map(|v| Self::placeholder_value(&v)))
+                .or_else(|| context.variables.get(name).cloned())
+                .ok_or_else(|| {
+                    AppError::Validation(format!(
+                        "Path parameter {{{}}} in endpoint {} is not bound by a step, example column or variable",
+                        name, endpoint
+                    ))
+                })?;
+
+            path.push_str(&rest[..start]);
+            path.extend(utf8_percent_encode(&value, PATH_SEGMENT));
+            rest = &rest[end + 1..];
+        }
+
+        path.push_str(rest);
+        Ok(path)
+    }
+
+    /// Build the HTTP request for an example
+    fn build_request(
+        &self,
+        api: &Api,
+        environment: &Environment,
+        context: &StepContext,
+        example_data: &serde_json::Value,
+    ) -> Result<Request, AppError> {
+        // Build URL
+        let endpoint = context.generators.expand(&api.endpoint);
+        let endpoint = self.bind_path_params(&endpoint, context, example_data)?;
+        let endpoint = self.substitute_placeholders(&endpoint, example_data, context);
+        let url = format!(
+            "{}{}",
+            environment.domain_name.trim_end_matches('/'),
+            endpoint
+        );
+
         // Parse HTTP method
         let method = match api.http_method.to_uppercase().as_str() {
             "GET" => Method::GET,
@@ -332,6 +1428,11 @@ impl TestRunner {
         // Build request
         let mut request = self.client.request(method.clone(), &url);
 
+        // Add query params, percent-encoded in step order
+        if !context.query_params.is_empty() {
+            request = request.query(&context.query_params);
+        }
+
         // Add auth header if configured
         if let Some(token) = &self.config.auth_token {
             request = request.header("Authorization", format!("Bearer {}", token));
@@ -349,32 +1450,174 @@ impl TestRunner {
 
         // Add body for methods that support it
         if matches!(method, Method::POST | Method::PUT | Method::PATCH) {
-            if let Some(body) = self.build_request_body(context, example_data) {
-                request = request.json(&body);
+            let encoding = context
+                .body_encoding
+                .or_else(|| {
+                    api.request_content_type
+                        .as_deref()
+                        .and_then(BodyEncoding::parse)
+                })
+                .unwrap_or_default();
+
+            if let Some(fixture) = &context.raw_request_body {
+                // Non-JSON fixtures are sent as-is, whatever the encoding
+                if let Some(content_type) = &fixture.content_type {
+                    let has_content_type = context
+                        .request_headers
+                        .keys()
+                        .any(|k| k.eq_ignore_ascii_case("content-type"));
+                    if !has_content_type {
+                        request = request.header(reqwest::header::CONTENT_TYPE, content_type);
+                    }
+                }
+                request = request.body(fixture.bytes.clone());
+            } else {
+                match encoding {
+                    BodyEncoding::Json => {
+                        if let Some(body) = self.build_request_body(context, example_data) {
+                            request = request.json(&body);
+                        }
+                    }
+                    BodyEncoding::Form => {
+                        request = request.form(&self.build_form_fields(context, example_data));
+                    }
+                    BodyEncoding::Multipart => {
+                        let fields = self.build_form_fields(context, example_data);
+                        request = request.multipart(self.build_multipart(fields)?);
+                    }
+                }
             }
         }
 
-        // Execute request
-        let response: Response = request
-            .send()
-            .await
-            .map_err(|e| AppError::Internal(format!("HTTP request failed: {}", e)))?;
+        request
+            .build()
+            .map_err(|e| AppError::Validation(format!("Invalid request: {}", e)))
+    }
 
-        let status = response.status().as_u16() as i16;
+    /// Apply the steps' cookie changes to the jar, then send its cookies
+    fn send_cookies(context: &StepContext, request: &mut Request) {
+        let url = request.url().clone();
+        for action in &context.cookie_actions {
+            context.cookies.apply(action, &url);
+        }
+        context.cookies.add_to(request);
+    }
 
-        // Parse response body
-        let body: serde_json::Value = response.json().await.unwrap_or(serde_json::Value::Null);
+    /// Apply the API's auth strategy, else the bootstrap token, else the
+    /// environment's strategy, unless the run has an `auth_token`
+    async fn authorize(&self, api: &Api, mut request: Request) -> Result<Request, AppError> {
+        if self.config.auth_token.is_some() {
+            return Ok(request);
+        }
 
-        Ok((status, body))
+        let auth = match (self.config.auth.apis.get(&api.id), &self.bootstrap_header) {
+            (Some(auth), _) => auth,
+            (None, Some((name, value))) => {
+                set_header(&mut request, name, value)?;
+                return Ok(request);
+            }
+            (None, None) => match &self.config.auth.environment {
+                Some(auth) => auth,
+                None => return Ok(request),
+            },
+        };
+        let request = apply_auth(&self.client, &self.tokens, auth, request).await?;
+
+        // OAuth2 tokens are obtained during the run, like the bootstrap token
+        let mut mask = self.mask.lock().unwrap_or_else(PoisonError::into_inner);
+        for token in self.tokens.secrets() {
+            mask.add(&token);
+        }
+        Ok(request)
+    }
+
+    /// Execute HTTP request, following redirects as `policy` allows. Cookies
+    /// set by every response are stored in `cookies`, and the jar's cookies
+    /// for each redirect target are sent to it. Timing covers the whole chain.
+    async fn execute_request(
+        &self,
+        mut request: Request,
+        policy: RedirectPolicy,
+        cookies: &CookieJar,
+    ) -> Result<HttpResponse, AppError> {
+        self.timing.reset();
+        let sent = Instant::now();
+        let mut redirects = Vec::new();
+
+        let (response, url) = loop {
+            let resend = policy.follow.then(|| Resend::new(&request));
+            let url = request.url().clone();
+            let response: Response = self
+                .client
+                .execute(request)
+                .await
+                .map_err(|e| AppError::Internal(format!("HTTP request failed: {}", e)))?;
+            cookies.store(&url, response.headers());
+
+            let status = response.status();
+            let next = resend.zip(redirect::location(status, response.headers(), &url));
+            let Some(next) = next.and_then(|(resend, target)| {
+                let request = resend.to(status, target.clone())?;
+                Some((request, target))
+            }) else {
+                break (response, url);
+            };
+
+            if redirects.len() >= policy.max_hops as usize {
+                return Err(AppError::Validation(format!(
+                    "Stopped after {} redirects, the most the redirect policy allows",
+                    policy.max_hops
+                )));
+            }
+            let (next, target) = next;
+            redirects.push(RedirectHop {
+                status: status.as_u16() as i16,
+                url: redact_url(&url),
+                location: redact_url(&target),
+            });
+            request = next;
+            cookies.add_to(&mut request);
+        };
+        let ttfb = sent.elapsed();
+
+        let status = response.status().as_u16() as i16;
+        let headers = response.headers().clone();
+        let content_type = headers
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+
+        let received = Instant::now();
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|e| AppError::Internal(format!("Reading the response body failed: {}", e)))?;
+        let timing = self.timing.finish(ttfb, received.elapsed());
+
+        // Keep the raw bytes; JSON assertions and captures use the parsed body
+        let raw = ResponseBody::new(bytes.to_vec(), content_type);
+        let body = raw.json();
+
+        Ok(HttpResponse {
+            status,
+            headers,
+            url,
+            redirects,
+            body,
+            raw,
+            timing,
+        })
     }
 
     /// Validate response against expected values
     fn validate_response(
         &self,
-        status: i16,
-        body: &serde_json::Value,
+        response: &HttpResponse,
         context: &StepContext,
     ) -> Result<(), String> {
+        let status = response.status;
+        let body = &response.body;
+
         // Validate status code
         if let Some(expected_status) = context.expected_status {
             if status != expected_status {
@@ -383,132 +1626,85 @@ impl TestRunner {
                     expected_status, status
                 ));
             }
+        } else if context.expect_success && !(200..300).contains(&status) {
+            return Err(format!("Expected a 2xx status, got {}", status));
         }
 
-        // Validate body contains patterns
-        for pattern in &context.expected_body_contains {
-            let body_str = body.to_string();
-            if !body_str.contains(pattern) {
-                return Err(format!(
-                    "Response body does not contain expected pattern: {}",
-                    pattern
-                ));
-            }
+        // Validate JSONPath assertions
+        for assertion in &context.json_assertions {
+            assertion.check(body)?;
         }
 
-        // Validate expected body (deep comparison)
-        if let Some(expected_body) = &context.expected_body {
-            if !expected_body.is_null() && !self.json_contains(body, expected_body) {
+        // Validate response time
+        if let Some(limit) = context.max_response_time_ms {
+            if response.timing.total_ms > limit {
                 return Err(format!(
-                    "Response body does not match expected. Expected: {}, Got: {}",
-                    expected_body, body
+                    "Expected response time under {} ms, took {} ms",
+                    limit, response.timing.total_ms
                 ));
             }
         }
 
-        Ok(())
-    }
+        // Validate headers and cookies
+        for assertion in &context.header_assertions {
+            assertion.check(&response.headers)?;
+        }
+        for assertion in &context.cookie_assertions {
+            assertion.check(&response.headers)?;
+        }
+        for assertion in &context.cookie_jar_assertions {
+            assertion.check(context.cookies.get(&assertion.name).as_deref())?;
+        }
 
-    /// Check if actual JSON contains all fields from expected JSON
-    fn json_contains(&self, actual: &serde_json::Value, expected: &serde_json::Value) -> bool {
-        match (actual, expected) {
-            (serde_json::Value::Object(actual_obj), serde_json::Value::Object(expected_obj)) => {
-                expected_obj.iter().all(|(key, expected_value)| {
-                    actual_obj
-                        .get(key)
-                        .map(|actual_value| self.json_contains(actual_value, expected_value))
-                        .unwrap_or(false)
-                })
-            }
-            (serde_json::Value::Array(actual_arr), serde_json::Value::Array(expected_arr)) => {
-                expected_arr.iter().all(|expected_item| {
-                    actual_arr
-                        .iter()
-                        .any(|actual_item| self.json_contains(actual_item, expected_item))
-                })
-            }
-            _ => actual == expected,
+        // Validate redirects and the final URL
+        for assertion in &context.redirect_assertions {
+            assertion.check(&response.redirects, &response.url)?;
         }
-    }
 
-    /// Parse header from step text
-    fn parse_header(&self, text: &str, context: &mut StepContext) {
-        // Pattern: "header X-Custom-Header to value" or "header 'X-Custom-Header' with value 'foo'"
-        let words: Vec<&str> = text.split_whitespace().collect();
-        if let Some(header_idx) = words.iter().position(|&w| w == "header") {
-            if let (Some(key), Some(value)) = (words.get(header_idx + 1), words.last()) {
-                let key = key.trim_matches(|c| c == '\'' || c == '"');
-                let value = value.trim_matches(|c| c == '\'' || c == '"');
-                context
-                    .request_headers
-                    .insert(key.to_string(), value.to_string());
-            }
+        // Validate the raw body
+        for assertion in &context.body_assertions {
+            assertion.check(&response.raw)?;
         }
-    }
 
-    /// Parse query param from step text
-    fn parse_query_param(&self, text: &str, context: &mut StepContext) {
-        let words: Vec<&str> = text.split_whitespace().collect();
-        if let Some(param_idx) = words.iter().position(|&w| w == "param" || w == "parameter") {
-            if let (Some(key), Some(value)) = (words.get(param_idx + 1), words.last()) {
-                let key = key.trim_matches(|c| c == '\'' || c == '"');
-                let value = value.trim_matches(|c| c == '\'' || c == '"');
-                context
-                    .query_params
-                    .insert(key.to_string(), value.to_string());
-            }
+        // Validate body shape against the JSON Schema
+        if let Some(schema) = &context.response_schema {
+            schema.validate(body)?;
         }
-    }
 
-    /// Extract status code from step text
-    fn extract_status_code(&self, text: &str) -> Option<i16> {
-        // Look for patterns like "status 200", "status code 200", "200 status"
-        let words: Vec<&str> = text.split_whitespace().collect();
-        for (i, word) in words.iter().enumerate() {
-            if let Ok(status) = word.parse::<i16>() {
-                if (100..600).contains(&status) {
-                    return Some(status);
-                }
-            }
-            // Check next word after "status"
-            if *word == "status" || *word == "code" {
-                if let Some(next) = words.get(i + 1) {
-                    if let Ok(status) = next.parse::<i16>() {
-                        if (100..600).contains(&status) {
-                            return Some(status);
-                        }
-                    }
-                }
+        // Validate body contains patterns, against the raw text for non-JSON bodies
+        let body_str = match body {
+            serde_json::Value::Null => String::from_utf8_lossy(&response.raw.bytes).into_owned(),
+            _ => body.to_string(),
+        };
+        for pattern in &context.expected_body_contains {
+            if !body_str.contains(pattern) {
+                return Err(format!(
+                    "Response body does not contain expected pattern: {}",
+                    pattern
+                ));
             }
         }
-        None
-    }
-
-    /// Extract quoted string from text
-    fn extract_quoted_string(&self, text: &str) -> Option<String> {
-        // Look for single or double quoted strings
-        let mut in_quote = false;
-        let mut quote_char = '"';
-        let mut result = String::new();
 
-        for c in text.chars() {
-            if !in_quote && (c == '"' || c == '\'') {
-                in_quote = true;
-                quote_char = c;
-            } else if in_quote && c == quote_char {
-                return Some(result);
-            } else if in_quote {
-                result.push(c);
+        // Validate expected body (deep comparison)
+        if let Some(expected_body) = &context.expected_body {
+            if !expected_body.is_null() {
+                body_match::compare(body, expected_body, &context.body_match).map_err(|e| {
+                    format!(
+                        "Response body does not match expected ({}). Expected: {}, Got: {}",
+                        e, expected_body, body
+                    )
+                })?;
             }
         }
 
-        None
+        Ok(())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::services::secrets::OpenedVariables;
 
     #[test]
     fn test_substitute_placeholders() {
@@ -518,46 +1714,1420 @@ mod tests {
             "password": "secret123"
         });
 
-        let result =
-            runner.substitute_placeholders("user <email> with password <password>", &example);
+        let result = runner.substitute_placeholders(
+            "user <email> with password <password>",
+            &example,
+            &StepContext::default(),
+        );
         assert_eq!(result, "user test@example.com with password secret123");
     }
 
     #[test]
-    fn test_extract_status_code() {
+    fn test_captured_variables_are_substituted() {
+        let runner = TestRunner::new().unwrap();
+        let body = serde_json::json!({
+            "data": { "id": 42, "tags": [{ "name": "a" }, { "name": "b" }] }
+        });
+        let captures = vec![
+            VariableCapture {
+                path: "$.data.id".to_string(),
+                name: "user_id".to_string(),
+            },
+            VariableCapture {
+                path: "$.data.tags[*].name".to_string(),
+                name: "tags".to_string(),
+            },
+        ];
+
+        runner.apply_captures(&body, &captures).unwrap();
+        assert_eq!(runner.variable("user_id"), Some(serde_json::json!(42)));
+        assert_eq!(runner.variable("tags"), Some(serde_json::json!(["a", "b"])));
+
+        let example = serde_json::json!({});
+        assert_eq!(
+            runner.substitute_placeholders(
+                "/api/users/<user_id>",
+                &example,
+                &StepContext::default()
+            ),
+            "/api/users/42"
+        );
+
+        // Example data takes precedence over run variables
+        let example = serde_json::json!({ "user_id": 7 });
+        assert_eq!(
+            runner.substitute_placeholders(
+                "/api/users/<user_id>",
+                &example,
+                &StepContext::default()
+            ),
+            "/api/users/7"
+        );
+    }
+
+    #[test]
+    fn test_outcome_step_parses_json_assertion() {
+        let runner = TestRunner::new().unwrap();
+        let mut context = StepContext::default();
+        let step = GherkinStep {
+            keyword: "Then".to_string(),
+            keyword_type: "Outcome".to_string(),
+            text: r#""$.meta.total" should equal <total>"#.to_string(),
+            doc_string: None,
+            data_table: None,
+            data_table_cells: None,
+        };
+
+        runner
+            .process_step(&mut context, &step, &serde_json::json!({ "total": 200 }))
+            .unwrap();
+
+        // The number must not be mistaken for an expected status code
+        assert_eq!(context.expected_status, None);
+        assert_eq!(context.json_assertions.len(), 1);
+        assert!(context.json_assertions[0]
+            .check(&serde_json::json!({ "meta": { "total": 200 } }))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_undefined_step_is_reported() {
+        let runner = TestRunner::new().unwrap();
+        let mut context = StepContext::default();
+        let step = GherkinStep {
+            keyword: "Given ".to_string(),
+            keyword_type: "Context".to_string(),
+            text: "a valid user exists".to_string(),
+            doc_string: None,
+            data_table: None,
+            data_table_cells: None,
+        };
+
+        let err = runner
+            .process_step(&mut context, &step, &serde_json::json!({}))
+            .unwrap_err();
+        assert_eq!(err, r#"Undefined step: "Given a valid user exists""#);
+    }
+
+    #[test]
+    fn test_capture_missing_path_fails() {
         let runner = TestRunner::new().unwrap();
+        let captures = vec![VariableCapture {
+            path: "$.data.id".to_string(),
+            name: "user_id".to_string(),
+        }];
+
+        let err = runner
+            .apply_captures(&serde_json::json!({ "data": {} }), &captures)
+            .unwrap_err();
+        assert!(err.contains("user_id"));
+        assert!(runner.variable("user_id").is_none());
+    }
+
+    #[test]
+    fn test_validate_response_against_schema() {
+        let runner = TestRunner::new().unwrap();
+        let schema = ResponseSchema::compile(&serde_json::json!({
+            "type": "object",
+            "required": ["id", "email"],
+            "properties": { "id": { "type": "integer" } }
+        }))
+        .unwrap();
+        let context = StepContext {
+            expected_status: Some(200),
+            response_schema: Some(Arc::new(schema)),
+            ..Default::default()
+        };
+
+        let response = |body| HttpResponse {
+            status: 200,
+            headers: HeaderMap::new(),
+            url: Url::parse("http://localhost/").unwrap(),
+            redirects: Vec::new(),
+            body,
+            raw: ResponseBody::default(),
+            timing: RequestTiming::default(),
+        };
+
+        // Values differ from run to run, only the shape is checked
+        let body = serde_json::json!({ "id": 42, "email": "a@example.com" });
+        assert!(runner.validate_response(&response(body), &context).is_ok());
+
+        let body = serde_json::json!({ "id": "42" });
+        let err = runner
+            .validate_response(&response(body), &context)
+            .unwrap_err();
+        assert!(err.contains("/id:"), "{}", err);
+        assert!(err.contains("\"email\" is a required property"), "{}", err);
+    }
+
+    #[test]
+    fn test_header_assertion_steps() {
+        let runner = TestRunner::new().unwrap();
+        let mut context = StepContext::default();
+        let step = |text: &str| GherkinStep {
+            keyword: "Then".to_string(),
+            keyword_type: "Outcome".to_string(),
+            text: text.to_string(),
+            doc_string: None,
+            data_table: None,
+            data_table_cells: None,
+        };
+
+        let example = serde_json::json!({});
+        runner
+            .process_step(
+                &mut context,
+                &step(r#"header "Content-Type" should contain "application/json""#),
+                &example,
+            )
+            .unwrap();
+        runner
+            .process_step(
+                &mut context,
+                &step(r#"cookie "session" should be HttpOnly"#),
+                &example,
+            )
+            .unwrap();
+
+        // Assertions must not be read as request headers to send
+        assert!(context.request_headers.is_empty());
+        assert_eq!(context.header_assertions.len(), 1);
+        assert_eq!(context.cookie_assertions.len(), 1);
+
+        let mut headers = HeaderMap::new();
+        headers.insert("content-type", "text/html".parse().unwrap());
+        headers.append("set-cookie", "session=1; HttpOnly".parse().unwrap());
+        headers.append("set-cookie", "theme=dark".parse().unwrap());
+        let response = HttpResponse {
+            status: 200,
+            headers,
+            url: Url::parse("http://localhost/").unwrap(),
+            redirects: Vec::new(),
+            body: serde_json::Value::Null,
+            raw: ResponseBody::default(),
+            timing: RequestTiming::default(),
+        };
+
+        let err = runner.validate_response(&response, &context).unwrap_err();
+        assert!(err.contains("Content-Type"), "{}", err);
 
         assert_eq!(
-            runner.extract_status_code("status should be 200"),
-            Some(200)
+            headers_to_json(&response.headers),
+            serde_json::json!({
+                "content-type": ["text/html"],
+                "set-cookie": ["session=1; HttpOnly", "theme=dark"]
+            })
         );
+    }
+
+    #[test]
+    fn test_response_time_limit_is_enforced() {
+        let runner = TestRunner::new().unwrap();
+        let mut context = StepContext {
+            expected_status: Some(200),
+            max_response_time_ms: Some(1000),
+            ..Default::default()
+        };
+
+        // The step overrides the API default, and its number is not a status code
+        let step = GherkinStep {
+            keyword: "Then".to_string(),
+            keyword_type: "Outcome".to_string(),
+            text: "the response time should be under 300 ms".to_string(),
+            doc_string: None,
+            data_table: None,
+            data_table_cells: None,
+        };
+        runner
+            .process_step(&mut context, &step, &serde_json::json!({}))
+            .unwrap();
+        assert_eq!(context.max_response_time_ms, Some(300));
+        assert_eq!(context.expected_status, Some(200));
+
+        let response = |total_ms| HttpResponse {
+            status: 200,
+            headers: HeaderMap::new(),
+            url: Url::parse("http://localhost/").unwrap(),
+            redirects: Vec::new(),
+            body: serde_json::Value::Null,
+            raw: ResponseBody::default(),
+            timing: RequestTiming {
+                ttfb_ms: total_ms,
+                total_ms,
+                ..Default::default()
+            },
+        };
+
+        assert!(runner.validate_response(&response(120), &context).is_ok());
+        let err = runner
+            .validate_response(&response(450), &context)
+            .unwrap_err();
+        assert!(err.contains("300 ms"), "{}", err);
+    }
+
+    #[test]
+    fn test_non_json_body_assertions() {
+        let runner = TestRunner::new().unwrap();
+        let mut context = StepContext::default();
+        let step = |text: &str| GherkinStep {
+            keyword: "Then".to_string(),
+            keyword_type: "Outcome".to_string(),
+            text: text.to_string(),
+            doc_string: None,
+            data_table: None,
+            data_table_cells: None,
+        };
+
+        let example = serde_json::json!({});
+        runner
+            .process_step(
+                &mut context,
+                &step(r#"the response body should match "^pong$""#),
+                &example,
+            )
+            .unwrap();
+        runner
+            .process_step(&mut context, &step(r#"it contains "pong""#), &example)
+            .unwrap();
+        assert_eq!(context.body_assertions.len(), 1);
+
+        let raw = ResponseBody::new(b"pong".to_vec(), Some("text/plain".to_string()));
+        let response = HttpResponse {
+            status: 200,
+            headers: HeaderMap::new(),
+            url: Url::parse("http://localhost/").unwrap(),
+            redirects: Vec::new(),
+            body: raw.json(),
+            raw,
+            timing: RequestTiming::default(),
+        };
+
+        // The body is not JSON, yet plain-text patterns still apply
+        assert_eq!(response.body, serde_json::Value::Null);
+        assert!(runner.validate_response(&response, &context).is_ok());
+    }
+
+    #[test]
+    fn test_form_fields_from_data_table() {
+        let runner = TestRunner::new().unwrap();
+        let example = serde_json::json!({});
+
+        // A two-column field/value table is read row by row
+        let context = StepContext {
+            setup_data: Some(vec![
+                serde_json::json!({ "field": "name", "value": "Ada" }),
+                serde_json::json!({ "field": "avatar", "value": "@avatar.png" }),
+            ]),
+            ..Default::default()
+        };
         assert_eq!(
-            runner.extract_status_code("the status code is 404"),
-            Some(404)
+            runner.build_form_fields(&context, &example),
+            vec![
+                ("name".to_string(), "Ada".to_string()),
+                ("avatar".to_string(), "@avatar.png".to_string()),
+            ]
         );
-        assert_eq!(runner.extract_status_code("expect 500 error"), Some(500));
+
+        // Without a table the JSON body is flattened; arrays repeat the field
+        let context = StepContext {
+            request_body: Some(serde_json::json!({ "tags": ["a", "b"], "age": 36 })),
+            ..Default::default()
+        };
+        let fields = runner.build_form_fields(&context, &example);
+        assert!(fields.contains(&("tags".to_string(), "a".to_string())));
+        assert!(fields.contains(&("tags".to_string(), "b".to_string())));
+        assert!(fields.contains(&("age".to_string(), "36".to_string())));
     }
 
     #[test]
-    fn test_json_contains() {
+    fn test_data_table_request_bodies() {
         let runner = TestRunner::new().unwrap();
+        let example = serde_json::json!({ "age": 36 });
+        let step = |text: &str, cells: &[&[&str]]| GherkinStep {
+            keyword: "When".to_string(),
+            keyword_type: "Action".to_string(),
+            text: text.to_string(),
+            doc_string: None,
+            data_table: None,
+            data_table_cells: Some(
+                cells
+                    .iter()
+                    .map(|row| row.iter().map(|cell| cell.to_string()).collect())
+                    .collect(),
+            ),
+        };
 
-        let actual = serde_json::json!({
-            "id": 1,
-            "name": "test",
-            "extra": "field"
+        // Rows under the header become objects, placeholders typed after substitution
+        let mut context = StepContext::default();
+        runner
+            .process_step(
+                &mut context,
+                &step(
+                    "I send the following items:",
+                    &[
+                        &["name", "age", "admin"],
+                        &["Ada", "<age>", "true"],
+                        &["Alan", "41.5", "null"],
+                    ],
+                ),
+                &example,
+            )
+            .unwrap();
+        assert_eq!(
+            context.request_body,
+            Some(serde_json::json!([
+                { "name": "Ada", "age": 36, "admin": true },
+                { "name": "Alan", "age": 41.5, "admin": null },
+            ]))
+        );
+
+        // A vertical table is one object of names and values
+        let mut context = StepContext::default();
+        runner
+            .process_step(
+                &mut context,
+                &step(
+                    "I send the following item:",
+                    &[&["name", "Ada"], &["age", "<age>"], &["tags", "[\"a\"]"]],
+                ),
+                &example,
+            )
+            .unwrap();
+        assert_eq!(
+            context.request_body,
+            Some(serde_json::json!({ "name": "Ada", "age": 36, "tags": ["a"] }))
+        );
+
+        let err = runner
+            .process_step(
+                &mut StepContext::default(),
+                &step("I send the following item:", &[&["name", "Ada", "x"]]),
+                &example,
+            )
+            .unwrap_err();
+        assert!(err.contains("two-column"), "{}", err);
+
+        // Every row has a cell per header column
+        for cells in [&["Ada"][..], &["Ada", "36", "x"][..]] {
+            let err = runner
+                .process_step(
+                    &mut StepContext::default(),
+                    &step("I send the following items:", &[&["name", "age"], cells]),
+                    &example,
+                )
+                .unwrap_err();
+            assert_eq!(
+                err,
+                format!("Row 2 has {} cells, the header has 2", cells.len())
+            );
+        }
+    }
+
+    #[test]
+    fn test_doc_strings_and_tables_on_any_step() {
+        let runner = TestRunner::new().unwrap();
+        let example = serde_json::json!({ "name": "Ada" });
+
+        // Scenarios saved before step definitions may attach them to Then
+        // steps, which still feed the request
+        let mut context = StepContext::default();
+        let step = GherkinStep {
+            keyword: "Then".to_string(),
+            keyword_type: "Outcome".to_string(),
+            text: "the response status should be 201".to_string(),
+            doc_string: Some(r#"{ "name": "<name>" }"#.to_string()),
+            data_table: Some(vec![serde_json::json!({ "name": "<name>" })]),
+            data_table_cells: None,
+        };
+        runner.process_step(&mut context, &step, &example).unwrap();
+        assert_eq!(
+            context.request_body,
+            Some(serde_json::json!({ "name": "Ada" }))
+        );
+        assert_eq!(
+            context.setup_data,
+            Some(vec![serde_json::json!({ "name": "Ada" })])
+        );
+        assert_eq!(context.expected_status, Some(201));
+    }
+
+    #[test]
+    fn test_multipart_requires_known_fixtures() {
+        let mut config = TestConfig::default();
+        config.fixtures.insert(
+            "avatar.png".to_string(),
+            FixtureFile {
+                file_name: "avatar.png".to_string(),
+                content_type: Some("image/png".to_string()),
+                bytes: vec![0x89, 0x50, 0x4e, 0x47],
+            },
+        );
+        let runner = TestRunner::with_config(config).unwrap();
+
+        let fields = vec![
+            ("avatar".to_string(), "@avatar.png".to_string()),
+            ("handle".to_string(), "@@ada".to_string()),
+        ];
+        assert!(runner.build_multipart(fields).is_ok());
+
+        let fields = vec![("cv".to_string(), "@missing.pdf".to_string())];
+        let err = runner.build_multipart(fields).unwrap_err();
+        assert!(err.to_string().contains("@missing.pdf"), "{}", err);
+    }
+
+    #[test]
+    fn test_fixture_steps() {
+        let mut config = TestConfig::default();
+        config.fixtures.insert(
+            "user.json".to_string(),
+            FixtureFile {
+                file_name: "user.json".to_string(),
+                content_type: Some("application/json".to_string()),
+                bytes: br#"{ "email": "<email>" }"#.to_vec(),
+            },
+        );
+        config.fixtures.insert(
+            "report.pdf".to_string(),
+            FixtureFile {
+                file_name: "report.pdf".to_string(),
+                content_type: Some("application/pdf".to_string()),
+                bytes: b"%PDF-1.7".to_vec(),
+            },
+        );
+        let runner = TestRunner::with_config(config).unwrap();
+        let step = |keyword_type: &str, text: &str| GherkinStep {
+            keyword: "Given".to_string(),
+            keyword_type: keyword_type.to_string(),
+            text: text.to_string(),
+            doc_string: None,
+            data_table: None,
+            data_table_cells: None,
+        };
+
+        let example = serde_json::json!({ "email": "ada@example.com" });
+        let mut context = StepContext::default();
+        runner
+            .process_step(
+                &mut context,
+                &step("Action", r#"the request body is fixture "user.json""#),
+                &example,
+            )
+            .unwrap();
+        runner
+            .process_step(
+                &mut context,
+                &step(
+                    "Outcome",
+                    r#"the response body should equal fixture "report.pdf""#,
+                ),
+                &example,
+            )
+            .unwrap();
+        assert_eq!(context.request_fixture.as_deref(), Some("user.json"));
+        assert_eq!(context.expected_fixture.as_deref(), Some("report.pdf"));
+        assert!(context.body_assertions.is_empty());
+
+        // JSON fixtures become the body; other fixtures are compared by digest
+        runner.resolve_fixtures(&mut context, &example).unwrap();
+        assert_eq!(
+            context.request_body,
+            Some(serde_json::json!({ "email": "ada@example.com" }))
+        );
+        assert!(context.raw_request_body.is_none());
+        assert_eq!(context.body_assertions.len(), 1);
+
+        let raw = ResponseBody::new(b"%PDF-1.7".to_vec(), Some("application/pdf".to_string()));
+        assert!(context.body_assertions[0].check(&raw).is_ok());
+
+        let mut context = StepContext {
+            request_fixture: Some("missing.json".to_string()),
+            ..Default::default()
+        };
+        let err = runner.resolve_fixtures(&mut context, &example).unwrap_err();
+        assert!(err.to_string().contains("missing.json"), "{}", err);
+    }
+
+    #[test]
+    fn test_bind_path_params() {
+        let runner = TestRunner::new().unwrap();
+        let mut context = StepContext::default();
+        let step = GherkinStep {
+            keyword: "Given".to_string(),
+            keyword_type: "Context".to_string(),
+            text: "path param id is <user_id>".to_string(),
+            doc_string: None,
+            data_table: None,
+            data_table_cells: None,
+        };
+
+        let example = serde_json::json!({ "user_id": 42, "slug": "a b/c" });
+        runner.process_step(&mut context, &step, &example).unwrap();
+        assert_eq!(context.path_params.get("id"), Some(&"42".to_string()));
+        assert!(context.query_params.is_empty());
+
+        // Steps, then example columns; values never add path segments
+        let path = runner
+            .bind_path_params("/users/{id}/posts/{slug}", &context, &example)
+            .unwrap();
+        assert_eq!(path, "/users/42/posts/a%20b%2Fc");
+
+        runner.set_variable("org", serde_json::json!("acme"));
+        let path = runner
+            .bind_path_params("/orgs/{org}", &context, &example)
+            .unwrap();
+        assert_eq!(path, "/orgs/acme");
+
+        let err = runner
+            .bind_path_params("/users/{id}/items/{item_id}", &context, &example)
+            .unwrap_err();
+        assert!(err.to_string().contains("{item_id}"), "{}", err);
+    }
+
+    #[test]
+    fn test_query_params_repeat_and_encode() {
+        let runner = TestRunner::new().unwrap();
+        let mut context = StepContext::default();
+        let step = |text: &str| GherkinStep {
+            keyword: "Given".to_string(),
+            keyword_type: "Context".to_string(),
+            text: text.to_string(),
+            doc_string: None,
+            data_table: None,
+            data_table_cells: None,
+        };
+
+        let example = serde_json::json!({ "tags": ["a", "b&c"], "q": "x y" });
+        runner
+            .process_step(&mut context, &step("query param q is <q>"), &example)
+            .unwrap();
+        runner
+            .process_step(&mut context, &step("query param tag is <tags>"), &example)
+            .unwrap();
+        assert_eq!(
+            context.query_params,
+            vec![
+                ("q".to_string(), "x y".to_string()),
+                ("tag".to_string(), "a".to_string()),
+                ("tag".to_string(), "b&c".to_string()),
+            ]
+        );
+
+        let request = runner
+            .client
+            .get("http://localhost/search")
+            .query(&context.query_params)
+            .build()
+            .unwrap();
+        assert_eq!(
+            request.url().as_str(),
+            "http://localhost/search?q=x+y&tag=a&tag=b%26c"
+        );
+    }
+
+    #[test]
+    fn test_scoped_variables() {
+        let runner = TestRunner::with_config(TestConfig {
+            variables: VariableScopes {
+                environment: OpenedVariables {
+                    variables: HashMap::from([("api_key".to_string(), "k-123".to_string())]),
+                    secret_names: ["api_key".to_string()].into(),
+                    secret_values: vec!["k-123".to_string()],
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .unwrap();
+        let context = StepContext {
+            variables: Arc::new(HashMap::from([
+                ("tenant".to_string(), "acme".to_string()),
+                ("user_id".to_string(), "scoped-user".to_string()),
+            ])),
+            ..Default::default()
+        };
+
+        // Example columns and run variables take precedence
+        let example = serde_json::json!({ "user_id": 7 });
+        assert_eq!(
+            runner.substitute_placeholders("<tenant>/<user_id>", &example, &context),
+            "acme/7"
+        );
+        let path = runner
+            .bind_path_params("/tenants/{tenant}", &context, &example)
+            .unwrap();
+        assert_eq!(path, "/tenants/acme");
+
+        // Secret values never reach the recorded result
+        let result = runner.mask_secrets(TestResult {
+            scenario_id: Uuid::nil(),
+            api_id: Uuid::nil(),
+            example_index: 0,
+            pass: false,
+            error_message: Some("Expected key k-123".to_string()),
+            request: None,
+            response_status: 200,
+            response_data: Some(serde_json::json!({ "echo": "k-123" })),
+            response_headers: None,
+            request_duration_ms: 0,
+            timing: None,
+            redirects: Vec::new(),
+            workflow_steps: Vec::new(),
+            request_time: time::OffsetDateTime::now_utc(),
         });
+        assert_eq!(result.error_message.unwrap(), "Expected key [REDACTED]");
+        assert_eq!(
+            result.response_data,
+            Some(serde_json::json!({ "echo": "[REDACTED]" }))
+        );
+    }
 
-        let expected = serde_json::json!({
-            "id": 1,
-            "name": "test"
+    #[test]
+    fn test_generators_after_placeholders() {
+        let runner = TestRunner::with_config(TestConfig {
+            seed: 42,
+            ..Default::default()
+        })
+        .unwrap();
+        let context = |index| StepContext {
+            variables: Arc::new(HashMap::from([(
+                "email".to_string(),
+                "user-{{random_string:8}}@example.com".to_string(),
+            )])),
+            generators: Arc::new(Generators::for_example(runner.seed(), Uuid::nil(), index)),
+            ..Default::default()
+        };
+
+        let text = "<email> {{uuid}} {{unknown}}";
+        let first = runner.substitute_placeholders(text, &serde_json::Value::Null, &context(0));
+        assert!(!first.contains("{{random_string") && !first.contains("{{uuid}}"));
+        assert!(first.ends_with(" {{unknown}}"));
+
+        // The same seed and example reproduce the values
+        assert_eq!(
+            runner.substitute_placeholders(text, &serde_json::Value::Null, &context(0)),
+            first
+        );
+        assert_ne!(
+            runner.substitute_placeholders(text, &serde_json::Value::Null, &context(1)),
+            first
+        );
+    }
+
+    fn test_api(method: &str, endpoint: &str) -> Api {
+        let now = time::OffsetDateTime::now_utc();
+        Api {
+            id: Uuid::new_v4(),
+            collection_id: Uuid::new_v4(),
+            name: endpoint.to_string(),
+            http_method: method.to_string(),
+            endpoint: endpoint.to_string(),
+            severity: 1,
+            description: None,
+            response_schema: None,
+            sla_ms: None,
+            request_content_type: None,
+            auth: None,
+            redirect_policy: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// A scenario with one example expecting status 200
+    fn test_scenario(api: &Api, steps: serde_json::Value) -> Scenario {
+        let now = time::OffsetDateTime::now_utc();
+        Scenario {
+            id: Uuid::new_v4(),
+            api_id: api.id,
+            title: api.name.clone(),
+            description: None,
+            tags: Vec::new(),
+            kind: ScenarioKind::Single,
+            background_steps: serde_json::json!([]),
+            steps,
+            examples: serde_json::json!([{
+                "example": {},
+                "expected_response_body": null,
+                "expected_status_code": 200
+            }]),
+            response_schema: None,
+            body_match: None,
+            variables: Default::default(),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    fn local_environment(port: u16) -> Environment {
+        let now = time::OffsetDateTime::now_utc();
+        Environment {
+            id: Uuid::new_v4(),
+            project_id: Uuid::new_v4(),
+            title: "local".to_string(),
+            domain_name: format!("http://127.0.0.1:{}", port),
+            variables: Default::default(),
+            auth: None,
+            auth_bootstrap: None,
+            transport: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_auth_bootstrap_token_replaces_environment_auth() {
+        use crate::models::{AuthBootstrap, RequestAuth};
+        use crate::services::BootstrapScenario;
+        use axum::routing::{get, post};
+
+        async fn whoami(headers: axum::http::HeaderMap) -> axum::Json<serde_json::Value> {
+            let header = |name: &str| headers.get(name).map(|v| v.to_str().unwrap().to_string());
+            axum::Json(serde_json::json!({
+                "authorization": header("authorization"),
+                "session": header("x-session"),
+            }))
+        }
+
+        let app = axum::Router::new()
+            .route(
+                "/login",
+                post(|| async { axum::Json(serde_json::json!({ "data": { "token": "t-1" } })) }),
+            )
+            .route("/me", get(whoami))
+            .route("/public", get(whoami));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let mut environment = local_environment(port);
+        environment.auth = Some(RequestAuth::ApiKey {
+            name: "X-Session".to_string(),
+            value: "static".to_string(),
+            location: Default::default(),
         });
 
-        assert!(runner.json_contains(&actual, &expected));
+        let login = test_api("POST", "/login");
+        let me = test_api("GET", "/me");
+        let public = Api {
+            auth: Some(RequestAuth::None),
+            ..test_api("GET", "/public")
+        };
+        let config = TestConfig {
+            auth: RunAuth {
+                environment: environment.auth.clone(),
+                apis: HashMap::from([(public.id, RequestAuth::None)]),
+                bootstrap: Some(BootstrapScenario {
+                    config: serde_json::from_value::<AuthBootstrap>(serde_json::json!({
+                        "scenario_id": Uuid::nil(),
+                        "token_path": "$.data.token"
+                    }))
+                    .unwrap(),
+                    scenario: test_scenario(&login, serde_json::json!([])),
+                    api: login,
+                }),
+            },
+            ..Default::default()
+        };
+
+        let mut runner = TestRunner::with_config(config).unwrap();
+        runner.bootstrap(&environment).await.unwrap();
 
-        let not_expected = serde_json::json!({
-            "id": 2
+        // The token replaces the environment's key and is masked in results
+        let results = runner
+            .run_scenario(
+                &test_scenario(&me, serde_json::json!([])),
+                &me,
+                &environment,
+            )
+            .await
+            .unwrap();
+        assert!(results[0].pass, "{:?}", results[0].error_message);
+        assert_eq!(
+            results[0].response_data,
+            Some(serde_json::json!({ "authorization": "Bearer [REDACTED]", "session": null }))
+        );
+
+        // An API's own strategy still wins
+        let results = runner
+            .run_scenario(
+                &test_scenario(&public, serde_json::json!([])),
+                &public,
+                &environment,
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            results[0].response_data,
+            Some(serde_json::json!({ "authorization": null, "session": null }))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_replay_sends_only_the_stored_request() {
+        use crate::models::RequestAuth;
+        use axum::routing::get;
+
+        let app = axum::Router::new().route(
+            "/me",
+            get(|headers: axum::http::HeaderMap| async move {
+                let key = headers["x-key"].to_str().unwrap().to_string();
+                axum::Json(serde_json::json!({ "key": key }))
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let mut environment = local_environment(port);
+        environment.auth = Some(RequestAuth::ApiKey {
+            name: "X-Key".to_string(),
+            value: "k-123".to_string(),
+            location: Default::default(),
+        });
+        let config = TestConfig {
+            auth: RunAuth {
+                environment: environment.auth.clone(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let runner = TestRunner::with_config(config).unwrap();
+
+        let me = test_api("GET", "/me");
+        let scenario = test_scenario(&me, serde_json::json!([]));
+        let original = runner
+            .run_single_example(&scenario, &me, &environment, 0)
+            .await
+            .unwrap();
+        let stored = original.request.unwrap();
+        assert_eq!(stored.headers["x-key"], serde_json::json!(["[REDACTED]"]));
+
+        // The masked key is filled in again
+        let replay = runner
+            .replay_example(&scenario, &me, &environment, 0, &stored)
+            .await
+            .unwrap();
+        assert!(replay.pass, "{:?}", replay.error_message);
+        assert_eq!(
+            replay.response_data,
+            Some(serde_json::json!({ "key": "[REDACTED]" }))
+        );
+
+        // A scenario that now builds another request is not replayed
+        let changed = test_scenario(
+            &me,
+            serde_json::json!([{
+                "keyword": "Given",
+                "keyword_type": "Context",
+                "text": "query param page is 2"
+            }]),
+        );
+        let err = runner
+            .replay_example(&changed, &me, &environment, 0, &stored)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("URL"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_body_read_error_fails_the_example() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // The connection closes before the announced body is sent
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 1024];
+            let _ = socket.read(&mut buf).await;
+            let _ = socket
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 100\r\n\r\n{}")
+                .await;
         });
-        assert!(!runner.json_contains(&actual, &not_expected));
+
+        let api = test_api("GET", "/");
+        let runner = TestRunner::new().unwrap();
+        let results = runner
+            .run_scenario(
+                &test_scenario(&api, serde_json::json!([])),
+                &api,
+                &local_environment(port),
+            )
+            .await
+            .unwrap();
+        assert!(!results[0].pass);
+        let error = results[0].error_message.as_deref().unwrap();
+        assert!(
+            error.contains("Reading the response body failed"),
+            "{}",
+            error
+        );
+        assert!(results[0].request.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_oauth2_tokens_are_masked() {
+        use crate::models::RequestAuth;
+        use axum::routing::{get, post};
+
+        let app = axum::Router::new()
+            .route(
+                "/token",
+                post(|| async {
+                    axum::Json(serde_json::json!({
+                        "access_token": "at-1",
+                        "refresh_token": "rt-1",
+                        "expires_in": 3600
+                    }))
+                }),
+            )
+            .route(
+                "/me",
+                get(|headers: axum::http::HeaderMap| async move {
+                    let authorization = headers["authorization"].to_str().unwrap().to_string();
+                    axum::Json(
+                        serde_json::json!({ "authorization": authorization, "refresh": "rt-1" }),
+                    )
+                }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let mut environment = local_environment(port);
+        environment.auth = Some(RequestAuth::OAuth2ClientCredentials {
+            token_url: format!("http://127.0.0.1:{}/token", port),
+            client_id: "client".to_string(),
+            client_secret: "secret".to_string(),
+            scope: None,
+            client_auth: Default::default(),
+        });
+        let config = TestConfig {
+            auth: RunAuth {
+                environment: environment.auth.clone(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let me = test_api("GET", "/me");
+        let runner = TestRunner::with_config(config).unwrap();
+        let results = runner
+            .run_scenario(
+                &test_scenario(&me, serde_json::json!([])),
+                &me,
+                &environment,
+            )
+            .await
+            .unwrap();
+        assert!(results[0].pass, "{:?}", results[0].error_message);
+        assert_eq!(
+            results[0].response_data,
+            Some(
+                serde_json::json!({ "authorization": "Bearer [REDACTED]", "refresh": "[REDACTED]" })
+            )
+        );
+        let request = results[0].request.as_ref().unwrap();
+        assert!(!request.headers.to_string().contains("at-1"));
+    }
+
+    #[tokio::test]
+    async fn test_cookie_jar_carries_session_across_scenarios() {
+        use axum::routing::{get, post};
+
+        let app = axum::Router::new()
+            .route(
+                "/login",
+                post(|| async { ([("set-cookie", "session=abc; Path=/; HttpOnly")], "ok") }),
+            )
+            .route(
+                "/me",
+                get(|headers: axum::http::HeaderMap| async move {
+                    let cookie = headers
+                        .get("cookie")
+                        .map(|v| v.to_str().unwrap().to_string());
+                    axum::Json(serde_json::json!({ "cookie": cookie }))
+                }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let environment = local_environment(port);
+        let login_api = test_api("POST", "/login");
+        let login = test_scenario(
+            &login_api,
+            serde_json::json!([{
+                "keyword": "Then",
+                "keyword_type": "Outcome",
+                "text": "the cookie jar should contain \"session\" with value \"abc\""
+            }]),
+        );
+        let me_api = test_api("GET", "/me");
+        let me = test_scenario(
+            &me_api,
+            serde_json::json!([{
+                "keyword": "Given",
+                "keyword_type": "Context",
+                "text": "cookie \"theme\" is \"dark\""
+            }]),
+        );
+
+        let cookie_sent = |scope| {
+            let (login, me, login_api, me_api, environment) =
+                (&login, &me, &login_api, &me_api, &environment);
+            async move {
+                let runner = TestRunner::with_config(TestConfig {
+                    cookie_jar: scope,
+                    ..Default::default()
+                })
+                .unwrap();
+                let results = runner
+                    .run_scenario(login, login_api, environment)
+                    .await
+                    .unwrap();
+                assert!(results[0].pass, "{:?}", results[0].error_message);
+                let results = runner.run_scenario(me, me_api, environment).await.unwrap();
+                results[0].response_data.clone().unwrap()["cookie"].clone()
+            }
+        };
+
+        assert_eq!(
+            cookie_sent(CookieJarScope::Run).await,
+            "session=abc; theme=dark"
+        );
+        // Without a run jar the next scenario starts without the session
+        assert_eq!(cookie_sent(CookieJarScope::Example).await, "theme=dark");
+    }
+
+    #[tokio::test]
+    async fn test_redirect_chain_is_followed_and_recorded() {
+        use axum::http::StatusCode;
+        use axum::routing::{get, post};
+
+        let app = axum::Router::new()
+            .route(
+                "/login",
+                post(|| async {
+                    (
+                        StatusCode::SEE_OTHER,
+                        [("location", "/step"), ("set-cookie", "session=abc; Path=/")],
+                    )
+                }),
+            )
+            .route(
+                "/step",
+                get(|| async { (StatusCode::FOUND, [("location", "/home?tab=1")]) }),
+            )
+            .route(
+                "/home",
+                get(|headers: axum::http::HeaderMap| async move {
+                    let cookie = headers
+                        .get("cookie")
+                        .map(|v| v.to_str().unwrap().to_string());
+                    axum::Json(serde_json::json!({ "cookie": cookie }))
+                }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let environment = local_environment(port);
+        let api = test_api("POST", "/login");
+        let step = |keyword_type: &str, text: &str| serde_json::json!({ "keyword": "*", "keyword_type": keyword_type, "text": text });
+        let runner = TestRunner::new().unwrap();
+
+        let followed = test_scenario(
+            &api,
+            serde_json::json!([
+                step("Outcome", "the final URL should be \"/home?tab=1\""),
+                step("Outcome", "the request should be redirected 2 times"),
+                step("Outcome", "redirect 1 should be 303 to \"/step\""),
+                step("Outcome", "redirect 2 should be 302"),
+            ]),
+        );
+        let result = &runner
+            .run_scenario(&followed, &api, &environment)
+            .await
+            .unwrap()[0];
+        assert!(result.pass, "{:?}", result.error_message);
+        assert_eq!(result.redirects.len(), 2);
+        assert_eq!(
+            result.redirects[1].location,
+            format!("http://127.0.0.1:{}/home?tab=1", port)
+        );
+        // The cookie set by the first redirect reaches the final URL
+        assert_eq!(
+            result.response_data.as_ref().unwrap()["cookie"],
+            "session=abc"
+        );
+
+        let not_followed = test_scenario(
+            &api,
+            serde_json::json!([
+                step("Context", "redirects are not followed"),
+                step("Outcome", "the response status should be 303"),
+                step("Outcome", "the request should not be redirected"),
+                step("Outcome", "header \"Location\" should be \"/step\""),
+            ]),
+        );
+        let result = &runner
+            .run_scenario(&not_followed, &api, &environment)
+            .await
+            .unwrap()[0];
+        assert!(result.pass, "{:?}", result.error_message);
+
+        // The API's policy applies unless a step overrides it
+        let limited = Api {
+            redirect_policy: Some(RedirectPolicy {
+                follow: true,
+                max_hops: 1,
+            }),
+            ..api.clone()
+        };
+        let result = &runner
+            .run_scenario(&followed, &limited, &environment)
+            .await
+            .unwrap()[0];
+        assert!(!result.pass);
+        assert!(result
+            .error_message
+            .as_deref()
+            .unwrap()
+            .contains("Stopped after 1 redirects"));
+    }
+
+    #[test]
+    fn test_background_steps_run_first() {
+        let api = test_api("GET", "/items");
+        let mut scenario = test_scenario(
+            &api,
+            serde_json::json!([{
+                "keyword": "Then",
+                "keyword_type": "Outcome",
+                "text": "the response status should be 200"
+            }]),
+        );
+        scenario.background_steps = serde_json::json!([{
+            "keyword": "Given",
+            "keyword_type": "Context",
+            "text": "cookie \"theme\" is \"dark\""
+        }]);
+
+        let runner = TestRunner::new().unwrap();
+        let (plan, _) = runner.plan_scenario(&scenario, &api).unwrap();
+        let texts: Vec<&str> = plan.steps.iter().map(|s| s.text.as_str()).collect();
+        assert_eq!(
+            texts,
+            [
+                "cookie \"theme\" is \"dark\"",
+                "the response status should be 200"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_hooks_wrap_each_example() {
+        use crate::services::HookScenario;
+        use axum::routing::{get, post};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let logouts = Arc::new(AtomicUsize::new(0));
+        let counter = logouts.clone();
+        let app = axum::Router::new()
+            .route(
+                "/login",
+                post(|| async { ([("set-cookie", "session=abc; Path=/")], "ok") }),
+            )
+            .route(
+                "/logout",
+                post(move || async move {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    axum::http::StatusCode::NO_CONTENT
+                }),
+            )
+            .route(
+                "/items",
+                get(|headers: axum::http::HeaderMap| async move {
+                    match headers.get("cookie") {
+                        Some(cookie) if cookie == "session=abc" => axum::http::StatusCode::OK,
+                        _ => axum::http::StatusCode::UNAUTHORIZED,
+                    }
+                }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let environment = local_environment(port);
+        let items_api = test_api("GET", "/items");
+        let hook = |method: &str, endpoint: &str, tag: &str| {
+            let api = Api {
+                collection_id: items_api.collection_id,
+                ..test_api(method, endpoint)
+            };
+            let scenario = Scenario {
+                tags: vec![tag.to_string()],
+                examples: serde_json::json!([]),
+                ..test_scenario(&api, serde_json::json!([]))
+            };
+            HookScenario { scenario, api }
+        };
+        let mut items = test_scenario(&items_api, serde_json::json!([]));
+        items.examples = serde_json::json!([
+            { "example": {}, "expected_response_body": null, "expected_status_code": 200 },
+            { "example": {}, "expected_response_body": null, "expected_status_code": 404 }
+        ]);
+
+        // Each example logs in first, so the session cookie is sent even
+        // though every example starts with an empty jar
+        let runner = TestRunner::with_config(TestConfig {
+            hooks: ScenarioHooks::new([
+                hook("POST", "/login", "@before"),
+                hook("POST", "/logout", "@after"),
+            ]),
+            ..Default::default()
+        })
+        .unwrap();
+        let results = runner
+            .run_scenario(&items, &items_api, &environment)
+            .await
+            .unwrap();
+        assert!(results[0].pass, "{:?}", results[0].error_message);
+        assert!(!results[1].pass);
+        assert_eq!(results[1].response_status, 200);
+        assert_eq!(logouts.load(Ordering::SeqCst), 2);
+
+        // A failing before hook stops the example, but after hooks still run
+        let runner = TestRunner::with_config(TestConfig {
+            hooks: ScenarioHooks::new([
+                hook("POST", "/missing", "@before"),
+                hook("POST", "/logout", "@after"),
+            ]),
+            ..Default::default()
+        })
+        .unwrap();
+        let results = runner
+            .run_scenario(&items, &items_api, &environment)
+            .await
+            .unwrap();
+        assert_eq!(results[0].response_status, 0);
+        assert!(results[0]
+            .error_message
+            .as_deref()
+            .unwrap()
+            .starts_with("Before hook \"/missing\" failed"));
+        assert_eq!(logouts.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn test_workflow_calls_apis_in_turn() {
+        use axum::routing::{get, post};
+
+        let app = axum::Router::new()
+            .route(
+                "/orders",
+                post(|| async {
+                    (
+                        axum::http::StatusCode::CREATED,
+                        [("set-cookie", "cart=1; Path=/")],
+                        axum::Json(serde_json::json!({ "id": 7 })),
+                    )
+                }),
+            )
+            .route(
+                "/payments",
+                post(|headers: axum::http::HeaderMap| async move {
+                    match headers.get("cookie") {
+                        Some(cookie) if cookie == "cart=1" => axum::http::StatusCode::OK,
+                        _ => axum::http::StatusCode::PAYMENT_REQUIRED,
+                    }
+                }),
+            )
+            .route(
+                "/orders/{id}",
+                get(
+                    |axum::extract::Path(id): axum::extract::Path<i64>| async move {
+                        axum::Json(serde_json::json!({ "id": id, "status": "paid" }))
+                    },
+                ),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let environment = local_environment(port);
+        let create = Api {
+            name: "Create order".to_string(),
+            ..test_api("POST", "/orders")
+        };
+        let pay = Api {
+            name: "Pay".to_string(),
+            ..test_api("POST", "/payments")
+        };
+        let get_order = test_api("GET", "/orders/{id}");
+        let runner = TestRunner::with_config(TestConfig {
+            apis: vec![create.clone(), pay, get_order],
+            ..Default::default()
+        })
+        .unwrap();
+
+        let step = |keyword_type: &str, text: &str| serde_json::json!({ "keyword": "When", "keyword_type": keyword_type, "text": text });
+        let workflow = Scenario {
+            kind: ScenarioKind::Workflow,
+            ..test_scenario(
+                &create,
+                serde_json::json!([
+                    step("Action", "I call \"Create order\""),
+                    step("Outcome", "I save \"$.id\" as <order_id>"),
+                    step("Action", "I call \"Pay\""),
+                    step("Action", "I call \"GET /orders/{id}\""),
+                    step("Context", "path param id is <order_id>"),
+                    step("Outcome", "\"$.status\" should equal \"paid\""),
+                ]),
+            )
+        };
+
+        // One result for the example, with a result per call; the cart cookie
+        // from the first call reaches the second
+        let results = runner
+            .run_scenario(&workflow, &create, &environment)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(results[0].pass, "{:?}", results[0].error_message);
+        let statuses: Vec<i16> = results[0]
+            .workflow_steps
+            .iter()
+            .map(|s| s.response_status)
+            .collect();
+        assert_eq!(statuses, [201, 200, 200]);
+        assert_eq!(
+            results[0].response_data,
+            Some(serde_json::json!({ "id": 7, "status": "paid" }))
+        );
+
+        // A failing call fails the example and ends it
+        let unpaid = Scenario {
+            kind: ScenarioKind::Workflow,
+            ..test_scenario(
+                &create,
+                serde_json::json!([
+                    step("Action", "I call \"Pay\""),
+                    step("Action", "I call \"Create order\""),
+                ]),
+            )
+        };
+        let results = runner
+            .run_scenario(&unpaid, &create, &environment)
+            .await
+            .unwrap();
+        assert!(!results[0].pass);
+        assert_eq!(results[0].workflow_steps.len(), 1);
+        assert_eq!(
+            results[0].error_message.as_deref(),
+            Some("Call 1 (POST /payments) failed: Expected a 2xx status, got 402")
+        );
     }
 }
@@ -0,0 +1,310 @@
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use rustls::client::{
+    ClientSessionMemoryCache, ClientSessionStore, Tls12ClientSessionValue, Tls13ClientSessionValue,
+};
+use rustls::pki_types::ServerName;
+use rustls::NamedGroup;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::net::ToSocketAddrs;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tower::{Layer, Service};
+use utoipa::ToSchema;
+
+/// Where the time of a single request went, in milliseconds.
+///
+/// `dns_ms`, `connect_ms` and `tls_ms` are only set when the request opened
+/// a new connection; a reused keep-alive connection skips these phases.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct RequestTiming {
+    pub dns_ms: Option<i64>,
+    /// Opening the TCP connection, through the proxy when there is one
+    pub connect_ms: Option<i64>,
+    /// The TLS handshake of `https` targets
+    #[serde(default)]
+    pub tls_ms: Option<i64>,
+    /// From sending the request to receiving the response headers
+    pub ttfb_ms: i64,
+    /// Reading the response body
+    pub download_ms: i64,
+    /// `ttfb_ms + download_ms`; excludes building the request and parsing the body
+    pub total_ms: i64,
+}
+
+/// Connection phases observed while a request is in flight
+#[derive(Debug, Default)]
+struct ConnectionPhases {
+    dns: Option<Duration>,
+    /// When the connector was called
+    started: Option<Instant>,
+    /// Until the TLS handshake started, i.e. the TCP connection was open
+    tcp: Option<Duration>,
+    /// Until the connection was ready, including any TLS handshake
+    connect: Option<Duration>,
+}
+
+/// Records DNS, connect and TLS durations for the requests of one HTTP client.
+///
+/// Phases are not tagged per request, so the client must send one request at
+/// a time, which is how `TestRunner` executes examples.
+#[derive(Debug, Clone, Default)]
+pub struct TimingRecorder {
+    phases: Arc<Mutex<ConnectionPhases>>,
+}
+
+impl TimingRecorder {
+    /// Forget phases recorded by an earlier request
+    pub fn reset(&self) {
+        *self.lock() = ConnectionPhases::default();
+    }
+
+    /// Build the timing of the request that just completed
+    pub fn finish(&self, ttfb: Duration, download: Duration) -> RequestTiming {
+        let phases = self.lock();
+        let dns = phases.dns.unwrap_or_default();
+
+        RequestTiming {
+            dns_ms: phases.dns.map(millis),
+            // The connector resolves the host itself, so DNS is subtracted
+            connect_ms: phases
+                .tcp
+                .or(phases.connect)
+                .map(|c| millis(c.saturating_sub(dns))),
+            tls_ms: phases
+                .tcp
+                .zip(phases.connect)
+                .map(|(tcp, connect)| millis(connect.saturating_sub(tcp))),
+            ttfb_ms: millis(ttfb),
+            download_ms: millis(download),
+            total_ms: millis(ttfb + download),
+        }
+    }
+
+    /// Connector layer that times connection setup
+    pub fn layer(&self) -> ConnectTimingLayer {
+        ConnectTimingLayer {
+            recorder: self.clone(),
+        }
+    }
+
+    /// TLS session cache that records when each handshake starts
+    pub fn session_store(&self) -> HandshakeTimingStore {
+        HandshakeTimingStore {
+            recorder: self.clone(),
+            sessions: ClientSessionMemoryCache::new(256),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, ConnectionPhases> {
+        self.phases.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// DNS resolver that times the system resolver
+impl Resolve for TimingRecorder {
+    fn resolve(&self, name: Name) -> Resolving {
+        let recorder = self.clone();
+        let host = name.as_str().to_string();
+
+        Box::pin(async move {
+            let start = Instant::now();
+            let addrs =
+                tokio::task::spawn_blocking(move || (host.as_str(), 0).to_socket_addrs()).await??;
+            recorder.lock().dns = Some(start.elapsed());
+
+            Ok(Box::new(addrs) as Addrs)
+        })
+    }
+}
+
+/// Tower layer wrapping the HTTP client's connector
+#[derive(Debug, Clone)]
+pub struct ConnectTimingLayer {
+    recorder: TimingRecorder,
+}
+
+impl<S> Layer<S> for ConnectTimingLayer {
+    type Service = ConnectTimingService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ConnectTimingService {
+            inner,
+            recorder: self.recorder.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ConnectTimingService<S> {
+    inner: S,
+    recorder: TimingRecorder,
+}
+
+impl<S, R> Service<R> for ConnectTimingService<S>
+where
+    S: Service<R>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<S::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: R) -> Self::Future {
+        let recorder = self.recorder.clone();
+        let start = Instant::now();
+        recorder.lock().started = Some(start);
+        let connecting = self.inner.call(request);
+
+        Box::pin(async move {
+            let result = connecting.await;
+            recorder.lock().connect = Some(start.elapsed());
+            result
+        })
+    }
+}
+
+/// Session cache of the client's TLS config. The client looks up a session
+/// to resume as the first step of every handshake, right after the TCP
+/// connection is open, which the HTTP client does not otherwise expose.
+#[derive(Debug)]
+pub struct HandshakeTimingStore {
+    recorder: TimingRecorder,
+    sessions: ClientSessionMemoryCache,
+}
+
+impl ClientSessionStore for HandshakeTimingStore {
+    fn set_kx_hint(&self, server_name: ServerName<'static>, group: NamedGroup) {
+        self.sessions.set_kx_hint(server_name, group)
+    }
+
+    fn kx_hint(&self, server_name: &ServerName<'_>) -> Option<NamedGroup> {
+        self.sessions.kx_hint(server_name)
+    }
+
+    fn set_tls12_session(&self, server_name: ServerName<'static>, value: Tls12ClientSessionValue) {
+        self.sessions.set_tls12_session(server_name, value)
+    }
+
+    fn tls12_session(&self, server_name: &ServerName<'_>) -> Option<Tls12ClientSessionValue> {
+        self.sessions.tls12_session(server_name)
+    }
+
+    fn remove_tls12_session(&self, server_name: &ServerName<'static>) {
+        self.sessions.remove_tls12_session(server_name)
+    }
+
+    fn insert_tls13_ticket(
+        &self,
+        server_name: ServerName<'static>,
+        value: Tls13ClientSessionValue,
+    ) {
+        self.sessions.insert_tls13_ticket(server_name, value)
+    }
+
+    /// Called first when a handshake starts, before the TLS 1.2 lookup
+    fn take_tls13_ticket(
+        &self,
+        server_name: &ServerName<'static>,
+    ) -> Option<Tls13ClientSessionValue> {
+        let mut phases = self.recorder.lock();
+        if let Some(started) = phases.started {
+            phases.tcp.get_or_insert(started.elapsed());
+        }
+        drop(phases);
+
+        self.sessions.take_tls13_ticket(server_name)
+    }
+}
+
+fn millis(duration: Duration) -> i64 {
+    duration.as_millis() as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_finish_splits_dns_from_connect() {
+        let recorder = TimingRecorder::default();
+        {
+            let mut phases = recorder.lock();
+            phases.dns = Some(Duration::from_millis(20));
+            phases.connect = Some(Duration::from_millis(70));
+        }
+
+        let timing = recorder.finish(Duration::from_millis(120), Duration::from_millis(30));
+        assert_eq!(timing.dns_ms, Some(20));
+        assert_eq!(timing.connect_ms, Some(50));
+        assert_eq!(timing.tls_ms, None);
+        assert_eq!(timing.ttfb_ms, 120);
+        assert_eq!(timing.download_ms, 30);
+        assert_eq!(timing.total_ms, 150);
+
+        // A reused connection has no DNS or connect phase
+        recorder.reset();
+        let timing = recorder.finish(Duration::from_millis(10), Duration::ZERO);
+        assert_eq!(timing.dns_ms, None);
+        assert_eq!(timing.connect_ms, None);
+
+        // A TLS handshake started 40 ms into connecting
+        {
+            let mut phases = recorder.lock();
+            phases.dns = Some(Duration::from_millis(20));
+            phases.tcp = Some(Duration::from_millis(40));
+            phases.connect = Some(Duration::from_millis(100));
+        }
+        let timing = recorder.finish(Duration::ZERO, Duration::ZERO);
+        assert_eq!(timing.connect_ms, Some(20));
+        assert_eq!(timing.tls_ms, Some(60));
+    }
+
+    #[tokio::test]
+    async fn test_records_phases_of_new_connections() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            loop {
+                let mut buf = [0u8; 1024];
+                if socket.read(&mut buf).await.unwrap_or(0) == 0 {
+                    break;
+                }
+                let _ = socket
+                    .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok")
+                    .await;
+            }
+        });
+
+        let recorder = TimingRecorder::default();
+        let client = reqwest::Client::builder()
+            .dns_resolver(recorder.clone())
+            .connector_layer(recorder.layer())
+            .build()
+            .unwrap();
+        let url = format!("http://localhost:{}/", port);
+
+        recorder.reset();
+        client.get(&url).send().await.unwrap().text().await.unwrap();
+        let timing = recorder.finish(Duration::ZERO, Duration::ZERO);
+        assert!(timing.dns_ms.is_some());
+        assert!(timing.connect_ms.is_some());
+        assert_eq!(timing.tls_ms, None);
+
+        // The second request reuses the keep-alive connection
+        recorder.reset();
+        client.get(&url).send().await.unwrap().text().await.unwrap();
+        let timing = recorder.finish(Duration::ZERO, Duration::ZERO);
+        assert_eq!(timing.dns_ms, None);
+        assert_eq!(timing.connect_ms, None);
+    }
+}
@@ -0,0 +1,438 @@
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use reqwest::header::{HeaderMap, CONTENT_TYPE};
+use reqwest::{Request, Url};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeSet;
+use utoipa::ToSchema;
+
+use crate::services::body::stored_body_value;
+
+/// Replacement for secret header, query and body values
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Header names that always carry credentials
+const SECRET_HEADERS: &[&str] = &["authorization", "proxy_authorization", "cookie"];
+
+/// Name fragments that mark a header, query parameter or JSON field as secret
+const SECRET_FRAGMENTS: &[&str] = &["token", "secret", "password", "passwd", "api_key", "apikey"];
+
+/// Characters escaped by form-urlencoded serialization (spaces become `+`)
+const FORM_VALUE: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'*')
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_');
+
+/// The request as it was sent, after placeholder substitution and encoding,
+/// with secrets redacted
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct SentRequest {
+    pub method: String,
+    /// URL, including the encoded query string; redirects followed are
+    /// recorded separately
+    pub url: String,
+    /// Request headers as `{ name: [values] }`
+    #[schema(value_type = Object)]
+    pub headers: Value,
+    /// Body in the same representation as `response_data`; streamed bodies
+    /// such as multipart forms are recorded as `{ content_type, streamed }`
+    pub body: Option<Value>,
+}
+
+impl SentRequest {
+    /// Record a built request, redacting credentials
+    pub fn capture(request: &Request) -> Self {
+        let content_type = request
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let is_form = content_type
+            .as_deref()
+            .is_some_and(|ct| ct.starts_with("application/x-www-form-urlencoded"));
+        let body = request.body().map(|body| match body.as_bytes() {
+            Some(bytes) if is_form => {
+                stored_body_value(redact_form(bytes).as_bytes(), content_type.as_deref())
+            }
+            Some(bytes) => redact_json(stored_body_value(bytes, content_type.as_deref())),
+            None => json!({ "content_type": content_type, "streamed": true }),
+        });
+
+        Self {
+            method: request.method().to_string(),
+            url: redact_url(request.url()),
+            headers: redact_headers(headers_to_json(request.headers())),
+            body,
+        }
+    }
+}
+
+impl SentRequest {
+    /// The parts that differ from `other`: the method, the URL from its path
+    /// on (the origin comes from the environment), each header not `ignored`
+    /// and the body. Multipart boundaries, random per request, are left out.
+    pub fn differences(&self, other: &SentRequest, ignored: impl Fn(&str) -> bool) -> Vec<String> {
+        let mut differences = Vec::new();
+        if !self.method.eq_ignore_ascii_case(&other.method) {
+            differences.push("method".to_string());
+        }
+        if url_from_path(&self.url) != url_from_path(&other.url) {
+            differences.push("URL".to_string());
+        }
+
+        let names: BTreeSet<&String> = [&self.headers, &other.headers]
+            .into_iter()
+            .filter_map(Value::as_object)
+            .flat_map(|headers| headers.keys())
+            .collect();
+        for name in names {
+            let (ours, theirs) = (self.headers.get(name), other.headers.get(name));
+            if !ignored(name) && ours.map(without_boundary) != theirs.map(without_boundary) {
+                differences.push(format!("header {}", name));
+            }
+        }
+
+        if self.body.as_ref().map(without_boundary) != other.body.as_ref().map(without_boundary) {
+            differences.push("body".to_string());
+        }
+        differences
+    }
+}
+
+fn url_from_path(url: &str) -> String {
+    match Url::parse(url) {
+        Ok(url) => match url.query() {
+            Some(query) => format!("{}?{}", url.path(), query),
+            None => url.path().to_string(),
+        },
+        Err(_) => url.to_string(),
+    }
+}
+
+/// A value with `boundary=` parameters of content types cut off
+fn without_boundary(value: &Value) -> Value {
+    match value {
+        Value::String(s) => match s.find("boundary=") {
+            Some(index) => Value::String(s[..index].to_string()),
+            None => value.clone(),
+        },
+        Value::Array(items) => Value::Array(items.iter().map(without_boundary).collect()),
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(key, value)| (key.clone(), without_boundary(value)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+/// A URL with secret query parameters redacted
+pub(crate) fn redact_url(url: &Url) -> String {
+    if !url.query_pairs().any(|(name, _)| is_secret_name(&name)) {
+        return url.to_string();
+    }
+
+    let pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .map(|(name, value)| {
+            let value = if is_secret_name(&name) {
+                REDACTED.to_string()
+            } else {
+                value.into_owned()
+            };
+            (name.into_owned(), value)
+        })
+        .collect();
+    let mut url = url.clone();
+    url.query_pairs_mut().clear().extend_pairs(pairs);
+    url.to_string()
+}
+
+/// Masks known secret values, such as environment secrets, wherever they
+/// appear in a recorded result, including their URL-encoded forms
+#[derive(Debug, Clone, Default)]
+pub struct SecretMask {
+    /// Longest first, so a secret containing another is masked whole
+    patterns: Vec<String>,
+}
+
+impl SecretMask {
+    pub fn new(values: &[String]) -> Self {
+        let mut mask = Self::default();
+        for value in values {
+            mask.add(value);
+        }
+        mask
+    }
+
+    /// Also mask `value`, e.g. a token obtained during the run
+    pub fn add(&mut self, value: &str) {
+        if value.is_empty() {
+            return;
+        }
+
+        self.patterns.extend([
+            value.to_string(),
+            utf8_percent_encode(value, NON_ALPHANUMERIC).to_string(),
+            utf8_percent_encode(value, FORM_VALUE)
+                .to_string()
+                .replace("%20", "+"),
+        ]);
+        self.patterns
+            .sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+        self.patterns.dedup();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    /// Replace secret values in text
+    pub fn text(&self, text: &str) -> String {
+        self.patterns
+            .iter()
+            .fold(text.to_string(), |text, pattern| {
+                text.replace(pattern, REDACTED)
+            })
+    }
+
+    /// Replace secret values in every string of a JSON value
+    pub fn json(&self, value: Value) -> Value {
+        match value {
+            Value::String(s) => Value::String(self.text(&s)),
+            Value::Object(map) => Value::Object(
+                map.into_iter()
+                    .map(|(key, value)| (key, self.json(value)))
+                    .collect(),
+            ),
+            Value::Array(items) => Value::Array(items.into_iter().map(|v| self.json(v)).collect()),
+            other => other,
+        }
+    }
+
+    /// Replace secret values in a recorded request
+    pub fn request(&self, request: SentRequest) -> SentRequest {
+        SentRequest {
+            method: request.method,
+            url: self.text(&request.url),
+            headers: self.json(request.headers),
+            body: request.body.map(|body| self.json(body)),
+        }
+    }
+}
+
+/// Convert headers to `{ name: [values] }`, keeping repeated headers such as
+/// `Set-Cookie` as separate values
+pub fn headers_to_json(headers: &HeaderMap) -> Value {
+    let mut map = serde_json::Map::new();
+
+    for (name, value) in headers {
+        let value = String::from_utf8_lossy(value.as_bytes()).into_owned();
+        if let Value::Array(values) = map
+            .entry(name.as_str())
+            .or_insert_with(|| Value::Array(Vec::new()))
+        {
+            values.push(Value::String(value));
+        }
+    }
+
+    Value::Object(map)
+}
+
+/// Whether a header, query parameter or field name holds a secret
+pub fn is_secret_name(name: &str) -> bool {
+    let name = name.to_lowercase().replace('-', "_");
+    SECRET_HEADERS.contains(&name.as_str())
+        || SECRET_FRAGMENTS
+            .iter()
+            .any(|fragment| name.contains(fragment))
+}
+
+/// Replace the values of secret headers in `{ name: [values] }`
+fn redact_headers(mut headers: Value) -> Value {
+    if let Value::Object(map) = &mut headers {
+        for (name, values) in map.iter_mut() {
+            if is_secret_name(name) {
+                if let Value::Array(values) = values {
+                    values.fill(Value::String(REDACTED.to_string()));
+                }
+            }
+        }
+    }
+    headers
+}
+
+/// Replace the values of secret fields in a form-urlencoded body
+fn redact_form(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes)
+        .split('&')
+        .map(|pair| match pair.split_once('=') {
+            Some((name, _))
+                if is_secret_name(
+                    &percent_decode_str(&name.replace('+', " ")).decode_utf8_lossy(),
+                ) =>
+            {
+                format!(
+                    "{}={}",
+                    name,
+                    utf8_percent_encode(REDACTED, NON_ALPHANUMERIC)
+                )
+            }
+            _ => pair.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// Replace the values of secret fields, at any depth
+fn redact_json(value: Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(key, value)| {
+                    let value = if is_secret_name(&key) && !value.is_object() {
+                        Value::String(REDACTED.to_string())
+                    } else {
+                        redact_json(value)
+                    };
+                    (key, value)
+                })
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.into_iter().map(redact_json).collect()),
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capture_redacts_secrets() {
+        let request = reqwest::Client::new()
+            .post("http://localhost/login?api_key=abc&page=2")
+            .header("Authorization", "Bearer secret-token")
+            .header("X-Request-Id", "42")
+            .json(&json!({
+                "email": "ada@example.com",
+                "password": "hunter2",
+                "profile": { "refresh_token": "xyz" }
+            }))
+            .build()
+            .unwrap();
+
+        let sent = SentRequest::capture(&request);
+        assert_eq!(sent.method, "POST");
+        assert_eq!(
+            sent.url,
+            "http://localhost/login?api_key=%5BREDACTED%5D&page=2"
+        );
+        assert_eq!(sent.headers["authorization"], json!([REDACTED]));
+        assert_eq!(sent.headers["x-request-id"], json!(["42"]));
+        assert_eq!(
+            sent.body,
+            Some(json!({
+                "email": "ada@example.com",
+                "password": REDACTED,
+                "profile": { "refresh_token": REDACTED }
+            }))
+        );
+    }
+
+    #[test]
+    fn test_capture_streamed_body() {
+        let form = reqwest::multipart::Form::new().text("name", "Ada");
+        let request = reqwest::Client::new()
+            .post("http://localhost/upload")
+            .multipart(form)
+            .build()
+            .unwrap();
+
+        let body = SentRequest::capture(&request).body.unwrap();
+        assert_eq!(body["streamed"], json!(true));
+        assert!(body["content_type"]
+            .as_str()
+            .unwrap()
+            .starts_with("multipart/form-data"));
+    }
+
+    #[test]
+    fn test_differences() {
+        let stored = SentRequest {
+            method: "POST".to_string(),
+            url: "http://staging.example.com/upload?dry_run=true".to_string(),
+            headers: json!({
+                "content-type": ["multipart/form-data; boundary=abc"],
+                "x-signature": ["s-1"]
+            }),
+            body: Some(json!({
+                "content_type": "multipart/form-data; boundary=abc",
+                "streamed": true
+            })),
+        };
+        let rebuilt = SentRequest {
+            url: "http://localhost:8080/upload?dry_run=true".to_string(),
+            headers: json!({
+                "content-type": ["multipart/form-data; boundary=xyz"],
+                "x-signature": ["s-2"]
+            }),
+            body: Some(json!({
+                "content_type": "multipart/form-data; boundary=xyz",
+                "streamed": true
+            })),
+            ..stored.clone()
+        };
+        assert!(rebuilt
+            .differences(&stored, |name| name == "x-signature")
+            .is_empty());
+        assert_eq!(
+            rebuilt.differences(&stored, |_| false),
+            vec!["header x-signature".to_string()]
+        );
+
+        let rebuilt = SentRequest {
+            method: "PUT".to_string(),
+            url: "http://staging.example.com/upload".to_string(),
+            body: None,
+            ..stored.clone()
+        };
+        assert_eq!(
+            rebuilt.differences(&stored, |_| false),
+            vec!["method".to_string(), "URL".to_string(), "body".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_secret_mask() {
+        let mask = SecretMask::new(&["s3cret value".to_string(), String::new()]);
+        let request = reqwest::Client::new()
+            .get("http://localhost/items")
+            .query(&[("q", "s3cret value")])
+            .header("X-Tenant", "s3cret value")
+            .build()
+            .unwrap();
+
+        let sent = mask.request(SentRequest::capture(&request));
+        assert_eq!(sent.url, "http://localhost/items?q=[REDACTED]");
+        assert_eq!(sent.headers["x-tenant"], json!([REDACTED]));
+        assert_eq!(
+            mask.json(json!({ "echo": ["id s3cret value"] })),
+            json!({ "echo": ["id [REDACTED]"] })
+        );
+        assert_eq!(mask.text("no secrets"), "no secrets");
+    }
+
+    #[test]
+    fn test_capture_redacts_form_fields() {
+        let request = reqwest::Client::new()
+            .post("http://localhost/login")
+            .form(&[("user", "ada"), ("password", "hunter2")])
+            .build()
+            .unwrap();
+
+        let body = SentRequest::capture(&request).body.unwrap();
+        assert_eq!(body["text"], json!("user=ada&password=%5BREDACTED%5D"));
+    }
+}
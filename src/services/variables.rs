@@ -0,0 +1,184 @@
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::services::secrets::OpenedVariables;
+
+/// Where a variable is defined, from broadest to narrowest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum VariableScope {
+    Project,
+    Collection,
+    Environment,
+    Scenario,
+    Example,
+}
+
+/// The value a variable resolves to and where it came from
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct ResolvedVariable {
+    pub value: String,
+    /// Scope the value comes from
+    pub scope: VariableScope,
+    /// Broader scopes that also define the name, whose values were replaced
+    pub overridden: Vec<VariableScope>,
+    /// Whether the value comes from a secret environment variable
+    pub secret: bool,
+}
+
+/// Variables of every scope that can apply to a run against one environment.
+/// Narrower scopes win: project, collection, environment, scenario, then the
+/// example row.
+#[derive(Debug, Clone, Default)]
+pub struct VariableScopes {
+    pub project: BTreeMap<String, String>,
+    /// Variables of each collection in the project, by collection ID
+    pub collections: HashMap<Uuid, BTreeMap<String, String>>,
+    /// Decrypted environment variables
+    pub environment: OpenedVariables,
+}
+
+impl VariableScopes {
+    /// Resolve the variables for a scenario of an API in `collection_id`,
+    /// optionally including the columns of one example row
+    pub fn resolve(
+        &self,
+        collection_id: Uuid,
+        scenario: &BTreeMap<String, String>,
+        example: Option<&serde_json::Value>,
+    ) -> BTreeMap<String, ResolvedVariable> {
+        let mut resolved = BTreeMap::new();
+
+        layer(&mut resolved, VariableScope::Project, &self.project);
+        if let Some(variables) = self.collections.get(&collection_id) {
+            layer(&mut resolved, VariableScope::Collection, variables);
+        }
+        layer(
+            &mut resolved,
+            VariableScope::Environment,
+            &self.environment.variables,
+        );
+        for name in &self.environment.secret_names {
+            if let Some(variable) = resolved.get_mut(name) {
+                variable.secret = true;
+            }
+        }
+        layer(&mut resolved, VariableScope::Scenario, scenario);
+
+        if let Some(serde_json::Value::Object(columns)) = example {
+            let columns =
+                columns
+                    .iter()
+                    .filter(|(_, value)| !value.is_null())
+                    .map(|(name, value)| {
+                        let value = match value {
+                            serde_json::Value::String(s) => s.clone(),
+                            other => other.to_string(),
+                        };
+                        (name.clone(), value)
+                    });
+            layer(&mut resolved, VariableScope::Example, columns);
+        }
+
+        resolved
+    }
+
+    /// Effective values for a scenario, without example columns
+    pub fn effective(
+        &self,
+        collection_id: Uuid,
+        scenario: &BTreeMap<String, String>,
+    ) -> HashMap<String, String> {
+        self.resolve(collection_id, scenario, None)
+            .into_iter()
+            .map(|(name, variable)| (name, variable.value))
+            .collect()
+    }
+}
+
+/// Apply one scope over the broader ones already resolved
+fn layer<I, K, V>(
+    resolved: &mut BTreeMap<String, ResolvedVariable>,
+    scope: VariableScope,
+    variables: I,
+) where
+    I: IntoIterator<Item = (K, V)>,
+    K: AsRef<str>,
+    V: AsRef<str>,
+{
+    for (name, value) in variables {
+        let value = value.as_ref().to_string();
+        match resolved.get_mut(name.as_ref()) {
+            Some(variable) => {
+                variable.overridden.push(variable.scope);
+                variable.value = value;
+                variable.scope = scope;
+                variable.secret = false;
+            }
+            None => {
+                resolved.insert(
+                    name.as_ref().to_string(),
+                    ResolvedVariable {
+                        value,
+                        scope,
+                        overridden: Vec::new(),
+                        secret: false,
+                    },
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn map(entries: &[(&str, &str)]) -> BTreeMap<String, String> {
+        entries
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_resolve_narrower_scope_wins() {
+        let collection_id = Uuid::new_v4();
+        let scopes = VariableScopes {
+            project: map(&[("base_user", "p@example.com"), ("region", "eu")]),
+            collections: HashMap::from([(collection_id, map(&[("base_user", "c@example.com")]))]),
+            environment: OpenedVariables {
+                variables: HashMap::from([("api_key".to_string(), "k-1".to_string())]),
+                secret_names: ["api_key".to_string()].into(),
+                secret_values: vec!["k-1".to_string()],
+            },
+        };
+        let scenario = map(&[("base_user", "s@example.com")]);
+
+        let resolved = scopes.resolve(collection_id, &scenario, None);
+        assert_eq!(resolved["base_user"].value, "s@example.com");
+        assert_eq!(resolved["base_user"].scope, VariableScope::Scenario);
+        assert_eq!(
+            resolved["base_user"].overridden,
+            vec![VariableScope::Project, VariableScope::Collection]
+        );
+        assert_eq!(resolved["region"].scope, VariableScope::Project);
+        assert!(resolved["api_key"].secret);
+
+        // The example row wins last; other collections do not apply
+        let example = json!({ "base_user": "e@example.com", "region": null });
+        let resolved = scopes.resolve(Uuid::new_v4(), &BTreeMap::new(), Some(&example));
+        assert_eq!(resolved["base_user"].value, "e@example.com");
+        assert_eq!(
+            resolved["base_user"].overridden,
+            vec![VariableScope::Project]
+        );
+        assert_eq!(resolved["region"].value, "eu");
+
+        let effective = scopes.effective(collection_id, &BTreeMap::new());
+        assert_eq!(effective["base_user"], "c@example.com");
+    }
+}
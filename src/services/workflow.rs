@@ -0,0 +1,202 @@
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::models::{Api, GherkinStep};
+use crate::services::redirect::RedirectHop;
+use crate::services::steps::{StepArg, StepKind, StepRegistry};
+use crate::services::test_runner::TestResult;
+use crate::services::timing::RequestTiming;
+use crate::services::transcript::SentRequest;
+
+/// Step starting each call of a workflow scenario. It names an API of the
+/// scenario's project, or gives its method and endpoint (`"POST /orders"`).
+pub const CALL_STEP: &str = "(I )call (the )(API ){string}";
+
+/// The steps of a workflow scenario that make up one call
+#[derive(Debug, Clone)]
+pub struct WorkflowCall {
+    /// Name, or method and endpoint, of the API called
+    pub target: String,
+    pub steps: Vec<GherkinStep>,
+}
+
+/// Split a workflow scenario's steps into calls, each starting at a call step
+pub fn split(steps: &[GherkinStep]) -> Result<Vec<WorkflowCall>, String> {
+    static CALLS: LazyLock<StepRegistry> = LazyLock::new(|| {
+        let mut registry = StepRegistry::new();
+        registry
+            .register(StepKind::Request, CALL_STEP, "", |_, _| Ok(()))
+            .expect("valid call step");
+        registry
+    });
+
+    let mut calls: Vec<WorkflowCall> = Vec::new();
+    for step in steps {
+        let target = (StepKind::of(step) == StepKind::Request)
+            .then(|| CALLS.find(StepKind::Request, &step.text, &step.text))
+            .flatten()
+            .and_then(|(_, _, args)| match args.into_iter().next() {
+                Some(StepArg::Text(target)) => Some(target),
+                _ => None,
+            });
+        match (target, calls.last_mut()) {
+            (Some(target), _) => calls.push(WorkflowCall {
+                target,
+                steps: Vec::new(),
+            }),
+            (None, Some(call)) => call.steps.push(step.clone()),
+            (None, None) => {
+                return Err(format!(
+                    "Workflow steps must start with a call step such as \
+                     `When I call \"Create order\"`, found \"{}\"",
+                    step.text
+                ))
+            }
+        }
+    }
+
+    if calls.is_empty() {
+        return Err("A workflow scenario needs at least one call step".to_string());
+    }
+    Ok(calls)
+}
+
+/// The API a call targets: the one with that name, or with that method and
+/// endpoint, among the project's APIs
+pub fn resolve<'a>(target: &str, apis: &'a [Api]) -> Result<&'a Api, String> {
+    let by_endpoint = |api: &&Api| {
+        target.split_once(' ').is_some_and(|(method, endpoint)| {
+            api.http_method.eq_ignore_ascii_case(method) && api.endpoint == endpoint.trim()
+        })
+    };
+
+    let mut matches: Vec<&Api> = apis.iter().filter(|api| api.name == target).collect();
+    if matches.is_empty() {
+        matches = apis.iter().filter(by_endpoint).collect();
+    }
+
+    match matches.as_slice() {
+        [api] => Ok(api),
+        [] => Err(format!("No API \"{}\" in this project", target)),
+        _ => Err(format!(
+            "{} APIs match \"{}\"; call it by a unique name",
+            matches.len(),
+            target
+        )),
+    }
+}
+
+/// Result of one call of a workflow example
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct WorkflowStepResult {
+    pub api_id: Uuid,
+    pub pass: bool,
+    pub error_message: Option<String>,
+    /// The request sent, with secrets redacted; absent when it could not be built
+    pub request: Option<SentRequest>,
+    pub response_status: i16,
+    pub response_data: Option<serde_json::Value>,
+    pub response_headers: Option<serde_json::Value>,
+    pub request_duration_ms: i64,
+    pub timing: Option<RequestTiming>,
+    #[serde(default)]
+    pub redirects: Vec<RedirectHop>,
+}
+
+impl From<TestResult> for WorkflowStepResult {
+    fn from(r: TestResult) -> Self {
+        Self {
+            api_id: r.api_id,
+            pass: r.pass,
+            error_message: r.error_message,
+            request: r.request,
+            response_status: r.response_status,
+            response_data: r.response_data,
+            response_headers: r.response_headers,
+            request_duration_ms: r.request_duration_ms,
+            timing: r.timing,
+            redirects: r.redirects,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(keyword_type: &str, text: &str) -> GherkinStep {
+        GherkinStep {
+            keyword: "When".to_string(),
+            keyword_type: keyword_type.to_string(),
+            text: text.to_string(),
+            doc_string: None,
+            data_table: None,
+            data_table_cells: None,
+        }
+    }
+
+    fn api(name: &str, method: &str, endpoint: &str) -> Api {
+        let now = time::OffsetDateTime::now_utc();
+        Api {
+            id: Uuid::new_v4(),
+            collection_id: Uuid::new_v4(),
+            name: name.to_string(),
+            http_method: method.to_string(),
+            endpoint: endpoint.to_string(),
+            severity: 1,
+            description: None,
+            response_schema: None,
+            sla_ms: None,
+            request_content_type: None,
+            auth: None,
+            redirect_policy: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    #[test]
+    fn test_split_at_call_steps() {
+        let calls = split(&[
+            step("Action", "I call \"Create order\""),
+            step("Context", "request body is {\"sku\": \"A-1\"}"),
+            step("Outcome", "I save \"$.id\" as <order_id>"),
+            step("Action", "I call the API \"GET /orders/{id}\""),
+            step("Outcome", "the response status should be 200"),
+        ])
+        .unwrap();
+
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].target, "Create order");
+        assert_eq!(calls[0].steps.len(), 2);
+        assert_eq!(calls[1].target, "GET /orders/{id}");
+        assert_eq!(calls[1].steps[0].text, "the response status should be 200");
+
+        assert!(split(&[step("Context", "header \"X-Id\" is \"1\"")])
+            .unwrap_err()
+            .contains("must start with a call step"));
+        assert!(split(&[]).is_err());
+    }
+
+    #[test]
+    fn test_resolve_by_name_or_endpoint() {
+        let apis = [
+            api("Create order", "POST", "/orders"),
+            api("Get order", "GET", "/orders/{id}"),
+            api("Get order", "GET", "/v2/orders/{id}"),
+        ];
+
+        assert_eq!(resolve("Create order", &apis).unwrap().id, apis[0].id);
+        assert_eq!(resolve("post /orders", &apis).unwrap().id, apis[0].id);
+        assert_eq!(
+            resolve("GET /v2/orders/{id}", &apis).unwrap().id,
+            apis[2].id
+        );
+        assert!(resolve("Get order", &apis)
+            .unwrap_err()
+            .contains("2 APIs match"));
+        assert!(resolve("Pay", &apis).unwrap_err().contains("No API"));
+    }
+}
@@ -7,6 +7,7 @@ use sqlx::postgres::PgPool;
 
 use crate::config::Config;
 use crate::queue::{JobQueue, RedisQueue};
+use crate::services::SecretCipher;
 
 /// Application state shared across all handlers
 #[derive(Clone)]
@@ -124,6 +125,11 @@ impl AppState {
     pub fn mongo_db(&self) -> mongodb::Database {
         self.mongo_client.database(&self.config.mongodb_database)
     }
+
+    /// Cipher for environment secrets
+    pub fn secret_cipher(&self) -> SecretCipher {
+        SecretCipher::new(&self.config.secrets_key)
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
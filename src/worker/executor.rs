@@ -3,12 +3,12 @@ use std::time::Instant;
 use uuid::Uuid;
 
 use serval_run::error::AppResult;
-use serval_run::models::{Api, Environment};
+use serval_run::models::{Api, Environment, Scenario};
 use serval_run::queue::{JobResult, TestJob, TestJobType};
 use serval_run::repositories::{
     ApiRepository, EnvironmentRepository, Repository, ScenarioRepository,
 };
-use serval_run::services::{TestConfig, TestResult, TestRunner};
+use serval_run::services::{random_seed, Hook, TestConfig, TestRunner};
 use serval_run::state::AppState;
 
 use super::result_handler::ResultHandler;
@@ -28,15 +28,6 @@ impl JobExecutor {
         let start = Instant::now();
         let user_id = job.user_id;
 
-        // Build test config from job config
-        let test_config = TestConfig {
-            timeout: std::time::Duration::from_secs(job.config.timeout_seconds),
-            auth_token: job.config.auth_token.clone(),
-            custom_headers: job.config.custom_headers.clone(),
-        };
-
-        let test_runner = TestRunner::with_config(test_config)?;
-
         // Load environment using Repository trait method
         let environment = <EnvironmentRepository as Repository<Environment>>::find_by_id(
             &self.state.db,
@@ -47,32 +38,46 @@ impl JobExecutor {
         // Get project_id from environment
         let project_id = environment.project_id;
 
-        // Execute based on job type
-        let results = match job.job_type {
-            TestJobType::Scenario => {
-                self.execute_scenario(&test_runner, job.target_id, user_id, &environment)
-                    .await?
-            }
-            TestJobType::Api => {
-                self.execute_api(&test_runner, job.target_id, user_id, &environment)
-                    .await?
-            }
-            TestJobType::Collection => {
-                self.execute_collection(&test_runner, job.target_id, user_id, &environment)
-                    .await?
-            }
+        // Load what the job runs before the config, which loads the fixtures
+        // its scenarios reference
+        let (collection_id, runs) = self.targets(&job, &environment, user_id).await?;
+
+        // Build test config from job config
+        let test_config = TestConfig {
+            timeout: std::time::Duration::from_secs(job.config.timeout_seconds),
+            auth_token: job.config.auth_token.clone(),
+            custom_headers: job.config.custom_headers.clone(),
+            seed: job.config.seed.unwrap_or_else(random_seed),
+            cookie_jar: job.config.cookie_jar,
+            ..TestConfig::load(
+                &self.state,
+                &environment,
+                Some(collection_id),
+                runs.iter().map(|(scenario, _)| scenario),
+            )
+            .await?
         };
 
+        let mut test_runner = TestRunner::with_config(test_config)?;
+        test_runner.bootstrap(&environment).await?;
+
+        let mut results = Vec::new();
+        for (scenario, api) in &runs {
+            results.extend(
+                test_runner
+                    .run_scenario(scenario, api, &environment)
+                    .await?,
+            );
+        }
+
         let total_duration_ms = start.elapsed().as_millis() as i64;
 
         // Save results and create report (using PostgreSQL)
         let report_id = ResultHandler::save_results(
             &self.state,
-            job.user_id,
+            &job,
             project_id,
-            &job.job_type,
-            job.target_id,
-            job.environment_id,
+            test_runner.seed(),
             &results,
         )
         .await?;
@@ -97,70 +102,71 @@ impl JobExecutor {
         })
     }
 
-    /// Execute a single scenario test
-    async fn execute_scenario(
+    /// Scenarios the job runs, in order, with their APIs, and the collection
+    /// whose hook scenarios run around them
+    async fn targets(
         &self,
-        runner: &TestRunner,
-        scenario_id: Uuid,
-        user_id: Uuid,
+        job: &TestJob,
         environment: &Environment,
-    ) -> AppResult<Vec<TestResult>> {
-        // Use find_by_id_and_user for ownership verification
-        let scenario =
-            ScenarioRepository::find_by_id_and_user(&self.state.db, scenario_id, user_id).await?;
-
-        let api =
-            <ApiRepository as Repository<Api>>::find_by_id(&self.state.db, scenario.api_id).await?;
-
-        runner.run_scenario(&scenario, &api, environment).await
-    }
-
-    /// Execute all scenarios for an API
-    async fn execute_api(
-        &self,
-        runner: &TestRunner,
-        api_id: Uuid,
         user_id: Uuid,
-        environment: &Environment,
-    ) -> AppResult<Vec<TestResult>> {
-        let api = ApiRepository::find_by_id_and_user(&self.state.db, api_id, user_id).await?;
-
-        // Use list_by_api with high limit to get all scenarios
-        let scenarios =
-            ScenarioRepository::list_by_api(&self.state.db, api_id, user_id, 1000, 0).await?;
+    ) -> AppResult<(Uuid, Vec<(Scenario, Api)>)> {
+        match job.job_type {
+            TestJobType::Scenario => {
+                // Use find_by_id_and_user for ownership verification
+                let scenario =
+                    ScenarioRepository::find_by_id_and_user(&self.state.db, job.target_id, user_id)
+                        .await?;
+                let api =
+                    <ApiRepository as Repository<Api>>::find_by_id(&self.state.db, scenario.api_id)
+                        .await?;
+                Ok((api.collection_id, vec![(scenario, api)]))
+            }
+            TestJobType::Api => {
+                let api =
+                    ApiRepository::find_by_id_and_user(&self.state.db, job.target_id, user_id)
+                        .await?;
+                let scenarios = self.api_scenarios(&api, environment, user_id).await?;
+                Ok((api.collection_id, scenarios))
+            }
+            TestJobType::Collection => {
+                // Use list_by_collection with high limit to get all APIs
+                let mut apis = ApiRepository::list_by_collection(
+                    &self.state.db,
+                    job.target_id,
+                    user_id,
+                    1000,
+                    0,
+                )
+                .await?;
+                apis.sort_by_key(|a| a.created_at);
 
-        let mut all_results = Vec::new();
-        for scenario in scenarios {
-            let results = runner.run_scenario(&scenario, &api, environment).await?;
-            all_results.extend(results);
+                let mut runs = Vec::new();
+                for api in &apis {
+                    runs.extend(self.api_scenarios(api, environment, user_id).await?);
+                }
+                Ok((job.target_id, runs))
+            }
         }
-
-        Ok(all_results)
     }
 
-    /// Execute all scenarios for all APIs in a collection
-    async fn execute_collection(
+    /// Scenarios of an API with the API, in creation order so values captured
+    /// by earlier scenarios are available to later ones; hook scenarios only
+    /// run around the others, and the auth bootstrap before them
+    async fn api_scenarios(
         &self,
-        runner: &TestRunner,
-        collection_id: Uuid,
-        user_id: Uuid,
+        api: &Api,
         environment: &Environment,
-    ) -> AppResult<Vec<TestResult>> {
-        // Use list_by_collection with high limit to get all APIs
-        let apis =
-            ApiRepository::list_by_collection(&self.state.db, collection_id, user_id, 1000, 0)
-                .await?;
-
-        let mut all_results = Vec::new();
-        for api in &apis {
-            let scenarios =
-                ScenarioRepository::list_by_api(&self.state.db, api.id, user_id, 1000, 0).await?;
-            for scenario in scenarios {
-                let results = runner.run_scenario(&scenario, api, environment).await?;
-                all_results.extend(results);
-            }
-        }
-
-        Ok(all_results)
+        user_id: Uuid,
+    ) -> AppResult<Vec<(Scenario, Api)>> {
+        // Use list_by_api with high limit to get all scenarios
+        let mut scenarios =
+            ScenarioRepository::list_by_api(&self.state.db, api.id, user_id, 1000, 0).await?;
+        scenarios.retain(|s| Hook::of(s).is_none() && !environment.is_bootstrap(s));
+        scenarios.sort_by_key(|s| s.created_at);
+
+        Ok(scenarios
+            .into_iter()
+            .map(|scenario| (scenario, api.clone()))
+            .collect())
     }
 }
@@ -3,7 +3,7 @@ use uuid::Uuid;
 
 use serval_run::error::AppResult;
 use serval_run::models::CreateReport;
-use serval_run::queue::TestJobType;
+use serval_run::queue::{TestJob, TestJobType};
 use serval_run::repositories::mongo::{ExecutionLog, MongoRepository};
 use serval_run::repositories::ReportRepository;
 use serval_run::services::TestResult;
@@ -13,14 +13,13 @@ use serval_run::state::AppState;
 pub struct ResultHandler;
 
 impl ResultHandler {
-    /// Save test results and create a report in PostgreSQL
+    /// Save test results and create a report in PostgreSQL, recording the
+    /// run's generator seed
     pub async fn save_results(
         state: &AppState,
-        user_id: Uuid,
+        job: &TestJob,
         project_id: Uuid,
-        job_type: &TestJobType,
-        target_id: Uuid,
-        environment_id: Uuid,
+        seed: u64,
         results: &[TestResult],
     ) -> AppResult<Uuid> {
         // Calculate summary
@@ -30,22 +29,23 @@ impl ResultHandler {
 
         // Determine report level and collection_id based on job type
         // Level: 0 = scenario, 1 = api, 2 = collection
-        let (report_level, collection_id) = match job_type {
+        let (report_level, collection_id) = match job.job_type {
             TestJobType::Scenario => (0_i16, None),
             TestJobType::Api => (1_i16, None),
-            TestJobType::Collection => (2_i16, Some(target_id)),
+            TestJobType::Collection => (2_i16, Some(job.target_id)),
         };
 
         // Create report using PostgreSQL repository
         let create_report = CreateReport {
-            environment_id,
+            environment_id: job.environment_id,
             collection_id,
             report_level,
-            report_type: Some(job_type.as_str().to_string()),
+            report_type: Some(job.job_type.as_str().to_string()),
+            seed: Some(seed as i64),
         };
 
         let report =
-            ReportRepository::create(&state.db, project_id, user_id, &create_report).await?;
+            ReportRepository::create(&state.db, project_id, job.user_id, &create_report).await?;
 
         // Save individual test results to PostgreSQL
         Self::save_responses(state, report.id, results).await?;
@@ -61,7 +61,7 @@ impl ResultHandler {
         ReportRepository::finish_report(
             &state.db,
             report.id,
-            user_id,
+            job.user_id,
             pass_rate,
             total_tests as i32,
         )
@@ -94,12 +94,23 @@ impl ResultHandler {
                 api_id: Set(result.api_id),
                 scenario_id: Set(result.scenario_id),
                 example_index: Set(result.example_index),
+                request_data: Set(result
+                    .request
+                    .as_ref()
+                    .and_then(|r| serde_json::to_value(r).ok())),
                 response_data: Set(result.response_data.clone()),
+                response_headers: Set(result.response_headers.clone()),
                 response_status: Set(result.response_status),
                 pass: Set(result.pass),
                 error_message: Set(result.error_message.clone()),
                 request_time: Set(result.request_time),
                 request_duration_ms: Set(Some(result.request_duration_ms as i32)),
+                timing: Set(result
+                    .timing
+                    .as_ref()
+                    .and_then(|t| serde_json::to_value(t).ok())),
+                redirects: Set(result.stored_redirects()),
+                workflow_steps: Set(result.stored_workflow_steps()),
             };
 
             response_model.insert(&state.db).await.map_err(|e| {
@@ -115,11 +126,16 @@ impl ResultHandler {
                 api_id: r.api_id.to_string(),
                 scenario_id: r.scenario_id.to_string(),
                 example_index: r.example_index,
+                request: r.request.clone(),
                 response_status: r.response_status,
                 response_data: r.response_data.clone(),
+                response_headers: r.response_headers.clone(),
                 pass: r.pass,
                 error_message: r.error_message.clone(),
                 duration_ms: r.request_duration_ms,
+                timing: r.timing.clone(),
+                redirects: r.redirects.clone(),
+                workflow_steps: r.workflow_steps.clone(),
                 created_at: bson::DateTime::now(),
             })
             .collect();
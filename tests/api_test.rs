@@ -65,6 +65,92 @@ async fn test_create_api_with_all_fields() {
     assert_eq!(body["description"].as_str().unwrap(), "Creates a new user");
 }
 
+#[tokio::test]
+async fn test_create_api_with_response_schema() {
+    let app = TestApp::new().await;
+    let factory = Factory::new(&app.state);
+    let auth = factory.create_user().await;
+    let project = factory.create_project(auth.user_id).await;
+    let collection = factory.create_collection(project.id, auth.user_id).await;
+
+    let schema = json!({
+        "type": "object",
+        "required": ["id"],
+        "properties": { "id": { "type": "integer" } }
+    });
+
+    let response = app
+        .server
+        .post(&format!("/api/collections/{}/apis", collection.id))
+        .add_header("Authorization", auth.auth_header())
+        .json(&json!({
+            "name": "Get User",
+            "http_method": "GET",
+            "endpoint": "/users/1",
+            "response_schema": schema
+        }))
+        .await;
+
+    response.assert_status(StatusCode::OK);
+
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["response_schema"], schema);
+}
+
+#[tokio::test]
+async fn test_create_api_invalid_response_schema() {
+    let app = TestApp::new().await;
+    let factory = Factory::new(&app.state);
+    let auth = factory.create_user().await;
+    let project = factory.create_project(auth.user_id).await;
+    let collection = factory.create_collection(project.id, auth.user_id).await;
+
+    let response = app
+        .server
+        .post(&format!("/api/collections/{}/apis", collection.id))
+        .add_header("Authorization", auth.auth_header())
+        .json(&json!({
+            "name": "Get User",
+            "http_method": "GET",
+            "endpoint": "/users/1",
+            "response_schema": { "type": "not-a-type" }
+        }))
+        .await;
+
+    response.assert_status(StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_create_api_request_content_type() {
+    let app = TestApp::new().await;
+    let factory = Factory::new(&app.state);
+    let auth = factory.create_user().await;
+    let project = factory.create_project(auth.user_id).await;
+    let collection = factory.create_collection(project.id, auth.user_id).await;
+
+    let create = |content_type: &str| {
+        app.server
+            .post(&format!("/api/collections/{}/apis", collection.id))
+            .add_header("Authorization", auth.auth_header())
+            .json(&json!({
+                "name": "Upload Avatar",
+                "http_method": "POST",
+                "endpoint": "/users/1/avatar",
+                "request_content_type": content_type
+            }))
+    };
+
+    // Short names are stored as their MIME type
+    let response = create("multipart").await;
+    response.assert_status(StatusCode::CREATED);
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["request_content_type"], "multipart/form-data");
+
+    create("text/csv")
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+}
+
 #[tokio::test]
 async fn test_create_api_invalid_collection() {
     let app = TestApp::new().await;
@@ -272,6 +358,89 @@ async fn test_update_api_partial() {
     assert_eq!(body["http_method"].as_str().unwrap(), api.http_method);
 }
 
+#[tokio::test]
+async fn test_update_api_auth_override() {
+    let app = TestApp::new().await;
+    let factory = Factory::new(&app.state);
+    let auth = factory.create_user().await;
+    let project = factory.create_project(auth.user_id).await;
+    let collection = factory.create_collection(project.id, auth.user_id).await;
+    let api = factory.create_api(collection.id, auth.user_id).await;
+    let url = format!("/api/apis/{}", api.id);
+
+    let response = app
+        .server
+        .put(&url)
+        .add_header("Authorization", auth.auth_header())
+        .json(&json!({
+            "auth": { "type": "basic", "username": "svc", "password": "pw" }
+        }))
+        .await;
+
+    response.assert_status(StatusCode::OK);
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["auth"]["username"], "svc");
+    assert_eq!(body["auth"]["password"], "********");
+
+    // Omitting `auth` leaves it alone; `null` goes back to the environment's
+    let response = app
+        .server
+        .put(&url)
+        .add_header("Authorization", auth.auth_header())
+        .json(&json!({ "name": "Renamed" }))
+        .await;
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["auth"]["type"], "basic");
+
+    let response = app
+        .server
+        .put(&url)
+        .add_header("Authorization", auth.auth_header())
+        .json(&json!({ "auth": null }))
+        .await;
+    let body: serde_json::Value = response.json();
+    assert!(body["auth"].is_null());
+}
+
+#[tokio::test]
+async fn test_update_api_redirect_policy() {
+    let app = TestApp::new().await;
+    let factory = Factory::new(&app.state);
+    let auth = factory.create_user().await;
+    let project = factory.create_project(auth.user_id).await;
+    let collection = factory.create_collection(project.id, auth.user_id).await;
+    let api = factory.create_api(collection.id, auth.user_id).await;
+    let url = format!("/api/apis/{}", api.id);
+
+    let response = app
+        .server
+        .put(&url)
+        .add_header("Authorization", auth.auth_header())
+        .json(&json!({ "redirect_policy": { "follow": false } }))
+        .await;
+
+    response.assert_status(StatusCode::OK);
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["redirect_policy"]["follow"], false);
+    assert_eq!(body["redirect_policy"]["max_hops"], 10);
+
+    app.server
+        .put(&url)
+        .add_header("Authorization", auth.auth_header())
+        .json(&json!({ "redirect_policy": { "max_hops": 50 } }))
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+
+    let response = app
+        .server
+        .put(&url)
+        .add_header("Authorization", auth.auth_header())
+        .json(&json!({ "redirect_policy": null }))
+        .await;
+    let body: serde_json::Value = response.json();
+    assert!(body["redirect_policy"].is_null());
+}
+
 #[tokio::test]
 async fn test_update_api_other_user() {
     let app = TestApp::new().await;
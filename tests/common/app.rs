@@ -23,6 +23,7 @@ pub fn test_config() -> Config {
         jwt_secret: "test-jwt-secret-that-is-at-least-32-characters-long".to_string(),
         jwt_expiration_hours: 24,
         refresh_token_expiration_days: 7,
+        secrets_key: "test-secrets-key".to_string(),
         host: "127.0.0.1".to_string(),
         port: 0,
     }
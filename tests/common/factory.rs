@@ -2,11 +2,12 @@ use uuid::Uuid;
 
 use serval_run::models::{
     Api, Collection, CreateApi, CreateCollection, CreateEnvironment, CreateProject, CreateReport,
-    CreateScenario, CreateUser, Environment, Project, Report, Scenario, User,
+    CreateResponse, CreateScenario, CreateUser, Environment, Project, Report, Response, Scenario,
+    User,
 };
 use serval_run::repositories::{
     ApiRepository, CollectionRepository, EnvironmentRepository, ProjectRepository,
-    ReportRepository, ScenarioRepository, UserRepository,
+    ReportRepository, ResponseRepository, ScenarioRepository, UserRepository,
 };
 use serval_run::services::AuthService;
 use serval_run::state::AppState;
@@ -84,6 +85,7 @@ impl<'a> Factory<'a> {
         let input = CreateProject {
             name: format!("Test Project {}", Uuid::new_v4()),
             description: Some("Test project description".to_string()),
+            variables: Default::default(),
         };
 
         ProjectRepository::create(&self.state.db, user_id, &input)
@@ -96,6 +98,7 @@ impl<'a> Factory<'a> {
         let input = CreateProject {
             name: name.to_string(),
             description: None,
+            variables: Default::default(),
         };
 
         ProjectRepository::create(&self.state.db, user_id, &input)
@@ -108,6 +111,7 @@ impl<'a> Factory<'a> {
         let input = CreateCollection {
             name: format!("Test Collection {}", Uuid::new_v4()),
             description: Some("Test collection description".to_string()),
+            variables: Default::default(),
         };
 
         CollectionRepository::create(&self.state.db, project_id, user_id, &input)
@@ -121,11 +125,21 @@ impl<'a> Factory<'a> {
         let input = CreateEnvironment {
             title: format!("test-{}", unique_id),
             domain_name: format!("https://api.test-{}.example.com", unique_id),
+            variables: Default::default(),
+            auth: None,
+            auth_bootstrap: None,
+            transport: None,
         };
 
-        EnvironmentRepository::create(&self.state.db, project_id, user_id, &input)
-            .await
-            .unwrap()
+        EnvironmentRepository::create(
+            &self.state.db,
+            &self.state.secret_cipher(),
+            project_id,
+            user_id,
+            &input,
+        )
+        .await
+        .unwrap()
     }
 
     /// Create a test API
@@ -136,11 +150,22 @@ impl<'a> Factory<'a> {
             endpoint: "/api/test".to_string(),
             severity: Some(1),
             description: Some("Test API description".to_string()),
+            response_schema: None,
+            sla_ms: None,
+            request_content_type: None,
+            auth: None,
+            redirect_policy: None,
         };
 
-        ApiRepository::create(&self.state.db, collection_id, user_id, &input)
-            .await
-            .unwrap()
+        ApiRepository::create(
+            &self.state.db,
+            &self.state.secret_cipher(),
+            collection_id,
+            user_id,
+            &input,
+        )
+        .await
+        .unwrap()
     }
 
     /// Create a test scenario
@@ -149,8 +174,13 @@ impl<'a> Factory<'a> {
             title: format!("Test Scenario {}", Uuid::new_v4()),
             description: Some("Test scenario description".to_string()),
             tags: Some(vec!["test".to_string()]),
+            kind: Default::default(),
+            background_steps: vec![],
             steps: vec![],
             examples: vec![],
+            response_schema: None,
+            body_match: None,
+            variables: Default::default(),
         };
 
         ScenarioRepository::create(&self.state.db, api_id, user_id, &input)
@@ -170,6 +200,7 @@ impl<'a> Factory<'a> {
             collection_id: None,
             report_level: 2, // project level
             report_type: Some("test".to_string()),
+            seed: None,
         };
 
         ReportRepository::create(&self.state.db, project_id, user_id, &input)
@@ -177,6 +208,39 @@ impl<'a> Factory<'a> {
             .unwrap()
     }
 
+    /// Create a stored test response in a report
+    pub async fn create_response(
+        &self,
+        report_id: Uuid,
+        api_id: Uuid,
+        scenario_id: Uuid,
+    ) -> Response {
+        let input = CreateResponse {
+            api_id,
+            scenario_id,
+            example_index: 0,
+            request_data: Some(serde_json::json!({
+                "method": "GET",
+                "url": "http://localhost/",
+                "headers": {},
+                "body": null
+            })),
+            response_data: Some(serde_json::json!({ "ok": true })),
+            response_headers: None,
+            response_status: 200,
+            pass: true,
+            error_message: None,
+            request_duration_ms: Some(12),
+            timing: None,
+            redirects: None,
+            workflow_steps: None,
+        };
+
+        ResponseRepository::create(&self.state.db, report_id, &input)
+            .await
+            .unwrap()
+    }
+
     /// Create a full test hierarchy: user -> project -> collection -> environment
     pub async fn create_hierarchy(&self) -> TestHierarchy {
         let auth = self.create_user().await;
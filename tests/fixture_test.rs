@@ -0,0 +1,136 @@
+mod common;
+
+use axum::body::Bytes;
+use axum::http::StatusCode;
+
+use common::{Factory, TestApp};
+
+#[tokio::test]
+async fn test_upload_and_list_fixtures() {
+    let app = TestApp::new().await;
+    let factory = Factory::new(&app.state);
+    let auth = factory.create_user().await;
+    let project = factory.create_project(auth.user_id).await;
+
+    let response = app
+        .server
+        .put(&format!("/api/projects/{}/fixtures/user.json", project.id))
+        .add_header("Authorization", auth.auth_header())
+        .content_type("application/json")
+        .bytes(Bytes::from_static(br#"{"name":"Ada"}"#))
+        .await;
+
+    response.assert_status(StatusCode::OK);
+
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["name"], "user.json");
+    assert_eq!(body["content_type"], "application/json");
+    assert_eq!(body["size"], 14);
+
+    let response = app
+        .server
+        .get(&format!("/api/projects/{}/fixtures", project.id))
+        .add_header("Authorization", auth.auth_header())
+        .await;
+
+    response.assert_status(StatusCode::OK);
+
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["total"], 1);
+    assert_eq!(body["data"][0]["name"], "user.json");
+}
+
+#[tokio::test]
+async fn test_upload_fixture_replaces_same_name() {
+    let app = TestApp::new().await;
+    let factory = Factory::new(&app.state);
+    let auth = factory.create_user().await;
+    let project = factory.create_project(auth.user_id).await;
+
+    for content in ["first", "second version"] {
+        app.server
+            .put(&format!("/api/projects/{}/fixtures/note.txt", project.id))
+            .add_header("Authorization", auth.auth_header())
+            .content_type("text/plain")
+            .bytes(Bytes::from(content))
+            .await
+            .assert_status(StatusCode::OK);
+    }
+
+    let response = app
+        .server
+        .get(&format!("/api/projects/{}/fixtures", project.id))
+        .add_header("Authorization", auth.auth_header())
+        .await;
+
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["total"], 1);
+    assert_eq!(body["data"][0]["size"], 14);
+}
+
+#[tokio::test]
+async fn test_upload_fixture_invalid_name() {
+    let app = TestApp::new().await;
+    let factory = Factory::new(&app.state);
+    let auth = factory.create_user().await;
+    let project = factory.create_project(auth.user_id).await;
+
+    let response = app
+        .server
+        .put(&format!("/api/projects/{}/fixtures/.hidden", project.id))
+        .add_header("Authorization", auth.auth_header())
+        .bytes(Bytes::from_static(b"data"))
+        .await;
+
+    response.assert_status(StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_upload_fixture_other_user_project() {
+    let app = TestApp::new().await;
+    let factory = Factory::new(&app.state);
+
+    let auth1 = factory.create_user().await;
+    let project = factory.create_project(auth1.user_id).await;
+
+    let auth2 = factory.create_user().await;
+    let response = app
+        .server
+        .put(&format!("/api/projects/{}/fixtures/user.json", project.id))
+        .add_header("Authorization", auth2.auth_header())
+        .bytes(Bytes::from_static(b"{}"))
+        .await;
+
+    response.assert_status(StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_delete_fixture() {
+    let app = TestApp::new().await;
+    let factory = Factory::new(&app.state);
+    let auth = factory.create_user().await;
+    let project = factory.create_project(auth.user_id).await;
+
+    app.server
+        .put(&format!("/api/projects/{}/fixtures/avatar.png", project.id))
+        .add_header("Authorization", auth.auth_header())
+        .content_type("image/png")
+        .bytes(Bytes::from_static(&[0x89, 0x50, 0x4e, 0x47]))
+        .await
+        .assert_status(StatusCode::OK);
+
+    let url = format!("/api/projects/{}/fixtures/avatar.png", project.id);
+    let response = app
+        .server
+        .delete(&url)
+        .add_header("Authorization", auth.auth_header())
+        .await;
+    response.assert_status(StatusCode::OK);
+
+    let response = app
+        .server
+        .delete(&url)
+        .add_header("Authorization", auth.auth_header())
+        .await;
+    response.assert_status(StatusCode::NOT_FOUND);
+}
@@ -247,6 +247,39 @@ async fn test_update_project() {
     assert_eq!(body["description"].as_str().unwrap(), "Updated description");
 }
 
+#[tokio::test]
+async fn test_update_project_variables() {
+    let app = TestApp::new().await;
+    let factory = Factory::new(&app.state);
+    let auth = factory.create_user().await;
+    let project = factory.create_project(auth.user_id).await;
+
+    let response = app
+        .server
+        .put(&format!("/api/projects/{}", project.id))
+        .add_header("Authorization", auth.auth_header())
+        .json(&json!({
+            "variables": { "base_user": "ada@example.com" }
+        }))
+        .await;
+
+    response.assert_status(StatusCode::OK);
+
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["variables"], json!({ "base_user": "ada@example.com" }));
+
+    let response = app
+        .server
+        .put(&format!("/api/projects/{}", project.id))
+        .add_header("Authorization", auth.auth_header())
+        .json(&json!({
+            "variables": { "base user": "ada@example.com" }
+        }))
+        .await;
+
+    response.assert_status(StatusCode::BAD_REQUEST);
+}
+
 #[tokio::test]
 async fn test_update_project_partial() {
     let app = TestApp::new().await;
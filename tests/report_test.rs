@@ -363,3 +363,98 @@ async fn test_report_unauthorized() {
 
     response.assert_status(StatusCode::UNAUTHORIZED);
 }
+
+#[tokio::test]
+async fn test_replay_response_not_found() {
+    let app = TestApp::new().await;
+    let factory = Factory::new(&app.state);
+    let auth = factory.create_user().await;
+
+    let response = app
+        .server
+        .post(&format!("/api/responses/{}/replay", Uuid::new_v4()))
+        .add_header("Authorization", auth.auth_header())
+        .json(&json!({}))
+        .await;
+
+    response.assert_status(StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_replay_response_missing_example() {
+    let app = TestApp::new().await;
+    let factory = Factory::new(&app.state);
+    let h = factory.create_hierarchy().await;
+    let api = factory.create_api(h.collection.id, h.auth.user_id).await;
+    let scenario = factory.create_scenario(api.id, h.auth.user_id).await;
+    let report = factory
+        .create_report(h.project.id, h.environment.id, h.auth.user_id)
+        .await;
+    let stored = factory
+        .create_response(report.id, api.id, scenario.id)
+        .await;
+
+    // The factory scenario has no examples left to replay
+    let response = app
+        .server
+        .post(&format!("/api/responses/{}/replay", stored.id))
+        .add_header("Authorization", h.auth.auth_header())
+        .json(&json!({}))
+        .await;
+
+    response.assert_status(StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_replay_response_environment_of_other_project() {
+    let app = TestApp::new().await;
+    let factory = Factory::new(&app.state);
+    let h = factory.create_hierarchy().await;
+    let api = factory.create_api(h.collection.id, h.auth.user_id).await;
+    let scenario = factory.create_scenario(api.id, h.auth.user_id).await;
+    let report = factory
+        .create_report(h.project.id, h.environment.id, h.auth.user_id)
+        .await;
+    let stored = factory
+        .create_response(report.id, api.id, scenario.id)
+        .await;
+
+    let other_project = factory.create_project(h.auth.user_id).await;
+    let other_env = factory
+        .create_environment(other_project.id, h.auth.user_id)
+        .await;
+
+    let response = app
+        .server
+        .post(&format!("/api/responses/{}/replay", stored.id))
+        .add_header("Authorization", h.auth.auth_header())
+        .json(&json!({ "environment_id": other_env.id }))
+        .await;
+
+    response.assert_status(StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_replay_response_other_user() {
+    let app = TestApp::new().await;
+    let factory = Factory::new(&app.state);
+    let h = factory.create_hierarchy().await;
+    let api = factory.create_api(h.collection.id, h.auth.user_id).await;
+    let scenario = factory.create_scenario(api.id, h.auth.user_id).await;
+    let report = factory
+        .create_report(h.project.id, h.environment.id, h.auth.user_id)
+        .await;
+    let stored = factory
+        .create_response(report.id, api.id, scenario.id)
+        .await;
+
+    let auth2 = factory.create_user().await;
+    let response = app
+        .server
+        .post(&format!("/api/responses/{}/replay", stored.id))
+        .add_header("Authorization", auth2.auth_header())
+        .json(&json!({}))
+        .await;
+
+    response.assert_status(StatusCode::NOT_FOUND);
+}
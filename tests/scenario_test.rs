@@ -293,6 +293,65 @@ async fn test_update_scenario() {
     assert_eq!(body["description"].as_str().unwrap(), "Updated description");
 }
 
+#[tokio::test]
+async fn test_update_scenario_response_schema() {
+    let app = TestApp::new().await;
+    let factory = Factory::new(&app.state);
+    let auth = factory.create_user().await;
+    let project = factory.create_project(auth.user_id).await;
+    let collection = factory.create_collection(project.id, auth.user_id).await;
+    let api = factory.create_api(collection.id, auth.user_id).await;
+    let scenario = factory.create_scenario(api.id, auth.user_id).await;
+
+    let schema = json!({
+        "type": "array",
+        "items": { "type": "object", "required": ["id", "name"] }
+    });
+
+    let response = app
+        .server
+        .put(&format!("/api/scenarios/{}", scenario.id))
+        .add_header("Authorization", auth.auth_header())
+        .json(&json!({ "response_schema": schema }))
+        .await;
+
+    response.assert_status(StatusCode::OK);
+
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["response_schema"], schema);
+
+    // Invalid schemas are rejected
+    let response = app
+        .server
+        .put(&format!("/api/scenarios/{}", scenario.id))
+        .add_header("Authorization", auth.auth_header())
+        .json(&json!({ "response_schema": { "minimum": "ten" } }))
+        .await;
+
+    response.assert_status(StatusCode::BAD_REQUEST);
+
+    // Other fields leave the schema alone, null clears it
+    let response = app
+        .server
+        .put(&format!("/api/scenarios/{}", scenario.id))
+        .add_header("Authorization", auth.auth_header())
+        .json(&json!({ "title": "Renamed" }))
+        .await;
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["response_schema"], schema);
+
+    let response = app
+        .server
+        .put(&format!("/api/scenarios/{}", scenario.id))
+        .add_header("Authorization", auth.auth_header())
+        .json(&json!({ "response_schema": null }))
+        .await;
+
+    response.assert_status(StatusCode::OK);
+    let body: serde_json::Value = response.json();
+    assert!(body["response_schema"].is_null());
+}
+
 #[tokio::test]
 async fn test_update_scenario_other_user() {
     let app = TestApp::new().await;
@@ -455,6 +514,291 @@ Feature: Login
     );
 }
 
+#[tokio::test]
+async fn test_create_from_gherkin_keeps_background() {
+    let app = TestApp::new().await;
+    let factory = Factory::new(&app.state);
+    let auth = factory.create_user().await;
+    let project = factory.create_project(auth.user_id).await;
+    let collection = factory.create_collection(project.id, auth.user_id).await;
+    let api = factory.create_api(collection.id, auth.user_id).await;
+
+    let gherkin_code = r#"
+Feature: Items
+
+  Background:
+    Given header "X-Tenant" is "acme"
+
+  Scenario: List items
+    Then the response status should be 200
+
+  Scenario: Count items
+    Then $.count should exist
+"#;
+
+    let response = app
+        .server
+        .post(&format!("/api/apis/{}/scenarios/from-gherkin", api.id))
+        .add_header("Authorization", auth.auth_header())
+        .json(&json!({
+            "gherkin_code": gherkin_code
+        }))
+        .await;
+
+    response.assert_status(StatusCode::OK);
+
+    let body: serde_json::Value = response.json();
+    let created = body["created"].as_array().unwrap();
+    assert_eq!(created.len(), 2);
+    for scenario in created {
+        assert_eq!(
+            scenario["background_steps"][0]["text"],
+            "header \"X-Tenant\" is \"acme\""
+        );
+        assert_eq!(scenario["steps"].as_array().unwrap().len(), 1);
+    }
+}
+
+#[tokio::test]
+async fn test_create_workflow_scenario() {
+    let app = TestApp::new().await;
+    let factory = Factory::new(&app.state);
+    let auth = factory.create_user().await;
+    let project = factory.create_project(auth.user_id).await;
+    let collection = factory.create_collection(project.id, auth.user_id).await;
+    let api = factory.create_api(collection.id, auth.user_id).await;
+    let other_collection = factory.create_collection(project.id, auth.user_id).await;
+    let other_api = factory.create_api(other_collection.id, auth.user_id).await;
+
+    let workflow = |target: &str| {
+        json!({
+            "title": "Order then check",
+            "kind": "workflow",
+            "steps": [
+                { "keyword": "When", "keyword_type": "Action", "text": format!("I call \"{}\"", api.name) },
+                { "keyword": "When", "keyword_type": "Action", "text": format!("I call \"{}\"", target) },
+                { "keyword": "Then", "keyword_type": "Outcome", "text": "the response status should be 200" }
+            ],
+            "examples": []
+        })
+    };
+
+    // Calls may target any API of the project
+    let response = app
+        .server
+        .post(&format!("/api/apis/{}/scenarios", api.id))
+        .add_header("Authorization", auth.auth_header())
+        .json(&workflow(&other_api.name))
+        .await;
+
+    response.assert_status(StatusCode::OK);
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["kind"], "workflow");
+
+    let response = app
+        .server
+        .post(&format!("/api/apis/{}/scenarios", api.id))
+        .add_header("Authorization", auth.auth_header())
+        .json(&workflow("No such API"))
+        .await;
+
+    response.assert_status(StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = response.json();
+    assert!(body["details"]
+        .as_str()
+        .unwrap()
+        .contains("Call 2: No API \"No such API\""));
+}
+
+#[tokio::test]
+async fn test_create_scenario_with_body_match() {
+    let app = TestApp::new().await;
+    let factory = Factory::new(&app.state);
+    let auth = factory.create_user().await;
+    let project = factory.create_project(auth.user_id).await;
+    let collection = factory.create_collection(project.id, auth.user_id).await;
+    let api = factory.create_api(collection.id, auth.user_id).await;
+
+    let scenario = |ignore_path: &str| {
+        json!({
+            "title": "Exact user",
+            "steps": [],
+            "examples": [{
+                "example": {},
+                "expected_response_body": { "id": "<any-uuid>", "name": "Ada" },
+                "expected_status_code": 200,
+                "body_match": { "mode": "ordered" }
+            }],
+            "body_match": { "mode": "exact", "ignore_paths": [ignore_path] }
+        })
+    };
+
+    let response = app
+        .server
+        .post(&format!("/api/apis/{}/scenarios", api.id))
+        .add_header("Authorization", auth.auth_header())
+        .json(&scenario("$.items[*].created_at"))
+        .await;
+
+    response.assert_status(StatusCode::OK);
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["body_match"]["mode"], "exact");
+    assert_eq!(body["examples"][0]["body_match"]["mode"], "ordered");
+
+    // A null body_match restores subset matching
+    let response = app
+        .server
+        .put(&format!("/api/scenarios/{}", body["id"].as_str().unwrap()))
+        .add_header("Authorization", auth.auth_header())
+        .json(&json!({ "body_match": null }))
+        .await;
+
+    response.assert_status(StatusCode::OK);
+    let body: serde_json::Value = response.json();
+    assert!(body["body_match"].is_null());
+
+    let response = app
+        .server
+        .post(&format!("/api/apis/{}/scenarios", api.id))
+        .add_header("Authorization", auth.auth_header())
+        .json(&scenario("$.items[x]"))
+        .await;
+
+    response.assert_status(StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = response.json();
+    assert!(body["details"]
+        .as_str()
+        .unwrap()
+        .contains("Invalid ignore path"));
+}
+
+#[tokio::test]
+async fn test_effective_variables() {
+    let app = TestApp::new().await;
+    let factory = Factory::new(&app.state);
+    let auth = factory.create_user().await;
+    let project = factory.create_project(auth.user_id).await;
+    let collection = factory.create_collection(project.id, auth.user_id).await;
+    let api = factory.create_api(collection.id, auth.user_id).await;
+    let scenario = factory.create_scenario(api.id, auth.user_id).await;
+    let environment = factory.create_environment(project.id, auth.user_id).await;
+
+    for (path, variables) in [
+        (
+            format!("/api/projects/{}", project.id),
+            json!({ "base_user": "p@example.com", "region": "eu" }),
+        ),
+        (
+            format!("/api/collections/{}", collection.id),
+            json!({ "base_user": "c@example.com" }),
+        ),
+        (
+            format!("/api/environments/{}", environment.id),
+            json!({
+                "base_user": { "value": "e@example.com" },
+                "api_key": { "value": "k-123", "secret": true }
+            }),
+        ),
+    ] {
+        app.server
+            .put(&path)
+            .add_header("Authorization", auth.auth_header())
+            .json(&json!({ "variables": variables }))
+            .await
+            .assert_status(StatusCode::OK);
+    }
+
+    app.server
+        .put(&format!("/api/scenarios/{}", scenario.id))
+        .add_header("Authorization", auth.auth_header())
+        .json(&json!({
+            "variables": { "base_user": "s@example.com" },
+            "examples": [{
+                "example": { "base_user": "x@example.com" },
+                "expected_response_body": null,
+                "expected_status_code": 200
+            }]
+        }))
+        .await
+        .assert_status(StatusCode::OK);
+
+    let response = app
+        .server
+        .get(&format!(
+            "/api/scenarios/{}/variables?environment_id={}",
+            scenario.id, environment.id
+        ))
+        .add_header("Authorization", auth.auth_header())
+        .await;
+
+    response.assert_status(StatusCode::OK);
+
+    let body: serde_json::Value = response.json();
+    let variables = &body["variables"];
+    assert_eq!(variables["base_user"]["value"], "s@example.com");
+    assert_eq!(variables["base_user"]["scope"], "scenario");
+    assert_eq!(
+        variables["base_user"]["overridden"],
+        json!(["project", "collection", "environment"])
+    );
+    assert_eq!(variables["region"]["scope"], "project");
+    assert_eq!(variables["api_key"]["value"], "********");
+    assert_eq!(variables["api_key"]["secret"], true);
+
+    // The example row wins over every scope
+    let response = app
+        .server
+        .get(&format!(
+            "/api/scenarios/{}/variables?environment_id={}&example_index=0",
+            scenario.id, environment.id
+        ))
+        .add_header("Authorization", auth.auth_header())
+        .await;
+
+    response.assert_status(StatusCode::OK);
+
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["variables"]["base_user"]["value"], "x@example.com");
+    assert_eq!(body["variables"]["base_user"]["scope"], "example");
+
+    let response = app
+        .server
+        .get(&format!(
+            "/api/scenarios/{}/variables?environment_id={}&example_index=5",
+            scenario.id, environment.id
+        ))
+        .add_header("Authorization", auth.auth_header())
+        .await;
+
+    response.assert_status(StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_effective_variables_environment_other_project() {
+    let app = TestApp::new().await;
+    let factory = Factory::new(&app.state);
+    let auth = factory.create_user().await;
+    let project = factory.create_project(auth.user_id).await;
+    let other_project = factory.create_project(auth.user_id).await;
+    let collection = factory.create_collection(project.id, auth.user_id).await;
+    let api = factory.create_api(collection.id, auth.user_id).await;
+    let scenario = factory.create_scenario(api.id, auth.user_id).await;
+    let environment = factory
+        .create_environment(other_project.id, auth.user_id)
+        .await;
+
+    let response = app
+        .server
+        .get(&format!(
+            "/api/scenarios/{}/variables?environment_id={}",
+            scenario.id, environment.id
+        ))
+        .add_header("Authorization", auth.auth_header())
+        .await;
+
+    response.assert_status(StatusCode::BAD_REQUEST);
+}
+
 #[tokio::test]
 async fn test_scenario_unauthorized() {
     let app = TestApp::new().await;
@@ -0,0 +1,62 @@
+mod common;
+
+use axum::http::StatusCode;
+
+use common::{Factory, TestApp};
+
+#[tokio::test]
+async fn test_list_steps() {
+    let app = TestApp::new().await;
+    let factory = Factory::new(&app.state);
+    let auth = factory.create_user().await;
+
+    let response = app
+        .server
+        .get("/api/steps")
+        .add_header("Authorization", auth.auth_header())
+        .await;
+
+    response.assert_status(StatusCode::OK);
+
+    let body: serde_json::Value = response.json();
+    let steps = body["data"].as_array().unwrap();
+    let status = steps
+        .iter()
+        .find(|s| s["pattern"] == "(the )(response )status (code )should be {int}")
+        .expect("status step is listed");
+    assert_eq!(status["kind"], "outcome");
+    assert_eq!(status["syntax"], "cucumber_expression");
+    assert_eq!(status["parameters"], serde_json::json!(["int"]));
+
+    let parameter_types = body["parameter_types"].as_array().unwrap();
+    assert!(parameter_types.iter().any(|t| t["name"] == "jsonpath"));
+}
+
+#[tokio::test]
+async fn test_list_steps_by_kind() {
+    let app = TestApp::new().await;
+    let factory = Factory::new(&app.state);
+    let auth = factory.create_user().await;
+
+    let response = app
+        .server
+        .get("/api/steps?kind=request")
+        .add_header("Authorization", auth.auth_header())
+        .await;
+
+    response.assert_status(StatusCode::OK);
+
+    let body: serde_json::Value = response.json();
+    let steps = body["data"].as_array().unwrap();
+    assert!(!steps.is_empty());
+    assert!(steps.iter().all(|s| s["kind"] == "request"));
+}
+
+#[tokio::test]
+async fn test_list_steps_unauthorized() {
+    let app = TestApp::new().await;
+
+    let response = app.server.get("/api/steps").await;
+
+    response.assert_status(StatusCode::UNAUTHORIZED);
+}
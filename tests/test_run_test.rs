@@ -31,6 +31,28 @@ async fn test_run_scenario_async() {
     assert_eq!(body["status"].as_str().unwrap(), "pending");
 }
 
+#[tokio::test]
+async fn test_run_scenario_invalid_seed() {
+    let app = TestApp::new().await;
+    let factory = Factory::new(&app.state);
+    let h = factory.create_hierarchy().await;
+    let api = factory.create_api(h.collection.id, h.auth.user_id).await;
+    let scenario = factory.create_scenario(api.id, h.auth.user_id).await;
+
+    let response = app
+        .server
+        .post(&format!("/api/scenarios/{}/run", scenario.id))
+        .add_header("Authorization", h.auth.auth_header())
+        .json(&json!({
+            "environment_id": h.environment.id,
+            "async_execution": true,
+            "seed": 1_u64 << 53
+        }))
+        .await;
+
+    response.assert_status(StatusCode::BAD_REQUEST);
+}
+
 #[tokio::test]
 async fn test_run_scenario_not_found() {
     let app = TestApp::new().await;